@@ -380,7 +380,7 @@ fn search_command(command: SearchCommand, database: Database) -> Result<(), Box<
                     });
                 }
 
-                let documents = builder.query(ref_reader, &query, 0..command.number_results)?;
+                let documents = builder.query(ref_reader, &query, 0..command.number_results)?.documents;
 
                 let mut retrieve_duration = Duration::default();
 
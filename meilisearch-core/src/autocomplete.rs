@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+
+use fst::{IntoStreamer, Streamer};
+use meilisearch_tokenizer::split_query_string;
+use sdset::duo::Intersection;
+use sdset::{SetBuf, SetOperation};
+
+use crate::automaton::build_prefix_dfa;
+use crate::database::MainT;
+use crate::tokenizers::index_tokenizer;
+use crate::{store, DocumentId, MResult};
+
+/// The last query words shorter than this number of characters are never
+/// completed, too many words of the index start with them.
+const MIN_PREFIX_LEN: usize = 2;
+
+/// The maximum number of index words visited to complete the last query word,
+/// the postings list of each of them is read and its documents are filtered.
+const MAX_VISITED_WORDS: usize = 1000;
+
+/// A query in which the last word was completed by a word of the index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Completion {
+    pub completion: String,
+    /// The number of documents that contain all the words of the completion.
+    pub nb_documents: usize,
+}
+
+/// Completes the last word of the query with the words of the index that start
+/// with it, the completions are sorted by the number of documents containing them.
+///
+/// The other words of the query must be complete, only the documents containing
+/// all of them are counted, the completions are therefore short phrases. Only the
+/// first words of the index, in lexicographic order, are considered as completions.
+pub fn autocomplete<F>(
+    reader: &heed::RoTxn<MainT>,
+    main_store: store::Main,
+    postings_lists_store: store::PostingsLists,
+    query: &str,
+    filter: Option<F>,
+    limit: usize,
+) -> MResult<Vec<Completion>>
+where
+    F: Fn(DocumentId) -> bool,
+{
+    let words_set = match main_store.words_fst(reader)? {
+        Some(words) => words,
+        None => return Ok(Vec::new()),
+    };
+
+    let tokenizer = index_tokenizer(reader, main_store)?;
+    let mut words: Vec<_> = split_query_string(&*tokenizer, query).map(str::to_lowercase).collect();
+    let prefix = match words.pop() {
+        Some(prefix) => prefix,
+        None => return Ok(Vec::new()),
+    };
+
+    if prefix.chars().count() < MIN_PREFIX_LEN {
+        return Ok(Vec::new());
+    }
+
+    // the documents that contain all the complete words of the query
+    let mut context: Option<SetBuf<DocumentId>> = None;
+    for word in &words {
+        let docids = match postings_lists_store.postings_list(reader, word.as_bytes())? {
+            Some(postings) => postings.docids.into_owned(),
+            None => return Ok(Vec::new()),
+        };
+
+        context = Some(match context {
+            Some(context) => Intersection::new(&context, &docids).into_set_buf(),
+            None => docids,
+        });
+    }
+
+    let mut filter_map = HashMap::new();
+    let mut completions = Vec::new();
+
+    let dfa = build_prefix_dfa(&prefix, 0);
+    let mut stream = words_set.search(&dfa).into_stream();
+    let mut visited = 0;
+    while let Some(input) = stream.next() {
+        visited += 1;
+        if visited > MAX_VISITED_WORDS { break }
+
+        let postings = match postings_lists_store.postings_list(reader, input)? {
+            Some(postings) => postings,
+            None => continue,
+        };
+
+        let docids = match &context {
+            Some(context) => Intersection::new(context, &postings.docids).into_set_buf(),
+            None => postings.docids.into_owned(),
+        };
+
+        let nb_documents = match &filter {
+            Some(filter) => docids
+                .iter()
+                .filter(|id| *filter_map.entry(**id).or_insert_with(|| (filter)(**id)))
+                .count(),
+            None => docids.len(),
+        };
+
+        if nb_documents != 0 {
+            if let Ok(word) = std::str::from_utf8(input) {
+                completions.push((word.to_owned(), nb_documents));
+            }
+        }
+    }
+
+    completions.sort_unstable_by(|(wa, ca), (wb, cb)| cb.cmp(ca).then_with(|| wa.cmp(wb)));
+    completions.truncate(limit);
+
+    let completions = completions
+        .into_iter()
+        .map(|(word, nb_documents)| {
+            let completion = if words.is_empty() {
+                word
+            } else {
+                format!("{} {}", words.join(" "), word)
+            };
+            Completion { completion, nb_documents }
+        })
+        .collect();
+
+    Ok(completions)
+}
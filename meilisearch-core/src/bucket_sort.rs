@@ -1,33 +1,75 @@
 use std::borrow::Cow;
-use std::collections::HashMap;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
 use std::mem;
 use std::ops::Deref;
 use std::ops::Range;
 use std::rc::Rc;
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::time::Instant;
+use std::sync::atomic::{self, AtomicUsize};
+use std::time::{Duration, Instant};
 use std::fmt;
 
 use compact_arena::{SmallArena, Idx32, mk_arena};
 use log::debug;
+use meilisearch_schema::SchemaAttr;
 use meilisearch_types::DocIndex;
-use sdset::{Set, SetBuf, exponential_search};
+use sdset::{Set, SetBuf, SetOperation, exponential_search};
 use slice_group_by::{GroupBy, GroupByMut};
 
 use crate::criterion::{Criteria, Context, ContextMut};
+use crate::search_cursor::SearchCursor;
 use crate::distinct_map::{BufferedDistinctMap, DistinctMap};
+use crate::facets::{facets_distribution, FacetsDistribution};
 use crate::raw_document::RawDocument;
 use crate::{database::MainT, reordered_attrs::ReorderedAttrs};
-use crate::{store, Document, DocumentId, MResult};
-use crate::query_tree::{create_query_tree, traverse_query_tree};
+use crate::{store, Document, DocumentId, Error, MatchingStrategy, MResult, Number, PrefixSearch, Stemmer, TypoTolerance, WordOrigin};
+use crate::query_tree::{create_query_tree, is_placeholder_query, traverse_query_tree};
 use crate::query_tree::{Operation, QueryResult, QueryKind, QueryId, PostingsKey};
 use crate::query_tree::Context as QTContext;
+use crate::tokenizers::index_tokenizer;
+
+#[derive(Debug)]
+pub struct SortResult {
+    pub documents: Vec<Document>,
+    pub facets: Option<FacetsDistribution>,
+    /// The number of candidates that were removed by the distinct rule
+    /// because they share the distinct key of a returned document, only the
+    /// candidates checked before the fetch timeout are counted.
+    pub collapsed: HashMap<DocumentId, usize>,
+    /// The number of documents accepted by the filter and the distinct rule.
+    pub nb_hits: usize,
+    /// Whether `nb_hits` is exact or was estimated because the fetch timeout was reached
+    /// before every candidate was checked, it is always exact without a filter nor a
+    /// distinct rule as the candidates are counted without being checked.
+    pub exhaustive_nb_hits: bool,
+    /// The position of the last returned document, used to ask for the following ones.
+    pub cursor: Option<SearchCursor>,
+}
+
+impl Default for SortResult {
+    fn default() -> SortResult {
+        SortResult {
+            documents: Vec::new(),
+            facets: None,
+            collapsed: HashMap::new(),
+            nb_hits: 0,
+            exhaustive_nb_hits: true,
+            cursor: None,
+        }
+    }
+}
 
 pub fn bucket_sort<'c, FI>(
     reader: &heed::RoTxn<MainT>,
     query: &str,
     range: Range<usize>,
+    search_after: Option<&SearchCursor>,
     filter: Option<FI>,
+    facets: Option<&[SchemaAttr]>,
+    timeout: Option<Duration>,
+    ranking_info: bool,
+    matching_strategy: MatchingStrategy,
+    prefix_search: Option<PrefixSearch>,
     criteria: Criteria<'c>,
     searchable_attrs: Option<ReorderedAttrs>,
     main_store: store::Main,
@@ -36,22 +78,32 @@ pub fn bucket_sort<'c, FI>(
     synonyms_store: store::Synonyms,
     prefix_documents_cache_store: store::PrefixDocumentsCache,
     prefix_postings_lists_cache_store: store::PrefixPostingsListsCache,
-) -> MResult<Vec<Document>>
+    facets_store: store::Facets,
+) -> MResult<SortResult>
 where
     FI: Fn(DocumentId) -> bool,
 {
-    // We delegate the filter work to the distinct query builder,
-    // specifying a distinct rule that has no effect.
-    if filter.is_some() {
+    let before_bucket_sort = Instant::now();
+    let tokenizer = index_tokenizer(reader, main_store)?;
+
+    // We delegate the filter and the placeholder query work to the distinct
+    // query builder, specifying a distinct rule that has no effect.
+    if filter.is_some() || is_placeholder_query(&*tokenizer, query) {
         let distinct = |_| None;
         let distinct_size = 1;
         return bucket_sort_with_distinct(
             reader,
             query,
             range,
+            search_after,
             filter,
             distinct,
             distinct_size,
+            facets,
+            timeout,
+            ranking_info,
+            matching_strategy,
+            prefix_search,
             criteria,
             searchable_attrs,
             main_store,
@@ -60,22 +112,38 @@ where
             synonyms_store,
             prefix_documents_cache_store,
             prefix_postings_lists_cache_store,
+            facets_store,
         );
     }
 
     let words_set = match unsafe { main_store.static_words_fst(reader)? } {
         Some(words) => words,
-        None => return Ok(Vec::new()),
+        None => return Ok(SortResult::default()),
     };
 
+    let (typo_tolerance, exact_attributes) = typo_tolerance(reader, main_store)?;
+    let prefix_search = match prefix_search {
+        Some(prefix_search) => prefix_search,
+        None => main_store.prefix_search(reader)?.unwrap_or_default(),
+    };
+    let stemmer = main_store.stemming_language(reader)?.map(Stemmer::new);
+    let stop_words = main_store.stop_words_fst(reader)?.unwrap_or_default();
+
     let context = QTContext {
         words_set,
         synonyms: synonyms_store,
         postings_lists: postings_lists_store,
         prefix_postings_lists: prefix_postings_lists_cache_store,
+        typo_tolerance,
+        exact_attributes,
+        matching_strategy,
+        prefix_search,
+        stemmer,
+        tokenizer,
+        stop_words,
     };
 
-    let (operation, mapping) = create_query_tree(reader, &context, query)?;
+    let (operation, mapping, origins) = create_query_tree(reader, &context, query)?;
     debug!("operation:\n{:?}", operation);
     debug!("mapping:\n{:?}", mapping);
 
@@ -83,6 +151,8 @@ where
         match operation {
             Operation::And(ops) => ops.iter().for_each(|op| recurs_operation(map, op)),
             Operation::Or(ops) => ops.iter().for_each(|op| recurs_operation(map, op)),
+            Operation::Not(_) => (),
+            Operation::Fallback(ops) => ops.iter().for_each(|op| recurs_operation(map, op)),
             Operation::Query(query) => { map.insert(query.id, &query.kind); },
         }
     }
@@ -94,13 +164,16 @@ where
     debug!("found {} documents", docids.len());
     debug!("number of postings {:?}", queries.len());
 
+    let facets = match facets {
+        Some(attributes) => Some(facets_distribution(reader, facets_store, attributes, &docids)?),
+        None => None,
+    };
+
     let before = Instant::now();
     mk_arena!(arena);
-    let mut bare_matches = cleanup_bare_matches(&mut arena, &docids, queries);
+    let mut bare_matches = cleanup_bare_matches(&mut arena, &docids, queries, &origins);
     debug!("matches cleaned in {:.02?}", before.elapsed());
 
-    let before_bucket_sort = Instant::now();
-
     let before_raw_documents_building = Instant::now();
     let mut raw_documents = Vec::new();
     for bare_matches in bare_matches.linear_group_by_key_mut(|sm| sm.document_id) {
@@ -112,6 +185,16 @@ where
         before_raw_documents_building.elapsed(),
     );
 
+    if let Some(cursor) = search_after {
+        let ctx = ContextMut {
+            reader,
+            postings_lists: &mut arena,
+            query_mapping: &mapping,
+            documents_fields_counts_store,
+        };
+        retain_after_cursor(ctx, &criteria, cursor, &mut raw_documents)?;
+    }
+
     let before_criterion_loop = Instant::now();
     let proximity_count = AtomicUsize::new(0);
 
@@ -159,24 +242,53 @@ where
     }
 
     debug!("criterion loop took {:.02?}", before_criterion_loop.elapsed());
-    debug!("proximity evaluation called {} times", proximity_count.load(Ordering::Relaxed));
+    debug!("proximity evaluation called {} times", proximity_count.load(atomic::Ordering::Relaxed));
+
+    let ctx = Context {
+        postings_lists: &arena,
+        query_mapping: &mapping,
+    };
+
+    let cursor = raw_documents.iter().skip(range.start).take(range.len()).last().map(|rd| {
+        SearchCursor { document_id: rd.id, values: cursor_values(&criteria, &ctx, rd) }
+    });
 
     let iter = raw_documents.into_iter().skip(range.start).take(range.len());
-    let iter = iter.map(|rd| Document::from_raw(rd, &queries_kinds, &arena, searchable_attrs.as_ref()));
+    let iter = iter.map(|rd| {
+        let info = if ranking_info { Some(criteria_values(&criteria, &ctx, &rd)) } else { None };
+        let mut document = Document::from_raw(rd, &queries_kinds, &arena, searchable_attrs.as_ref());
+        document.ranking_info = info;
+        document
+    });
     let documents = iter.collect();
 
     debug!("bucket sort took {:.02?}", before_bucket_sort.elapsed());
 
-    Ok(documents)
+    Ok(SortResult {
+        documents,
+        facets,
+        collapsed: HashMap::new(),
+        // without filter nor distinct rule all the candidates are hits, they are
+        // counted without checking them one by one and the timeout is not needed
+        nb_hits: docids.len(),
+        exhaustive_nb_hits: true,
+        cursor,
+    })
 }
 
 pub fn bucket_sort_with_distinct<'c, FI, FD>(
     reader: &heed::RoTxn<MainT>,
     query: &str,
     range: Range<usize>,
+    search_after: Option<&SearchCursor>,
     filter: Option<FI>,
     distinct: FD,
     distinct_size: usize,
+    facets: Option<&[SchemaAttr]>,
+    timeout: Option<Duration>,
+    ranking_info: bool,
+    matching_strategy: MatchingStrategy,
+    prefix_search: Option<PrefixSearch>,
     criteria: Criteria<'c>,
     searchable_attrs: Option<ReorderedAttrs>,
     main_store: store::Main,
@@ -185,24 +297,52 @@ pub fn bucket_sort_with_distinct<'c, FI, FD>(
     synonyms_store: store::Synonyms,
     _prefix_documents_cache_store: store::PrefixDocumentsCache,
     prefix_postings_lists_cache_store: store::PrefixPostingsListsCache,
-) -> MResult<Vec<Document>>
+    facets_store: store::Facets,
+) -> MResult<SortResult>
 where
     FI: Fn(DocumentId) -> bool,
     FD: Fn(DocumentId) -> Option<u64>,
 {
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+
     let words_set = match unsafe { main_store.static_words_fst(reader)? } {
         Some(words) => words,
-        None => return Ok(Vec::new()),
+        None => return Ok(SortResult::default()),
+    };
+
+    let (typo_tolerance, exact_attributes) = typo_tolerance(reader, main_store)?;
+    let prefix_search = match prefix_search {
+        Some(prefix_search) => prefix_search,
+        None => main_store.prefix_search(reader)?.unwrap_or_default(),
     };
+    let stemmer = main_store.stemming_language(reader)?.map(Stemmer::new);
+    let stop_words = main_store.stop_words_fst(reader)?.unwrap_or_default();
+    let tokenizer = index_tokenizer(reader, main_store)?;
+
+    // a query without words to search for is a placeholder query, it returns all the
+    // documents, but the excluded ones, sorted by the criteria that does not depend
+    // on the query words
+    let placeholder = is_placeholder_query(&*tokenizer, query);
 
     let context = QTContext {
         words_set,
         synonyms: synonyms_store,
         postings_lists: postings_lists_store,
         prefix_postings_lists: prefix_postings_lists_cache_store,
+        typo_tolerance,
+        exact_attributes,
+        matching_strategy,
+        prefix_search,
+        stemmer,
+        tokenizer,
+        stop_words,
     };
 
-    let (operation, mapping) = create_query_tree(reader, &context, query)?;
+    let (operation, mapping, origins) = if query.trim().is_empty() {
+        (Operation::Or(Vec::new()), HashMap::new(), HashMap::new())
+    } else {
+        create_query_tree(reader, &context, query)?
+    };
     debug!("operation:\n{:?}", operation);
     debug!("mapping:\n{:?}", mapping);
 
@@ -210,6 +350,8 @@ where
         match operation {
             Operation::And(ops) => ops.iter().for_each(|op| recurs_operation(map, op)),
             Operation::Or(ops) => ops.iter().for_each(|op| recurs_operation(map, op)),
+            Operation::Not(_) => (),
+            Operation::Fallback(ops) => ops.iter().for_each(|op| recurs_operation(map, op)),
             Operation::Query(query) => { map.insert(query.id, &query.kind); },
         }
     }
@@ -217,30 +359,88 @@ where
     let mut queries_kinds = HashMap::new();
     recurs_operation(&mut queries_kinds, &operation);
 
-    let QueryResult { docids, queries } = traverse_query_tree(reader, &context, &operation)?;
+    let QueryResult { docids, queries } = if placeholder {
+        let mut docids = Vec::new();
+        for result in documents_fields_counts_store.documents_ids(reader)? {
+            docids.push(result?);
+        }
+        let mut docids = SetBuf::new_unchecked(docids);
+
+        // the documents that contain the negated words are removed
+        if let Operation::Not(excluded) = &operation {
+            let excluded = traverse_query_tree(reader, &context, excluded)?.docids;
+            docids = sdset::duo::Difference::new(&docids, &excluded).into_set_buf();
+        }
+
+        QueryResult { docids: Cow::Owned(docids), queries: HashMap::new() }
+    } else {
+        traverse_query_tree(reader, &context, &operation)?
+    };
     debug!("found {} documents", docids.len());
     debug!("number of postings {:?}", queries.len());
 
     let before = Instant::now();
     mk_arena!(arena);
-    let mut bare_matches = cleanup_bare_matches(&mut arena, &docids, queries);
+    let mut bare_matches = cleanup_bare_matches(&mut arena, &docids, queries, &origins);
     debug!("matches cleaned in {:.02?}", before.elapsed());
 
     let before_raw_documents_building = Instant::now();
     let mut raw_documents = Vec::new();
-    for bare_matches in bare_matches.linear_group_by_key_mut(|sm| sm.document_id) {
-        let raw_document = RawDocument::new(bare_matches, &mut arena, searchable_attrs.as_ref());
-        raw_documents.push(raw_document);
+    if placeholder {
+        raw_documents.extend(docids.iter().cloned().map(RawDocument::placeholder));
+    } else {
+        for bare_matches in bare_matches.linear_group_by_key_mut(|sm| sm.document_id) {
+            let raw_document = RawDocument::new(bare_matches, &mut arena, searchable_attrs.as_ref());
+            raw_documents.push(raw_document);
+        }
     }
     debug!("creating {} candidates documents took {:.02?}",
         raw_documents.len(),
         before_raw_documents_building.elapsed(),
     );
 
+    // the hits are counted on all the candidates, not only the ones after the cursor
+    let candidates_ids: Vec<_> = raw_documents.iter().map(|rd| rd.id).collect();
+
+    // the candidates sorted before the cursor are removed before the distinct rule
+    // is applied, the query builder rejects the cursors along with a distinct rule
+    if let Some(cursor) = search_after {
+        let ctx = ContextMut {
+            reader,
+            postings_lists: &mut arena,
+            query_mapping: &mapping,
+            documents_fields_counts_store,
+        };
+        retain_after_cursor(ctx, &criteria, cursor, &mut raw_documents)?;
+    }
+
     let mut groups = vec![raw_documents.as_mut_slice()];
     let mut key_cache = HashMap::new();
 
     let mut filter_map = HashMap::new();
+
+    // the facets distribution is computed on all the documents accepted by the filter,
+    // we keep the filter results to avoid calling it again while sorting the documents
+    let facets = match facets {
+        Some(attributes) => {
+            let docids = match &filter {
+                Some(filter) => {
+                    let mut accepted = Vec::with_capacity(docids.len());
+                    for id in docids.iter() {
+                        let filter_accepted = (filter)(*id);
+                        filter_map.insert(*id, filter_accepted);
+                        if filter_accepted {
+                            accepted.push(*id);
+                        }
+                    }
+                    Cow::Owned(SetBuf::new_unchecked(accepted))
+                }
+                None => Cow::Borrowed(&*docids),
+            };
+            Some(facets_distribution(reader, facets_store, attributes, &docids)?)
+        }
+        None => None,
+    };
     // these two variables informs on the current distinct map and
     // on the raw offset of the start of the group where the
     // range.start bound is located according to the distinct function
@@ -331,22 +531,36 @@ where
     // automatons we save that as the next valid result
     let mut seen = BufferedDistinctMap::new(&mut distinct_map);
 
+    let mut returned_keys = HashMap::new();
+
     let mut documents = Vec::with_capacity(range.len());
+    let mut cursor = None;
     for raw_document in raw_documents.into_iter().skip(distinct_raw_offset) {
         let filter_accepted = match &filter {
-            Some(_) => filter_map.remove(&raw_document.id).unwrap(),
+            Some(_) => filter_map[&raw_document.id],
             None => true,
         };
 
         if filter_accepted {
-            let key = key_cache.remove(&raw_document.id).unwrap();
-            let distinct_accepted = match key {
+            let key = key_cache[&raw_document.id].clone();
+            let distinct_accepted = match key.clone() {
                 Some(key) => seen.register(key),
                 None => seen.register_without_key(),
             };
 
             if distinct_accepted && seen.len() > range.start {
-                documents.push(Document::from_raw(raw_document, &queries_kinds, &arena, searchable_attrs.as_ref()));
+                if let Some(key) = key {
+                    returned_keys.insert(raw_document.id, key);
+                }
+
+                let ctx = Context { postings_lists: &arena, query_mapping: &mapping };
+                let info = if ranking_info { Some(criteria_values(&criteria, &ctx, &raw_document)) } else { None };
+                let values = cursor_values(&criteria, &ctx, &raw_document);
+                cursor = Some(SearchCursor { document_id: raw_document.id, values });
+
+                let mut document = Document::from_raw(raw_document, &queries_kinds, &arena, searchable_attrs.as_ref());
+                document.ranking_info = info;
+                documents.push(document);
                 if documents.len() == range.len() {
                     break;
                 }
@@ -354,19 +568,188 @@ where
         }
     }
 
-    Ok(documents)
+    // the hits are counted from the candidates already checked while sorting, the others
+    // are checked until the fetch timeout is reached, the count is then estimated from
+    // the proportion of hits among the checked candidates. The documents sharing the
+    // distinct key of a returned document are counted along the way.
+    let keys: HashSet<_> = returned_keys.values().cloned().collect();
+    let mut keys_counts = HashMap::new();
+    let mut hits_map = DistinctMap::new(distinct_size);
+    let mut hits = BufferedDistinctMap::new(&mut hits_map);
+    let mut checked = 0;
+    let mut exhaustive_nb_hits = true;
+
+    for &id in &candidates_ids {
+        let already_checked = key_cache.contains_key(&id) || filter_map.get(&id) == Some(&false);
+        if !already_checked && deadline.map_or(false, |deadline| Instant::now() > deadline) {
+            exhaustive_nb_hits = false;
+            continue;
+        }
+
+        checked += 1;
+
+        let filter_accepted = match &filter {
+            Some(filter) => *filter_map.entry(id).or_insert_with(|| (filter)(id)),
+            None => true,
+        };
+
+        if filter_accepted {
+            let key = key_cache.entry(id).or_insert_with(|| (distinct)(id).map(Rc::new));
+            match key.clone() {
+                Some(key) => {
+                    if keys.contains(&key) {
+                        *keys_counts.entry(key.clone()).or_insert(0) += 1;
+                    }
+                    hits.register(key)
+                }
+                None => hits.register_without_key(),
+            };
+        }
+    }
+
+    let nb_hits = if exhaustive_nb_hits {
+        hits.len()
+    } else if checked == 0 {
+        candidates_ids.len()
+    } else {
+        hits.len() * candidates_ids.len() / checked
+    };
+
+    let mut returned_counts = HashMap::new();
+    for key in returned_keys.values() {
+        *returned_counts.entry(key.clone()).or_insert(0) += 1;
+    }
+
+    let mut collapsed = HashMap::new();
+    for (id, key) in returned_keys {
+        let count = keys_counts.get(&key).cloned().unwrap_or(0);
+        let returned = returned_counts.get(&key).cloned().unwrap_or(0);
+        collapsed.insert(id, count - returned);
+    }
+
+    Ok(SortResult { documents, facets, collapsed, nb_hits, exhaustive_nb_hits, cursor })
+}
+
+/// Removes the candidates that are sorted before the cursor document along with
+/// the cursor document itself by comparing their values to the ones of the cursor,
+/// a criterion is only evaluated on the candidates that the previous criteria could
+/// not separate from the cursor document.
+///
+/// The criteria must end with the `DocumentId` one for no candidate to be equal to
+/// the cursor document, the cursor is rejected if it was built from other criteria.
+fn retain_after_cursor<'tag, 'txn>(
+    ctx: ContextMut<'_, '_, 'tag, 'txn, '_>,
+    criteria: &Criteria,
+    cursor: &SearchCursor,
+    raw_documents: &mut Vec<RawDocument<'_, 'tag>>,
+) -> MResult<()>
+{
+    let ContextMut { reader, postings_lists, query_mapping, documents_fields_counts_store } = ctx;
+
+    let same_criteria = criteria.as_ref().len() == cursor.values.len()
+        && criteria.as_ref().iter().zip(&cursor.values).all(|(c, (name, _))| c.name() == name);
+    if !same_criteria {
+        return Err(Error::SearchCursorCriteriaDiffer);
+    }
+
+    // the candidates that are equal to the cursor document for now are kept first
+    let mut tied_len = raw_documents.len();
+    let mut after = HashSet::new();
+
+    for (criterion, (_, cursor_value)) in criteria.as_ref().iter().zip(&cursor.values) {
+        if tied_len == 0 { break }
+
+        let tied = &mut raw_documents[..tied_len];
+        let ctx = ContextMut {
+            reader,
+            postings_lists: &mut *postings_lists,
+            query_mapping,
+            documents_fields_counts_store,
+        };
+        criterion.prepare(ctx, tied)?;
+
+        let ctx = Context { postings_lists: &*postings_lists, query_mapping };
+        let mut equal = 0;
+        for i in 0..tied.len() {
+            // the documents without value are sorted after the others
+            let order = match (criterion.value(&ctx, &tied[i]), cursor_value) {
+                (Some(value), Some(cursor_value)) => criterion.evaluate_value(&value, cursor_value),
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (None, None) => Ordering::Equal,
+            };
+
+            match order {
+                Ordering::Equal => {
+                    tied.swap(equal, i);
+                    equal += 1;
+                }
+                Ordering::Greater => { after.insert(tied[i].id); },
+                Ordering::Less => (),
+            }
+        }
+
+        tied_len = equal;
+    }
+
+    raw_documents.retain(|rd| after.contains(&rd.id));
+
+    Ok(())
+}
+
+/// Returns the name of each criterion along with the value
+/// it computed for the document, to build a search cursor.
+fn cursor_values(criteria: &Criteria, ctx: &Context, document: &RawDocument) -> Vec<(String, Option<Number>)> {
+    criteria.as_ref().iter()
+        .map(|criterion| (criterion.name().to_string(), criterion.value(ctx, document)))
+        .collect()
+}
+
+/// Returns the value of each criterion for the document, the
+/// criteria that can not report a value for a document are skipped.
+fn criteria_values(criteria: &Criteria, ctx: &Context, document: &RawDocument) -> Vec<(String, Number)> {
+    let mut values = Vec::new();
+    for criterion in criteria.as_ref() {
+        if let Some(value) = criterion.value(ctx, document) {
+            values.push((criterion.name().to_string(), value));
+        }
+    }
+    values
+}
+
+/// Returns the typo tolerance settings of the index along with
+/// the attributes in which the typos are disabled.
+fn typo_tolerance(
+    reader: &heed::RoTxn<MainT>,
+    main_store: store::Main,
+) -> MResult<(TypoTolerance, Vec<SchemaAttr>)> {
+    let typo_tolerance = main_store.typo_tolerance(reader)?.unwrap_or_default();
+
+    let mut exact_attributes = Vec::new();
+    if let Some(schema) = main_store.schema(reader)? {
+        for name in &typo_tolerance.disable_on_attributes {
+            if let Some(attribute) = schema.attribute(name) {
+                exact_attributes.push(attribute);
+            }
+        }
+    }
+
+    Ok((typo_tolerance, exact_attributes))
 }
 
 fn cleanup_bare_matches<'tag, 'txn>(
     arena: &mut SmallArena<'tag, PostingsListView<'txn>>,
     docids: &Set<DocumentId>,
     queries: HashMap<PostingsKey, Cow<'txn, Set<DocIndex>>>,
+    origins: &HashMap<QueryId, WordOrigin>,
 ) -> Vec<BareMatch<'tag>>
 {
     let docidslen = docids.len() as f32;
     let mut bare_matches = Vec::new();
 
-    for (PostingsKey { query, input, distance, is_exact }, matches) in queries {
+    for (PostingsKey { query, phrase_offset, input, distance, is_exact }, matches) in queries {
+        let query_index = query.id + phrase_offset;
+        let is_original = origins.get(&query_index).map_or(true, |o| *o == WordOrigin::Original);
         let postings_list_view = PostingsListView::original(Rc::from(input), Rc::new(matches));
         let pllen = postings_list_view.len() as f32;
 
@@ -380,9 +763,10 @@ fn cleanup_bare_matches<'tag, 'txn>(
 
                     let bare_match = BareMatch {
                         document_id,
-                        query_index: query.id,
+                        query_index,
                         distance,
                         is_exact,
+                        is_original,
                         postings_list: posting_list_index,
                     };
 
@@ -411,9 +795,10 @@ fn cleanup_bare_matches<'tag, 'txn>(
 
                     let bare_match = BareMatch {
                         document_id: *id,
-                        query_index: query.id,
+                        query_index,
                         distance,
                         is_exact,
+                        is_original,
                         postings_list: posting_list_index,
                     };
 
@@ -435,6 +820,9 @@ pub struct BareMatch<'tag> {
     pub query_index: usize,
     pub distance: u8,
     pub is_exact: bool,
+    /// Whether the match comes from a word typed by the user and not
+    /// from a synonym, a split word or a concatenation of words.
+    pub is_original: bool,
     pub postings_list: Idx32<'tag>,
 }
 
@@ -445,6 +833,7 @@ impl fmt::Debug for BareMatch<'_> {
             .field("query_index", &self.query_index)
             .field("distance", &self.distance)
             .field("is_exact", &self.is_exact)
+            .field("is_original", &self.is_original)
             .finish()
     }
 }
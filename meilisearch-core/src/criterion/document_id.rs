@@ -1,5 +1,5 @@
 use std::cmp::Ordering;
-use crate::RawDocument;
+use crate::{Number, RawDocument};
 use super::{Criterion, Context};
 
 pub struct DocumentId;
@@ -13,4 +13,8 @@ impl Criterion for DocumentId {
 
         lhs.cmp(rhs)
     }
+
+    fn value(&self, _ctx: &Context, document: &RawDocument) -> Option<Number> {
+        Some(Number::Unsigned(document.id.0))
+    }
 }
@@ -2,7 +2,7 @@ use std::cmp::{Ordering, Reverse};
 use std::collections::hash_map::{HashMap, Entry};
 use meilisearch_schema::SchemaAttr;
 use slice_group_by::GroupBy;
-use crate::{RawDocument, MResult};
+use crate::{Number, RawDocument, MResult};
 use crate::bucket_sort::BareMatch;
 use super::{Criterion, Context, ContextMut};
 
@@ -21,13 +21,13 @@ impl Criterion for Exact {
         let reader = ctx.reader;
 
         'documents: for doc in documents {
-            doc.bare_matches.sort_unstable_by_key(|bm| (bm.query_index, Reverse(bm.is_exact)));
+            doc.bare_matches.sort_unstable_by_key(|bm| (bm.query_index, Reverse(is_original_exact(bm))));
 
             // mark the document if we find a "one word field" that matches
             let mut fields_counts = HashMap::new();
             for group in doc.bare_matches.linear_group_by_key(|bm| bm.query_index) {
-                for group in group.linear_group_by_key(|bm| bm.is_exact) {
-                    if !group[0].is_exact { break }
+                for group in group.linear_group_by_key(is_original_exact) {
+                    if !is_original_exact(&group[0]) { break }
 
                     for bm in group {
                         for di in ctx.postings_lists[bm.postings_list].as_ref() {
@@ -55,24 +55,43 @@ impl Criterion for Exact {
     }
 
     fn evaluate(&self, _ctx: &Context, lhs: &RawDocument, rhs: &RawDocument) -> Ordering {
-        #[inline]
-        fn sum_exact_query_words(matches: &[BareMatch]) -> usize {
-            let mut sum_exact_query_words = 0;
+        exact_key(lhs).cmp(&exact_key(rhs)).reverse()
+    }
 
-            for group in matches.linear_group_by_key(|bm| bm.query_index) {
-                sum_exact_query_words += group[0].is_exact as usize;
-            }
+    // whether a field contains only one exact word and the number of exact
+    // query words, encoded in a single number in the same order as the documents
+    fn value(&self, _ctx: &Context, document: &RawDocument) -> Option<Number> {
+        let (one_word_field, exact_words) = exact_key(document);
+        Some(Number::Unsigned((one_word_field as u64) << 32 | exact_words as u64))
+    }
 
-            sum_exact_query_words
-        }
+    fn evaluate_value(&self, lhs: &Number, rhs: &Number) -> Ordering {
+        lhs.cmp(rhs).reverse()
+    }
+}
 
-        // does it contains a "one word field"
-        lhs.contains_one_word_field.cmp(&rhs.contains_one_word_field).reverse()
-        // if not, with document contains the more exact words
-        .then_with(|| {
-            let lhs = sum_exact_query_words(&lhs.bare_matches);
-            let rhs = sum_exact_query_words(&rhs.bare_matches);
-            lhs.cmp(&rhs).reverse()
-        })
+/// The key on which the documents are sorted, the higher the better.
+#[inline]
+fn exact_key(document: &RawDocument) -> (bool, usize) {
+    // does it contains a "one word field",
+    // if not, with document contains the more exact words
+    (document.contains_one_word_field, sum_exact_query_words(&document.bare_matches))
+}
+
+#[inline]
+fn sum_exact_query_words(matches: &[BareMatch]) -> usize {
+    let mut sum_exact_query_words = 0;
+
+    for group in matches.linear_group_by_key(|bm| bm.query_index) {
+        sum_exact_query_words += is_original_exact(&group[0]) as usize;
     }
+
+    sum_exact_query_words
+}
+
+/// Only the exact matches of the words typed by the user are
+/// considered exact, the exact synonyms are not.
+#[inline]
+fn is_original_exact(bm: &BareMatch) -> bool {
+    bm.is_exact && bm.is_original
 }
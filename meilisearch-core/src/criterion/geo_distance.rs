@@ -0,0 +1,94 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use meilisearch_schema::{Schema, SchemaAttr};
+use ordered_float::OrderedFloat;
+use crate::{GeoMap, GeoPoint, Number, RawDocument};
+use super::{Criterion, Context};
+
+/// An helper struct that permit to sort documents by the distance
+/// between their geo attribute and a point given at query time.
+///
+/// Documents without a geo point are placed after all the others.
+///
+/// # Example
+///
+/// ```ignore
+/// let point = GeoPoint::new(48.8566, 2.3522);
+/// let geo_distance = GeoDistance::new(&geo_map, &schema, "location", point)?;
+///
+/// let builder = CriteriaBuilder::with_capacity(8)
+///        .add(Typo)
+///        .add(Words)
+///        .add(geo_distance)
+///        .add(DocumentId);
+///
+/// let criterion = builder.build();
+///
+/// ```
+pub struct GeoDistance<'a> {
+    geo_map: &'a GeoMap,
+    attr: SchemaAttr,
+    point: GeoPoint,
+}
+
+impl<'a> GeoDistance<'a> {
+    pub fn new(
+        geo_map: &'a GeoMap,
+        schema: &Schema,
+        attr_name: &str,
+        point: GeoPoint,
+    ) -> Result<GeoDistance<'a>, GeoDistanceError> {
+        let attr = match schema.attribute(attr_name) {
+            Some(attr) => attr,
+            None => return Err(GeoDistanceError::AttributeNotFound),
+        };
+
+        if !schema.props(attr).is_geo() {
+            return Err(GeoDistanceError::AttributeNotRegisteredForGeo);
+        }
+
+        Ok(GeoDistance { geo_map, attr, point })
+    }
+}
+
+impl Criterion for GeoDistance<'_> {
+    fn name(&self) -> &str {
+        "geo distance"
+    }
+
+    fn evaluate(&self, _ctx: &Context, lhs: &RawDocument, rhs: &RawDocument) -> Ordering {
+        let lhs = self.geo_map.get(lhs.id, self.attr).map(|p| p.distance(self.point));
+        let rhs = self.geo_map.get(rhs.id, self.attr).map(|p| p.distance(self.point));
+
+        match (lhs, rhs) {
+            (Some(lhs), Some(rhs)) => lhs.partial_cmp(&rhs).unwrap_or(Ordering::Equal),
+            (None, Some(_)) => Ordering::Greater,
+            (Some(_), None) => Ordering::Less,
+            (None, None) => Ordering::Equal,
+        }
+    }
+
+    fn value(&self, _ctx: &Context, document: &RawDocument) -> Option<Number> {
+        let distance = self.geo_map.get(document.id, self.attr)?.distance(self.point);
+        Some(Number::Float(OrderedFloat(distance)))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GeoDistanceError {
+    AttributeNotFound,
+    AttributeNotRegisteredForGeo,
+}
+
+impl fmt::Display for GeoDistanceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use GeoDistanceError::*;
+        match self {
+            AttributeNotFound => f.write_str("attribute not found in the schema"),
+            AttributeNotRegisteredForGeo => f.write_str("attribute not registered as a geo point"),
+        }
+    }
+}
+
+impl Error for GeoDistanceError {}
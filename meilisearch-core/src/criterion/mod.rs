@@ -9,7 +9,7 @@ use slice_group_by::GroupBy;
 use crate::bucket_sort::{SimpleMatch, PostingsListView};
 use crate::database::MainT;
 use crate::query_tree::QueryId;
-use crate::{store, RawDocument, MResult};
+use crate::{store, Number, RawDocument, MResult};
 
 mod typo;
 mod words;
@@ -19,6 +19,7 @@ mod words_position;
 mod exact;
 mod document_id;
 mod sort_by_attr;
+mod geo_distance;
 
 pub use self::typo::Typo;
 pub use self::words::Words;
@@ -28,6 +29,7 @@ pub use self::words_position::WordsPosition;
 pub use self::exact::Exact;
 pub use self::document_id::DocumentId;
 pub use self::sort_by_attr::SortByAttr;
+pub use self::geo_distance::{GeoDistance, GeoDistanceError};
 
 pub trait Criterion {
     fn name(&self) -> &str;
@@ -48,6 +50,23 @@ pub trait Criterion {
         rhs: &RawDocument<'r, 'tag>,
     ) -> Ordering;
 
+    /// Returns the value this criterion computed for the document,
+    /// it is used to explain the position of a document in the results.
+    fn value<'p, 'tag, 'txn, 'q, 'r>(
+        &self,
+        _ctx: &Context<'p, 'tag, 'txn, 'q>,
+        _document: &RawDocument<'r, 'tag>,
+    ) -> Option<Number>
+    {
+        None
+    }
+
+    /// Compares two values returned by `value`, in the same order as `evaluate`
+    /// compares the documents, it is used to resume a search from a cursor.
+    fn evaluate_value(&self, lhs: &Number, rhs: &Number) -> Ordering {
+        lhs.cmp(rhs)
+    }
+
     #[inline]
     fn eq<'p, 'tag, 'txn, 'q, 'r>(
         &self,
@@ -130,6 +149,18 @@ impl<'a> Default for Criteria<'a> {
     }
 }
 
+impl<'a> Criteria<'a> {
+    /// Appends the `DocumentId` criterion if it is not the last one,
+    /// no two documents are then equally ranked by the criteria.
+    pub fn with_document_id_tie_break(mut self) -> Criteria<'a> {
+        let tie_break = self.inner.last().map_or(false, |c| c.name() == DocumentId.name());
+        if !tie_break {
+            self.inner.push(Box::new(DocumentId));
+        }
+        self
+    }
+}
+
 impl<'a> AsRef<[Box<dyn Criterion + 'a>]> for Criteria<'a> {
     fn as_ref(&self) -> &[Box<dyn Criterion + 'a>] {
         &self.inner
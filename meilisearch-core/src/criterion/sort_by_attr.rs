@@ -2,7 +2,7 @@ use std::cmp::Ordering;
 use std::error::Error;
 use std::fmt;
 use meilisearch_schema::{Schema, SchemaAttr};
-use crate::{RankedMap, RawDocument};
+use crate::{Number, RankedMap, RawDocument};
 use super::{Criterion, Context};
 
 /// An helper struct that permit to sort documents by
@@ -42,6 +42,7 @@ use super::{Criterion, Context};
 pub struct SortByAttr<'a> {
     ranked_map: &'a RankedMap,
     attr: SchemaAttr,
+    name: String,
     reversed: bool,
 }
 
@@ -77,17 +78,21 @@ impl<'a> SortByAttr<'a> {
             return Err(SortByAttrError::AttributeNotRegisteredForRanking);
         }
 
-        Ok(SortByAttr {
-            ranked_map,
-            attr,
-            reversed,
-        })
+        let name = if reversed {
+            format!("desc({})", attr_name)
+        } else {
+            format!("asc({})", attr_name)
+        };
+
+        Ok(SortByAttr { ranked_map, attr, name, reversed })
     }
 }
 
 impl Criterion for SortByAttr<'_> {
+    // the criterion is named after the attribute and the order, like `asc(price)`,
+    // to never collide with the built-in criteria nor the other custom ones
     fn name(&self) -> &str {
-        "sort by attribute"
+        &self.name
     }
 
     fn evaluate(&self, _ctx: &Context, lhs: &RawDocument, rhs: &RawDocument) -> Ordering {
@@ -108,6 +113,19 @@ impl Criterion for SortByAttr<'_> {
             (None, None) => Ordering::Equal,
         }
     }
+
+    fn value(&self, _ctx: &Context, document: &RawDocument) -> Option<Number> {
+        self.ranked_map.get(document.id, self.attr)
+    }
+
+    fn evaluate_value(&self, lhs: &Number, rhs: &Number) -> Ordering {
+        let order = lhs.cmp(rhs);
+        if self.reversed {
+            order.reverse()
+        } else {
+            order
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
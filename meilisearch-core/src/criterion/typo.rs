@@ -1,5 +1,7 @@
 use std::cmp::Ordering;
-use crate::{RawDocument, MResult};
+use std::collections::HashSet;
+use crate::{Number, RawDocument, MResult};
+use crate::bucket_sort::BareMatch;
 use super::{Criterion, Context, ContextMut, prepare_query_distances};
 
 pub struct Typo;
@@ -18,38 +20,62 @@ impl Criterion for Typo {
     }
 
     fn evaluate(&self, _ctx: &Context, lhs: &RawDocument, rhs: &RawDocument) -> Ordering {
-        // This function is a wrong logarithmic 10 function.
-        // It is safe to panic on input number higher than 3,
-        // the number of typos is never bigger than that.
-        #[inline]
-        fn custom_log10(n: u8) -> f32 {
-            match n {
-                0 => 0.0,     // log(1)
-                1 => 0.30102, // log(2)
-                2 => 0.47712, // log(3)
-                3 => 0.60205, // log(4)
-                _ => panic!("invalid number"),
-            }
-        }
+        typo_key(lhs).cmp(&typo_key(rhs)).reverse()
+    }
 
-        #[inline]
-        fn compute_typos(distances: &[Option<u8>]) -> usize {
-            let mut number_words: usize = 0;
-            let mut sum_typos = 0.0;
+    // the score computed from the typos and the number of original words,
+    // encoded in a single number in the same order as the documents
+    fn value(&self, _ctx: &Context, document: &RawDocument) -> Option<Number> {
+        let (typos, original_words) = typo_key(document);
+        Some(Number::Unsigned((typos as u64) << 32 | original_words as u64))
+    }
 
-            for distance in distances {
-                if let Some(distance) = distance {
-                    sum_typos += custom_log10(*distance);
-                    number_words += 1;
-                }
-            }
+    fn evaluate_value(&self, lhs: &Number, rhs: &Number) -> Ordering {
+        lhs.cmp(rhs).reverse()
+    }
+}
 
-            (number_words as f32 / (sum_typos + 1.0) * 1000.0) as usize
+/// The key on which the documents are sorted, the higher the better.
+#[inline]
+fn typo_key(document: &RawDocument) -> (usize, usize) {
+    // This function is a wrong logarithmic 10 function.
+    // It is safe to panic on input number higher than 3,
+    // the number of typos is never bigger than that.
+    #[inline]
+    fn custom_log10(n: u8) -> f32 {
+        match n {
+            0 => 0.0,     // log(1)
+            1 => 0.30102, // log(2)
+            2 => 0.47712, // log(3)
+            3 => 0.60205, // log(4)
+            _ => panic!("invalid number"),
         }
+    }
 
-        let lhs = compute_typos(&lhs.processed_distances);
-        let rhs = compute_typos(&rhs.processed_distances);
+    #[inline]
+    fn compute_typos(distances: &[Option<u8>]) -> usize {
+        let mut number_words: usize = 0;
+        let mut sum_typos = 0.0;
 
-        lhs.cmp(&rhs).reverse()
+        for distance in distances {
+            if let Some(distance) = distance {
+                sum_typos += custom_log10(*distance);
+                number_words += 1;
+            }
+        }
+
+        (number_words as f32 / (sum_typos + 1.0) * 1000.0) as usize
     }
+
+    // if equal, the document matching the more original query words is better,
+    // a synonym, a split word or a concatenation is not what the user typed
+    let typos = compute_typos(&document.processed_distances);
+    let original_words = number_of_original_words(&document.bare_matches);
+
+    (typos, original_words)
+}
+
+#[inline]
+fn number_of_original_words(matches: &[BareMatch]) -> usize {
+    matches.iter().filter(|bm| bm.is_original).map(|bm| bm.query_index).collect::<HashSet<_>>().len()
 }
@@ -1,5 +1,5 @@
 use std::cmp::Ordering;
-use crate::{RawDocument, MResult};
+use crate::{Number, RawDocument, MResult};
 use super::{Criterion, Context, ContextMut, prepare_query_distances};
 
 pub struct Words;
@@ -18,14 +18,23 @@ impl Criterion for Words {
     }
 
     fn evaluate(&self, _ctx: &Context, lhs: &RawDocument, rhs: &RawDocument) -> Ordering {
-        #[inline]
-        fn number_of_query_words(distances: &[Option<u8>]) -> usize {
-            distances.iter().cloned().filter(Option::is_some).count()
-        }
-
         let lhs = number_of_query_words(&lhs.processed_distances);
         let rhs = number_of_query_words(&rhs.processed_distances);
 
         lhs.cmp(&rhs).reverse()
     }
+
+    fn value(&self, _ctx: &Context, document: &RawDocument) -> Option<Number> {
+        let words = number_of_query_words(&document.processed_distances);
+        Some(Number::Unsigned(words as u64))
+    }
+
+    fn evaluate_value(&self, lhs: &Number, rhs: &Number) -> Ordering {
+        lhs.cmp(rhs).reverse()
+    }
+}
+
+#[inline]
+fn number_of_query_words(distances: &[Option<u8>]) -> usize {
+    distances.iter().cloned().filter(Option::is_some).count()
 }
@@ -357,7 +357,7 @@ mod tests {
 
     use crate::criterion::{self, CriteriaBuilder};
     use crate::update::{ProcessedUpdateResult, UpdateStatus};
-    use crate::{Document, DocumentId};
+    use crate::{Document, DocumentId, GeoPoint, SortResult};
     use serde::de::IgnoredAny;
     use std::sync::mpsc;
 
@@ -664,8 +664,8 @@ mod tests {
 
         // even try to search for a document
         let reader = db.main_read_txn().unwrap();
-        let results = index.query_builder().query(&reader, "21 ", 0..20).unwrap();
-        assert_matches!(results.len(), 1);
+        let SortResult { documents, .. } = index.query_builder().query(&reader, "21 ", 0..20).unwrap();
+        assert_matches!(documents.len(), 1);
 
         reader.abort();
 
@@ -1074,8 +1074,8 @@ mod tests {
 
         let builder = index.query_builder_with_criteria(criteria);
 
-        let results = builder.query(&reader, "Kevin", 0..20).unwrap();
-        let mut iter = results.into_iter();
+        let SortResult { documents, .. } = builder.query(&reader, "Kevin", 0..20).unwrap();
+        let mut iter = documents.into_iter();
 
         assert_matches!(
             iter.next(),
@@ -1093,4 +1093,253 @@ mod tests {
         );
         assert_matches!(iter.next(), None);
     }
+
+    #[test]
+    fn geo_distance_ordering() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let database = Database::open_or_create(dir.path()).unwrap();
+        let db = &database;
+
+        let (sender, receiver) = mpsc::sync_channel(100);
+        let update_fn = move |_name: &str, update: ProcessedUpdateResult| {
+            sender.send(update.update_id).unwrap()
+        };
+        let index = database.create_index("test").unwrap();
+
+        database.set_update_callback(Box::new(update_fn));
+
+        let schema = {
+            let data = r#"
+                identifier = "id"
+
+                [attributes."name"]
+                displayed = true
+                indexed = true
+
+                [attributes."location"]
+                displayed = true
+                geo = true
+            "#;
+            toml::from_str(data).unwrap()
+        };
+
+        let mut writer = db.update_write_txn().unwrap();
+        let _update_id = index.schema_update(&mut writer, schema).unwrap();
+        writer.commit().unwrap();
+
+        let mut additions = index.documents_addition();
+
+        // DocumentId(7900334843754999545)
+        let doc1 = serde_json::json!({
+            "id": 123,
+            "name": "Kevin in London",
+            "location": { "lat": 51.5074, "lng": -0.1278 },
+        });
+
+        // DocumentId(8367468610878465872)
+        let doc2 = serde_json::json!({
+            "id": 234,
+            "name": "Kevin in Paris",
+            "location": [48.8566, 2.3522],
+        });
+
+        // a malformed geo point does not prevent the document from being indexed
+        let doc3 = serde_json::json!({
+            "id": 345,
+            "name": "Kevin somewhere",
+            "location": "somewhere",
+        });
+
+        additions.update_document(doc1);
+        additions.update_document(doc2);
+        additions.update_document(doc3);
+
+        let mut writer = db.update_write_txn().unwrap();
+        let update_id = additions.finalize(&mut writer).unwrap();
+        writer.commit().unwrap();
+
+        // block until the transaction is processed
+        let _ = receiver.into_iter().find(|id| *id == update_id);
+
+        let reader = db.main_read_txn().unwrap();
+        let schema = index.main.schema(&reader).unwrap().unwrap();
+        let geo_map = index.main.geo_map(&reader).unwrap().unwrap();
+        assert_eq!(index.main.number_of_documents(&reader).unwrap(), 3);
+        assert_eq!(geo_map.len(), 2);
+
+        // Versailles is closer to Paris than to London
+        let versailles = GeoPoint::new(48.8049, 2.1204);
+        let criteria = CriteriaBuilder::new()
+            .add(criterion::GeoDistance::new(&geo_map, &schema, "location", versailles).unwrap())
+            .add(criterion::DocumentId)
+            .build();
+
+        let builder = index.query_builder_with_criteria(criteria);
+
+        let SortResult { documents, .. } = builder.query(&reader, "Kevin", 0..20).unwrap();
+        let mut iter = documents.into_iter();
+
+        assert_matches!(
+            iter.next(),
+            Some(Document {
+                id: DocumentId(8367468610878465872),
+                ..
+            })
+        );
+        assert_matches!(
+            iter.next(),
+            Some(Document {
+                id: DocumentId(7900334843754999545),
+                ..
+            })
+        );
+        // the document without geo point is placed after the others
+        assert_matches!(iter.next(), Some(Document { .. }));
+        assert_matches!(iter.next(), None);
+    }
+
+    #[test]
+    fn legacy_schema_is_read() {
+        use heed::types::SerdeBincode;
+        use indexmap::IndexMap;
+        use serde::Serialize;
+
+        // the layout of the schemas stored before the geo attribute kind
+        #[derive(Serialize)]
+        struct LegacySchemaProps {
+            displayed: bool,
+            indexed: bool,
+            ranked: bool,
+        }
+
+        #[derive(Serialize)]
+        struct LegacySchema {
+            identifier: String,
+            attributes: IndexMap<String, LegacySchemaProps>,
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+
+        let database = Database::open_or_create(dir.path()).unwrap();
+        let db = &database;
+        let index = database.create_index("test").unwrap();
+
+        let mut attributes = IndexMap::new();
+        attributes.insert(String::from("id"), LegacySchemaProps { displayed: true, indexed: false, ranked: false });
+        attributes.insert(String::from("name"), LegacySchemaProps { displayed: true, indexed: true, ranked: false });
+        let legacy = LegacySchema { identifier: String::from("id"), attributes };
+
+        let mut writer = db.main_write_txn().unwrap();
+        index.main.main.put::<_, Str, SerdeBincode<LegacySchema>>(&mut writer, "schema", &legacy).unwrap();
+        writer.commit().unwrap();
+
+        let reader = db.main_read_txn().unwrap();
+        let schema = index.main.schema(&reader).unwrap().unwrap();
+        reader.abort();
+
+        let expected = {
+            let data = r#"
+                identifier = "id"
+
+                [attributes."id"]
+                displayed = true
+
+                [attributes."name"]
+                displayed = true
+                indexed = true
+            "#;
+            toml::from_str(data).unwrap()
+        };
+        assert_eq!(schema, expected);
+
+        // the schema is written back under the new key
+        let mut writer = db.main_write_txn().unwrap();
+        index.main.put_schema(&mut writer, &schema).unwrap();
+        writer.commit().unwrap();
+
+        let reader = db.main_read_txn().unwrap();
+        assert_eq!(index.main.schema(&reader).unwrap(), Some(expected));
+        assert!(index.main.main.get::<_, Str, Str>(&reader, "schema").unwrap().is_none());
+    }
+
+    #[test]
+    fn facets_distribution() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let database = Database::open_or_create(dir.path()).unwrap();
+        let db = &database;
+
+        let (sender, receiver) = mpsc::sync_channel(100);
+        let update_fn = move |_name: &str, update: ProcessedUpdateResult| {
+            sender.send(update.update_id).unwrap()
+        };
+        let index = database.create_index("test").unwrap();
+
+        database.set_update_callback(Box::new(update_fn));
+
+        let schema = {
+            let data = r#"
+                identifier = "id"
+
+                [attributes."name"]
+                displayed = true
+                indexed = true
+
+                [attributes."genre"]
+                displayed = true
+            "#;
+            toml::from_str(data).unwrap()
+        };
+
+        let mut writer = db.update_write_txn().unwrap();
+        let _update_id = index.schema_update(&mut writer, schema).unwrap();
+        let _update_id = index.facets_update(&mut writer, vec!["genre".to_string()]).unwrap();
+        writer.commit().unwrap();
+
+        let mut additions = index.documents_addition();
+        additions.update_document(serde_json::json!({ "id": 1, "name": "Kevin", "genre": "Comedy" }));
+        additions.update_document(serde_json::json!({ "id": 2, "name": "Kevin", "genre": "Drama" }));
+        additions.update_document(serde_json::json!({ "id": 3, "name": "Kevin", "genre": ["comedy", "Drama"] }));
+
+        let mut writer = db.update_write_txn().unwrap();
+        let update_id = additions.finalize(&mut writer).unwrap();
+        writer.commit().unwrap();
+
+        // block until the transaction is processed
+        let _ = receiver.iter().find(|id| *id == update_id);
+
+        let reader = db.main_read_txn().unwrap();
+        let schema = index.main.schema(&reader).unwrap().unwrap();
+        let genre = schema.attribute("genre").unwrap();
+
+        let mut builder = index.query_builder();
+        builder.with_facets(vec![genre]);
+        let SortResult { documents, facets, .. } = builder.query(&reader, "kevin", 0..1).unwrap();
+        assert_eq!(documents.len(), 1);
+
+        let facets = facets.unwrap();
+        assert_eq!(facets[&genre]["comedy"], 2);
+        assert_eq!(facets[&genre]["drama"], 2);
+        reader.abort();
+
+        let mut deletion = index.documents_deletion();
+        deletion.delete_document(&schema, serde_json::json!({ "id": 3 })).unwrap();
+
+        let mut writer = db.update_write_txn().unwrap();
+        let update_id = deletion.finalize(&mut writer).unwrap();
+        writer.commit().unwrap();
+
+        // block until the transaction is processed
+        let _ = receiver.iter().find(|id| *id == update_id);
+
+        let reader = db.main_read_txn().unwrap();
+        let mut builder = index.query_builder();
+        builder.with_facets(vec![genre]);
+        let SortResult { facets, .. } = builder.query(&reader, "kevin", 0..20).unwrap();
+
+        let facets = facets.unwrap();
+        assert_eq!(facets[&genre]["comedy"], 1);
+        assert_eq!(facets[&genre]["drama"], 1);
+    }
 }
@@ -12,6 +12,12 @@ pub enum Error {
     SchemaMissing,
     WordIndexMissing,
     MissingDocumentId,
+    FacetAttributeNotFound(String),
+    TypoAttributeNotFound(String),
+    InvalidTypoWordLengths { one_typo: usize, two_typos: usize },
+    TokenizerNotFound(String),
+    SearchCursorWithDistinct,
+    SearchCursorCriteriaDiffer,
     Zlmdb(heed::Error),
     Fst(fst::Error),
     SerdeJson(SerdeJsonError),
@@ -79,6 +85,16 @@ impl fmt::Display for Error {
             SchemaMissing => write!(f, "this index does not have a schema"),
             WordIndexMissing => write!(f, "this index does not have a word index"),
             MissingDocumentId => write!(f, "document id is missing"),
+            FacetAttributeNotFound(name) => write!(f, "facet attribute {} not found in the schema", name),
+            TypoAttributeNotFound(name) => write!(f, "typo tolerance attribute {} not found in the schema", name),
+            InvalidTypoWordLengths { one_typo, two_typos } => write!(
+                f,
+                "the minimum word length for two typos ({}) can not be lower than the one for one typo ({})",
+                two_typos, one_typo,
+            ),
+            TokenizerNotFound(name) => write!(f, "tokenizer {} is not registered", name),
+            SearchCursorWithDistinct => write!(f, "a search cursor can not be used along with a distinct rule"),
+            SearchCursorCriteriaDiffer => write!(f, "the ranking rules changed since the search cursor was created"),
             Zlmdb(e) => write!(f, "heed error; {}", e),
             Fst(e) => write!(f, "fst error; {}", e),
             SerdeJson(e) => write!(f, "serde json error; {}", e),
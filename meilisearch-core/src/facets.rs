@@ -0,0 +1,123 @@
+use std::collections::{BTreeMap, HashMap};
+
+use meilisearch_schema::SchemaAttr;
+use sdset::duo::{Difference, Intersection, Union};
+use sdset::{Set, SetBuf, SetOperation};
+use serde_json::Value;
+
+use crate::database::MainT;
+use crate::{store, DocumentId, MResult};
+
+/// The number of documents associated with each value of each facet attribute.
+pub type FacetsDistribution = HashMap<SchemaAttr, HashMap<String, usize>>;
+
+/// The documents ids that must be added to or removed from each facet value.
+pub type FacetsDelta = BTreeMap<(SchemaAttr, String), Vec<DocumentId>>;
+
+/// Returns the facet values of a document attribute, strings are lowercased,
+/// numbers and booleans are converted into strings and arrays are flattened.
+pub fn facet_values(value: &Value) -> Vec<String> {
+    match value {
+        Value::String(string) => vec![string.trim().to_lowercase()],
+        Value::Number(number) => vec![number.to_string()],
+        Value::Bool(boolean) => vec![boolean.to_string()],
+        Value::Array(values) => values.iter().flat_map(|v| match v {
+            Value::Array(_) => Vec::new(),
+            value => facet_values(value),
+        }).collect(),
+        Value::Null | Value::Object(_) => Vec::new(),
+    }
+}
+
+/// Registers the facet values of a document attribute in the given delta.
+pub fn register_facet_values(
+    delta: &mut FacetsDelta,
+    attribute: SchemaAttr,
+    document_id: DocumentId,
+    value: &Value,
+) {
+    for facet_value in facet_values(value) {
+        delta.entry((attribute, facet_value)).or_insert_with(Vec::new).push(document_id);
+    }
+}
+
+pub fn apply_facets_addition(
+    writer: &mut heed::RwTxn<MainT>,
+    facets_store: store::Facets,
+    addition: FacetsDelta,
+) -> MResult<()> {
+    for ((attribute, value), documents_ids) in addition {
+        let documents_ids = SetBuf::from_dirty(documents_ids);
+        let set = match facets_store.facet_documents(writer, attribute, &value)? {
+            Some(previous) => Union::new(&previous, &documents_ids).into_set_buf(),
+            None => documents_ids,
+        };
+
+        facets_store.put_facet_documents(writer, attribute, &value, &set)?;
+    }
+
+    Ok(())
+}
+
+pub fn apply_facets_deletion(
+    writer: &mut heed::RwTxn<MainT>,
+    facets_store: store::Facets,
+    deletion: FacetsDelta,
+) -> MResult<()> {
+    for ((attribute, value), documents_ids) in deletion {
+        let documents_ids = SetBuf::from_dirty(documents_ids);
+        let set = match facets_store.facet_documents(writer, attribute, &value)? {
+            Some(previous) => Difference::new(&previous, &documents_ids).into_set_buf(),
+            None => continue,
+        };
+
+        if set.is_empty() {
+            facets_store.del_facet_documents(writer, attribute, &value)?;
+        } else {
+            facets_store.put_facet_documents(writer, attribute, &value, &set)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Counts, for each value of the given facet attributes, the number
+/// of documents that are part of the given documents ids.
+pub fn facets_distribution(
+    reader: &heed::RoTxn<MainT>,
+    facets_store: store::Facets,
+    attributes: &[SchemaAttr],
+    documents_ids: &Set<DocumentId>,
+) -> MResult<FacetsDistribution> {
+    let mut distribution = HashMap::with_capacity(attributes.len());
+
+    for attribute in attributes {
+        let mut counts = HashMap::new();
+        for result in facets_store.attribute_facets(reader, *attribute)? {
+            let (value, facet_documents_ids) = result?;
+            let count = Intersection::new(&facet_documents_ids, documents_ids).into_set_buf().len();
+            if count != 0 {
+                counts.insert(value.to_string(), count);
+            }
+        }
+        distribution.insert(*attribute, counts);
+    }
+
+    Ok(distribution)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn extract_facet_values() {
+        assert_eq!(facet_values(&json!(" Comedy ")), vec!["comedy"]);
+        assert_eq!(facet_values(&json!(42)), vec!["42"]);
+        assert_eq!(facet_values(&json!(true)), vec!["true"]);
+        assert_eq!(facet_values(&json!(["Drama", 12, [1]])), vec!["drama", "12"]);
+        assert!(facet_values(&json!(null)).is_empty());
+        assert!(facet_values(&json!({ "a": "b" })).is_empty());
+    }
+}
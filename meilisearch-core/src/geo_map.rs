@@ -0,0 +1,116 @@
+use std::str::FromStr;
+
+use hashbrown::HashMap;
+use meilisearch_schema::SchemaAttr;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::DocumentId;
+
+/// The mean radius of the Earth in meters.
+const EARTH_RADIUS: f64 = 6_371_008.8;
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeoPoint {
+    pub lat: f64,
+    pub lng: f64,
+}
+
+impl GeoPoint {
+    pub fn new(lat: f64, lng: f64) -> GeoPoint {
+        GeoPoint { lat, lng }
+    }
+
+    /// Parses a geo point from an object with `lat` and `lng` fields
+    /// or from a `[lat, lng]` array, coordinates can be numbers or strings.
+    pub fn from_value(value: &Value) -> Option<GeoPoint> {
+        fn coordinate(value: &Value) -> Option<f64> {
+            match value {
+                Value::Number(number) => number.as_f64(),
+                Value::String(string) => f64::from_str(string.trim()).ok(),
+                _ => None,
+            }
+        }
+
+        let (lat, lng) = match value {
+            Value::Object(object) => (object.get("lat")?, object.get("lng")?),
+            Value::Array(array) if array.len() == 2 => (&array[0], &array[1]),
+            _ => return None,
+        };
+
+        let point = GeoPoint::new(coordinate(lat)?, coordinate(lng)?);
+        if point.is_valid() {
+            Some(point)
+        } else {
+            None
+        }
+    }
+
+    pub fn is_valid(self) -> bool {
+        self.lat >= -90.0 && self.lat <= 90.0 && self.lng >= -180.0 && self.lng <= 180.0
+    }
+
+    /// Returns the great-circle distance in meters between two points,
+    /// computed with the haversine formula.
+    pub fn distance(self, other: GeoPoint) -> f64 {
+        let (lat1, lat2) = (self.lat.to_radians(), other.lat.to_radians());
+        let dlat = lat2 - lat1;
+        let dlng = (other.lng - self.lng).to_radians();
+
+        let a = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlng / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS * a.sqrt().asin()
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct GeoMap(HashMap<(DocumentId, SchemaAttr), GeoPoint>);
+
+impl GeoMap {
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn insert(&mut self, document: DocumentId, attribute: SchemaAttr, point: GeoPoint) {
+        self.0.insert((document, attribute), point);
+    }
+
+    pub fn remove(&mut self, document: DocumentId, attribute: SchemaAttr) {
+        self.0.remove(&(document, attribute));
+    }
+
+    pub fn get(&self, document: DocumentId, attribute: SchemaAttr) -> Option<GeoPoint> {
+        self.0.get(&(document, attribute)).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parse_geo_points() {
+        let paris = GeoPoint::new(48.8566, 2.3522);
+        assert_eq!(GeoPoint::from_value(&json!({ "lat": 48.8566, "lng": 2.3522 })), Some(paris));
+        assert_eq!(GeoPoint::from_value(&json!({ "lat": "48.8566", "lng": "2.3522" })), Some(paris));
+        assert_eq!(GeoPoint::from_value(&json!([48.8566, 2.3522])), Some(paris));
+        assert_eq!(GeoPoint::from_value(&json!({ "lat": 48.8566 })), None);
+        assert_eq!(GeoPoint::from_value(&json!([91.0, 2.3522])), None);
+        assert_eq!(GeoPoint::from_value(&json!("paris")), None);
+    }
+
+    #[test]
+    fn haversine_distance() {
+        let paris = GeoPoint::new(48.8566, 2.3522);
+        let london = GeoPoint::new(51.5074, -0.1278);
+
+        assert_eq!(paris.distance(paris), 0.0);
+        let distance = paris.distance(london);
+        assert!((distance - 343_560.0).abs() < 1000.0, "{}", distance);
+    }
+}
@@ -2,11 +2,14 @@
 #[macro_use]
 extern crate assert_matches;
 
+mod autocomplete;
 mod automaton;
 mod bucket_sort;
 mod database;
 mod distinct_map;
 mod error;
+mod facets;
+mod geo_map;
 mod levenshtein;
 mod number;
 mod query_builder;
@@ -15,21 +18,38 @@ mod query_words_mapper;
 mod ranked_map;
 mod raw_document;
 mod reordered_attrs;
+mod similar;
+mod search_cursor;
+mod stemming;
+mod suggestions;
+mod tokenizers;
+mod typo_tolerance;
 mod update;
 pub mod criterion;
 pub mod raw_indexer;
 pub mod serde;
 pub mod store;
 
+pub use self::autocomplete::{autocomplete, Completion};
+pub use self::bucket_sort::SortResult;
 pub use self::database::{BoxUpdateFn, Database, MainT, UpdateT};
 pub use self::error::{Error, MResult};
+pub use self::facets::FacetsDistribution;
+pub use self::geo_map::{GeoMap, GeoPoint};
 pub use self::number::{Number, ParseNumberError};
+pub use self::query_tree::{MatchingStrategy, PrefixSearch};
 pub use self::ranked_map::RankedMap;
 pub use self::raw_document::RawDocument;
+pub use self::search_cursor::SearchCursor;
 pub use self::store::Index;
-pub use self::update::{EnqueuedUpdateResult, ProcessedUpdateResult, UpdateStatus, UpdateType};
+pub use self::stemming::{Language, Stemmer};
+pub use self::suggestions::suggest_query;
+pub use self::tokenizers::{register_tokenizer, tokenizer, ArcTokenizer, DEFAULT_TOKENIZER};
+pub use self::typo_tolerance::TypoTolerance;
+pub use self::update::{EnqueuedUpdateResult, ProcessedUpdateResult, SynonymsDefinitions, UpdateStatus, UpdateType};
+pub use meilisearch_tokenizer::{CjkDictionary, CjkTokenizer, CustomSeparators, DefaultTokenizer, Token, Tokenizer};
 pub use meilisearch_types::{DocIndex, DocumentId, Highlight};
-pub use query_words_mapper::QueryWordsMapper;
+pub use query_words_mapper::{QueryWordsMapper, WordOrigin};
 
 use std::convert::TryFrom;
 use std::collections::HashMap;
@@ -44,6 +64,8 @@ use crate::reordered_attrs::ReorderedAttrs;
 pub struct Document {
     pub id: DocumentId,
     pub highlights: Vec<Highlight>,
+    /// The value of each criterion for this document, in the criteria order.
+    pub ranking_info: Option<Vec<(String, Number)>>,
 
     #[cfg(test)]
     pub matches: Vec<crate::bucket_sort::SimpleMatch>,
@@ -96,12 +118,12 @@ fn highlights_from_raw_document<'a, 'tag, 'txn>(
 impl Document {
     #[cfg(not(test))]
     pub fn from_highlights(id: DocumentId, highlights: &[Highlight]) -> Document {
-        Document { id, highlights: highlights.to_owned() }
+        Document { id, highlights: highlights.to_owned(), ranking_info: None }
     }
 
     #[cfg(test)]
     pub fn from_highlights(id: DocumentId, highlights: &[Highlight]) -> Document {
-        Document { id, highlights: highlights.to_owned(), matches: Vec::new() }
+        Document { id, highlights: highlights.to_owned(), ranking_info: None, matches: Vec::new() }
     }
 
     #[cfg(not(test))]
@@ -119,7 +141,7 @@ impl Document {
             searchable_attrs,
         );
 
-        Document { id: raw_document.id, highlights }
+        Document { id: raw_document.id, highlights, ranking_info: None }
     }
 
     #[cfg(test)]
@@ -149,7 +171,7 @@ impl Document {
         }
         matches.sort_unstable();
 
-        Document { id: raw_document.id, highlights, matches }
+        Document { id: raw_document.id, highlights, ranking_info: None, matches }
     }
 }
 
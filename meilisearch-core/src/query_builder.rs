@@ -1,23 +1,34 @@
 use std::ops::Range;
 use std::time::Duration;
 
+use meilisearch_schema::SchemaAttr;
+
 use crate::database::MainT;
-use crate::bucket_sort::{bucket_sort, bucket_sort_with_distinct};
-use crate::{criterion::Criteria, Document, DocumentId};
-use crate::{reordered_attrs::ReorderedAttrs, store, MResult};
+use crate::error::Error;
+use crate::bucket_sort::{bucket_sort, bucket_sort_with_distinct, SortResult};
+use crate::similar::similar_documents;
+use crate::{criterion::Criteria, DocumentId, MatchingStrategy, PrefixSearch};
+use crate::{reordered_attrs::ReorderedAttrs, store, MResult, SearchCursor};
 
 pub struct QueryBuilder<'c, 'f, 'd> {
     criteria: Criteria<'c>,
     searchable_attrs: Option<ReorderedAttrs>,
     filter: Option<Box<dyn Fn(DocumentId) -> bool + 'f>>,
     distinct: Option<(Box<dyn Fn(DocumentId) -> Option<u64> + 'd>, usize)>,
+    facets: Option<Vec<SchemaAttr>>,
     timeout: Option<Duration>,
+    ranking_info: bool,
+    matching_strategy: MatchingStrategy,
+    prefix_search: Option<PrefixSearch>,
+    search_after: Option<SearchCursor>,
     main_store: store::Main,
     postings_lists_store: store::PostingsLists,
     documents_fields_counts_store: store::DocumentsFieldsCounts,
     synonyms_store: store::Synonyms,
+    docs_words_store: store::DocsWords,
     prefix_documents_cache_store: store::PrefixDocumentsCache,
     prefix_postings_lists_cache_store: store::PrefixPostingsListsCache,
+    facets_store: store::Facets,
 }
 
 impl<'c, 'f, 'd> QueryBuilder<'c, 'f, 'd> {
@@ -26,16 +37,20 @@ impl<'c, 'f, 'd> QueryBuilder<'c, 'f, 'd> {
         postings_lists: store::PostingsLists,
         documents_fields_counts: store::DocumentsFieldsCounts,
         synonyms: store::Synonyms,
+        docs_words: store::DocsWords,
         prefix_documents_cache: store::PrefixDocumentsCache,
         prefix_postings_lists_cache: store::PrefixPostingsListsCache,
+        facets: store::Facets,
     ) -> QueryBuilder<'c, 'f, 'd> {
         QueryBuilder::with_criteria(
             main,
             postings_lists,
             documents_fields_counts,
             synonyms,
+            docs_words,
             prefix_documents_cache,
             prefix_postings_lists_cache,
+            facets,
             Criteria::default(),
         )
     }
@@ -45,8 +60,10 @@ impl<'c, 'f, 'd> QueryBuilder<'c, 'f, 'd> {
         postings_lists: store::PostingsLists,
         documents_fields_counts: store::DocumentsFieldsCounts,
         synonyms: store::Synonyms,
+        docs_words: store::DocsWords,
         prefix_documents_cache: store::PrefixDocumentsCache,
         prefix_postings_lists_cache: store::PrefixPostingsListsCache,
+        facets: store::Facets,
         criteria: Criteria<'c>,
     ) -> QueryBuilder<'c, 'f, 'd> {
         QueryBuilder {
@@ -54,13 +71,20 @@ impl<'c, 'f, 'd> QueryBuilder<'c, 'f, 'd> {
             searchable_attrs: None,
             filter: None,
             distinct: None,
+            facets: None,
             timeout: None,
+            ranking_info: false,
+            matching_strategy: MatchingStrategy::default(),
+            prefix_search: None,
+            search_after: None,
             main_store: main,
             postings_lists_store: postings_lists,
             documents_fields_counts_store: documents_fields_counts,
             synonyms_store: synonyms,
+            docs_words_store: docs_words,
             prefix_documents_cache_store: prefix_documents_cache,
             prefix_postings_lists_cache_store: prefix_postings_lists_cache,
+            facets_store: facets,
         }
     }
 
@@ -71,17 +95,54 @@ impl<'c, 'f, 'd> QueryBuilder<'c, 'f, 'd> {
         self.filter = Some(Box::new(function))
     }
 
+    /// Bounds the time spent checking the candidates against the filter and the distinct
+    /// rule, starting from the beginning of the bucket sort. The candidates needed to
+    /// return the requested documents are always checked, the others are only checked
+    /// to count the hits until the timeout is reached, the count is then estimated from
+    /// the candidates checked until there. It has no effect without a filter nor a
+    /// distinct rule, the number of hits is then the number of candidates.
     pub fn with_fetch_timeout(&mut self, timeout: Duration) {
         self.timeout = Some(timeout)
     }
 
-    pub fn with_distinct<F, K>(&mut self, function: F, size: usize)
+    /// Reports the value of each criterion for the returned documents.
+    pub fn with_ranking_info(&mut self) {
+        self.ranking_info = true
+    }
+
+    /// Defines which query words can be ignored when
+    /// the documents do not match all of them.
+    pub fn with_matching_strategy(&mut self, strategy: MatchingStrategy) {
+        self.matching_strategy = strategy
+    }
+
+    /// Overrides the prefix search mode of the index for this query.
+    pub fn with_prefix_search(&mut self, prefix_search: PrefixSearch) {
+        self.prefix_search = Some(prefix_search)
+    }
+
+    /// Only returns the documents that are sorted after the document of the cursor,
+    /// the range of the query is then relative to the position of this document.
+    ///
+    /// The cursor can not be used along with a distinct rule, the distinct
+    /// values of the previous pages would be returned again.
+    pub fn with_search_after(&mut self, cursor: SearchCursor) {
+        self.search_after = Some(cursor)
+    }
+
+    pub fn with_distinct<F>(&mut self, function: F, size: usize)
     where
         F: Fn(DocumentId) -> Option<u64> + 'd,
     {
         self.distinct = Some((Box::new(function), size))
     }
 
+    /// Computes the number of documents matching the query
+    /// for each value of the given facet attributes.
+    pub fn with_facets(&mut self, attributes: Vec<SchemaAttr>) {
+        self.facets = Some(attributes)
+    }
+
     pub fn add_searchable_attribute(&mut self, attribute: u16) {
         let reorders = self.searchable_attrs.get_or_insert_with(ReorderedAttrs::new);
         reorders.insert_attribute(attribute);
@@ -92,16 +153,27 @@ impl<'c, 'f, 'd> QueryBuilder<'c, 'f, 'd> {
         reader: &heed::RoTxn<MainT>,
         query: &str,
         range: Range<usize>,
-    ) -> MResult<Vec<Document>> {
+    ) -> MResult<SortResult> {
+        // the cursor must designate a single position in the
+        // results, the documents are never equally ranked
+        let criteria = self.criteria.with_document_id_tie_break();
+
         match self.distinct {
+            Some(_) if self.search_after.is_some() => Err(Error::SearchCursorWithDistinct),
             Some((distinct, distinct_size)) => bucket_sort_with_distinct(
                 reader,
                 query,
                 range,
+                None,
                 self.filter,
                 distinct,
                 distinct_size,
-                self.criteria,
+                self.facets.as_ref().map(Vec::as_slice),
+                self.timeout,
+                self.ranking_info,
+                self.matching_strategy,
+                self.prefix_search,
+                criteria,
                 self.searchable_attrs,
                 self.main_store,
                 self.postings_lists_store,
@@ -109,13 +181,21 @@ impl<'c, 'f, 'd> QueryBuilder<'c, 'f, 'd> {
                 self.synonyms_store,
                 self.prefix_documents_cache_store,
                 self.prefix_postings_lists_cache_store,
-            ),
+                self.facets_store,
+            )
+            .map(|result| SortResult { cursor: None, ..result }),
             None => bucket_sort(
                 reader,
                 query,
                 range,
+                self.search_after.as_ref(),
                 self.filter,
-                self.criteria,
+                self.facets.as_ref().map(Vec::as_slice),
+                self.timeout,
+                self.ranking_info,
+                self.matching_strategy,
+                self.prefix_search,
+                criteria,
                 self.searchable_attrs,
                 self.main_store,
                 self.postings_lists_store,
@@ -123,28 +203,55 @@ impl<'c, 'f, 'd> QueryBuilder<'c, 'f, 'd> {
                 self.synonyms_store,
                 self.prefix_documents_cache_store,
                 self.prefix_postings_lists_cache_store,
+                self.facets_store,
             ),
         }
     }
+
+    /// Returns the documents that are similar to the given document instead of
+    /// the documents matching a query, the criteria and the facets are ignored.
+    pub fn similar(
+        self,
+        reader: &heed::RoTxn<MainT>,
+        document_id: DocumentId,
+        range: Range<usize>,
+    ) -> MResult<SortResult> {
+        similar_documents(
+            reader,
+            document_id,
+            range,
+            self.filter,
+            self.distinct,
+            self.ranking_info,
+            self.searchable_attrs.as_ref(),
+            self.main_store,
+            self.postings_lists_store,
+            self.docs_words_store,
+        )
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use std::cell::Cell;
     use std::collections::{BTreeSet, HashMap};
     use std::iter::FromIterator;
 
     use fst::{IntoStreamer, Set};
-    use meilisearch_schema::SchemaAttr;
+    use meilisearch_schema::{SchemaAttr, SchemaBuilder, INDEXED};
     use sdset::SetBuf;
     use tempfile::TempDir;
 
-    use crate::DocIndex;
+    use crate::{CustomSeparators, DocIndex, Document, Language, MatchingStrategy, Number, PrefixSearch, TypoTolerance};
+    use crate::autocomplete::{autocomplete, Completion};
     use crate::automaton::normalize_str;
     use crate::bucket_sort::SimpleMatch;
     use crate::database::Database;
+    use crate::stemming::stem_key;
     use crate::store::Index;
+    use crate::suggestions::suggest_query;
 
     fn set_from_stream<'f, I, S>(stream: I) -> Set
     where
@@ -324,8 +431,8 @@ mod tests {
         let reader = db.main_read_txn().unwrap();
 
         let builder = store.query_builder();
-        let results = builder.query(&reader, "iphone from apple", 0..20).unwrap();
-        let mut iter = results.into_iter();
+        let SortResult { documents, .. } = builder.query(&reader, "iphone from apple", 0..20).unwrap();
+        let mut iter = documents.into_iter();
 
         assert_matches!(iter.next(), Some(Document { id: DocumentId(0), matches, .. }) => {
             let mut matches = matches.into_iter();
@@ -347,8 +454,8 @@ mod tests {
         let reader = db.main_read_txn().unwrap();
 
         let builder = store.query_builder();
-        let results = builder.query(&reader, "hello", 0..20).unwrap();
-        let mut iter = results.into_iter();
+        let SortResult { documents, .. } = builder.query(&reader, "hello", 0..20).unwrap();
+        let mut iter = documents.into_iter();
 
         assert_matches!(iter.next(), Some(Document { id: DocumentId(0), matches, .. }) => {
             let mut matches = matches.into_iter();
@@ -358,8 +465,8 @@ mod tests {
         assert_matches!(iter.next(), None);
 
         let builder = store.query_builder();
-        let results = builder.query(&reader, "bonjour", 0..20).unwrap();
-        let mut iter = results.into_iter();
+        let SortResult { documents, .. } = builder.query(&reader, "bonjour", 0..20).unwrap();
+        let mut iter = documents.into_iter();
 
         assert_matches!(iter.next(), Some(Document { id: DocumentId(0), matches, .. }) => {
             let mut matches = matches.into_iter();
@@ -380,8 +487,8 @@ mod tests {
     //     let reader = db.main_read_txn().unwrap();
 
     //     let builder = store.query_builder();
-    //     let results = builder.query(&reader, "sal", 0..20).unwrap();
-    //     let mut iter = results.into_iter();
+    //     let SortResult { documents, .. } = builder.query(&reader, "sal", 0..20).unwrap();
+    //     let mut iter = documents.into_iter();
 
     //     assert_matches!(iter.next(), Some(Document { id: DocumentId(0), matches, .. }) => {
     //         let mut matches = matches.into_iter();
@@ -391,8 +498,8 @@ mod tests {
     //     assert_matches!(iter.next(), None);
 
     //     let builder = store.query_builder();
-    //     let results = builder.query(&reader, "bonj", 0..20).unwrap();
-    //     let mut iter = results.into_iter();
+    //     let SortResult { documents, .. } = builder.query(&reader, "bonj", 0..20).unwrap();
+    //     let mut iter = documents.into_iter();
 
     //     assert_matches!(iter.next(), Some(Document { id: DocumentId(0), matches, .. }) => {
     //         let mut matches = matches.into_iter();
@@ -402,14 +509,14 @@ mod tests {
     //     assert_matches!(iter.next(), None);
 
     //     let builder = store.query_builder();
-    //     let results = builder.query(&reader, "sal blabla", 0..20).unwrap();
-    //     let mut iter = results.into_iter();
+    //     let SortResult { documents, .. } = builder.query(&reader, "sal blabla", 0..20).unwrap();
+    //     let mut iter = documents.into_iter();
 
     //     assert_matches!(iter.next(), None);
 
     //     let builder = store.query_builder();
-    //     let results = builder.query(&reader, "bonj blabla", 0..20).unwrap();
-    //     let mut iter = results.into_iter();
+    //     let SortResult { documents, .. } = builder.query(&reader, "bonj blabla", 0..20).unwrap();
+    //     let mut iter = documents.into_iter();
 
     //     assert_matches!(iter.next(), None);
     // }
@@ -424,8 +531,8 @@ mod tests {
     //     let reader = db.main_read_txn().unwrap();
 
     //     let builder = store.query_builder();
-    //     let results = builder.query(&reader, "salutution", 0..20).unwrap();
-    //     let mut iter = results.into_iter();
+    //     let SortResult { documents, .. } = builder.query(&reader, "salutution", 0..20).unwrap();
+    //     let mut iter = documents.into_iter();
 
     //     assert_matches!(iter.next(), Some(Document { id: DocumentId(0), matches, .. }) => {
     //         let mut matches = matches.into_iter();
@@ -435,8 +542,8 @@ mod tests {
     //     assert_matches!(iter.next(), None);
 
     //     let builder = store.query_builder();
-    //     let results = builder.query(&reader, "saluttion", 0..20).unwrap();
-    //     let mut iter = results.into_iter();
+    //     let SortResult { documents, .. } = builder.query(&reader, "saluttion", 0..20).unwrap();
+    //     let mut iter = documents.into_iter();
 
     //     assert_matches!(iter.next(), Some(Document { id: DocumentId(0), matches, .. }) => {
     //         let mut matches = matches.into_iter();
@@ -462,8 +569,8 @@ mod tests {
         let reader = db.main_read_txn().unwrap();
 
         let builder = store.query_builder();
-        let results = builder.query(&reader, "hello", 0..20).unwrap();
-        let mut iter = results.into_iter();
+        let SortResult { documents, .. } = builder.query(&reader, "hello", 0..20).unwrap();
+        let mut iter = documents.into_iter();
 
         assert_matches!(iter.next(), Some(Document { id: DocumentId(0), matches, .. }) => {
             let mut matches = matches.into_iter();
@@ -483,17 +590,17 @@ mod tests {
         assert_matches!(iter.next(), None);
 
         let builder = store.query_builder();
-        let results = builder.query(&reader, "bonjour", 0..20).unwrap();
-        let mut iter = results.into_iter();
+        let SortResult { documents, .. } = builder.query(&reader, "bonjour", 0..20).unwrap();
+        let mut iter = documents.into_iter();
 
-        assert_matches!(iter.next(), Some(Document { id: DocumentId(0), matches, .. }) => {
+        assert_matches!(iter.next(), Some(Document { id: DocumentId(1), matches, .. }) => {
             let mut matches = matches.into_iter();
-            assert_matches!(matches.next(), Some(SimpleMatch { query_index: 0, word_index: 0, .. }));
+            assert_matches!(matches.next(), Some(SimpleMatch { query_index: 0, word_index: 3, .. }));
             assert_matches!(matches.next(), None);
         });
-        assert_matches!(iter.next(), Some(Document { id: DocumentId(1), matches, .. }) => {
+        assert_matches!(iter.next(), Some(Document { id: DocumentId(0), matches, .. }) => {
             let mut matches = matches.into_iter();
-            assert_matches!(matches.next(), Some(SimpleMatch { query_index: 0, word_index: 3, .. }));
+            assert_matches!(matches.next(), Some(SimpleMatch { query_index: 0, word_index: 0, .. }));
             assert_matches!(matches.next(), None);
         });
         assert_matches!(iter.next(), Some(Document { id: DocumentId(2), matches, .. }) => {
@@ -504,9 +611,14 @@ mod tests {
         assert_matches!(iter.next(), None);
 
         let builder = store.query_builder();
-        let results = builder.query(&reader, "salut", 0..20).unwrap();
-        let mut iter = results.into_iter();
+        let SortResult { documents, .. } = builder.query(&reader, "salut", 0..20).unwrap();
+        let mut iter = documents.into_iter();
 
+        assert_matches!(iter.next(), Some(Document { id: DocumentId(2), matches, .. }) => {
+            let mut matches = matches.into_iter();
+            assert_matches!(matches.next(), Some(SimpleMatch { query_index: 0, word_index: 5, .. }));
+            assert_matches!(matches.next(), None);
+        });
         assert_matches!(iter.next(), Some(Document { id: DocumentId(0), matches, .. }) => {
             let mut matches = matches.into_iter();
             assert_matches!(matches.next(), Some(SimpleMatch { query_index: 0, word_index: 0, .. }));
@@ -517,11 +629,6 @@ mod tests {
             assert_matches!(matches.next(), Some(SimpleMatch { query_index: 0, word_index: 3, .. }));
             assert_matches!(matches.next(), None);
         });
-        assert_matches!(iter.next(), Some(Document { id: DocumentId(2), matches, .. }) => {
-            let mut matches = matches.into_iter();
-            assert_matches!(matches.next(), Some(SimpleMatch { query_index: 0, word_index: 5, .. }));
-            assert_matches!(matches.next(), None);
-        });
         assert_matches!(iter.next(), None);
     }
 
@@ -550,8 +657,8 @@ mod tests {
         let reader = db.main_read_txn().unwrap();
 
         let builder = store.query_builder();
-        let results = builder.query(&reader, "NY subway", 0..20).unwrap();
-        let mut iter = results.into_iter();
+        let SortResult { documents, .. } = builder.query(&reader, "NY subway", 0..20).unwrap();
+        let mut iter = documents.into_iter();
 
         assert_matches!(iter.next(), Some(Document { id: DocumentId(1), matches, .. }) => {
             let mut iter = matches.into_iter();
@@ -572,8 +679,8 @@ mod tests {
         assert_matches!(iter.next(), None);
 
         let builder = store.query_builder();
-        let results = builder.query(&reader, "NYC subway", 0..20).unwrap();
-        let mut iter = results.into_iter();
+        let SortResult { documents, .. } = builder.query(&reader, "NYC subway", 0..20).unwrap();
+        let mut iter = documents.into_iter();
 
         assert_matches!(iter.next(), Some(Document { id: DocumentId(1), matches, .. }) => {
             let mut iter = matches.into_iter();
@@ -614,8 +721,8 @@ mod tests {
         let reader = db.main_read_txn().unwrap();
 
         let builder = store.query_builder();
-        let results = builder.query(&reader, "NY", 0..20).unwrap();
-        let mut iter = results.into_iter();
+        let SortResult { documents, .. } = builder.query(&reader, "NY", 0..20).unwrap();
+        let mut iter = documents.into_iter();
 
         assert_matches!(iter.next(), Some(Document { id: DocumentId(2), matches, .. }) => {
             let mut matches = matches.into_iter();
@@ -638,8 +745,8 @@ mod tests {
         assert_matches!(iter.next(), None);
 
         let builder = store.query_builder();
-        let results = builder.query(&reader, "new york", 0..20).unwrap();
-        let mut iter = results.into_iter();
+        let SortResult { documents, .. } = builder.query(&reader, "new york", 0..20).unwrap();
+        let mut iter = documents.into_iter();
 
         assert_matches!(iter.next(), Some(Document { id: DocumentId(0), matches, .. }) => {
             let mut matches = matches.into_iter();
@@ -672,8 +779,8 @@ mod tests {
         let reader = db.main_read_txn().unwrap();
 
         let builder = store.query_builder();
-        let results = builder.query(&reader, "NY subway", 0..20).unwrap();
-        let mut iter = results.into_iter();
+        let SortResult { documents, .. } = builder.query(&reader, "NY subway", 0..20).unwrap();
+        let mut iter = documents.into_iter();
 
         assert_matches!(iter.next(), Some(Document { id: DocumentId(0), matches, .. }) => {
             let mut matches = matches.into_iter();
@@ -689,8 +796,8 @@ mod tests {
         assert_matches!(iter.next(), None);
 
         let builder = store.query_builder();
-        let results = builder.query(&reader, "new york subway", 0..20).unwrap();
-        let mut iter = results.into_iter();
+        let SortResult { documents, .. } = builder.query(&reader, "new york subway", 0..20).unwrap();
+        let mut iter = documents.into_iter();
 
         assert_matches!(iter.next(), Some(Document { id: DocumentId(1), matches, .. }) => {
             let mut matches = matches.into_iter();
@@ -737,8 +844,8 @@ mod tests {
         let reader = db.main_read_txn().unwrap();
 
         let builder = store.query_builder();
-        let results = builder.query(&reader, "NY subway", 0..20).unwrap();
-        let mut iter = results.into_iter();
+        let SortResult { documents, .. } = builder.query(&reader, "NY subway", 0..20).unwrap();
+        let mut iter = documents.into_iter();
 
         assert_matches!(iter.next(), Some(Document { id: DocumentId(1), matches, .. }) => {
             let mut iter = matches.into_iter();
@@ -759,8 +866,8 @@ mod tests {
         assert_matches!(iter.next(), None);
 
         let builder = store.query_builder();
-        let results = builder.query(&reader, "NYC subway", 0..20).unwrap();
-        let mut iter = results.into_iter();
+        let SortResult { documents, .. } = builder.query(&reader, "NYC subway", 0..20).unwrap();
+        let mut iter = documents.into_iter();
 
         assert_matches!(iter.next(), Some(Document { id: DocumentId(1), matches, .. }) => {
             let mut iter = matches.into_iter();
@@ -812,8 +919,8 @@ mod tests {
         let reader = db.main_read_txn().unwrap();
 
         let builder = store.query_builder();
-        let results = builder.query(&reader, "NY subway broken", 0..20).unwrap();
-        let mut iter = results.into_iter();
+        let SortResult { documents, .. } = builder.query(&reader, "NY subway broken", 0..20).unwrap();
+        let mut iter = documents.into_iter();
 
         assert_matches!(iter.next(), Some(Document { id: DocumentId(0), matches, .. }) => {
             let mut iter = matches.into_iter();
@@ -828,8 +935,8 @@ mod tests {
         assert_matches!(iter.next(), None);
 
         let builder = store.query_builder();
-        let results = builder.query(&reader, "NYC subway", 0..20).unwrap();
-        let mut iter = results.into_iter();
+        let SortResult { documents, .. } = builder.query(&reader, "NYC subway", 0..20).unwrap();
+        let mut iter = documents.into_iter();
 
         assert_matches!(iter.next(), Some(Document { id: DocumentId(1), matches, .. }) => {
             let mut iter = matches.into_iter();
@@ -884,10 +991,10 @@ mod tests {
         let reader = db.main_read_txn().unwrap();
 
         let builder = store.query_builder();
-        let results = builder
+        let SortResult { documents, .. } = builder
             .query(&reader, "new york underground train broken", 0..20)
             .unwrap();
-        let mut iter = results.into_iter();
+        let mut iter = documents.into_iter();
 
         assert_matches!(iter.next(), Some(Document { id: DocumentId(2), matches, .. }) => {
             let mut matches = matches.into_iter();
@@ -914,10 +1021,10 @@ mod tests {
         assert_matches!(iter.next(), None);
 
         let builder = store.query_builder();
-        let results = builder
+        let SortResult { documents, .. } = builder
             .query(&reader, "new york city underground train broken", 0..20)
             .unwrap();
-        let mut iter = results.into_iter();
+        let mut iter = documents.into_iter();
 
         assert_matches!(iter.next(), Some(Document { id: DocumentId(2), matches, .. }) => {
             let mut matches = matches.into_iter();
@@ -958,8 +1065,8 @@ mod tests {
         let reader = db.main_read_txn().unwrap();
 
         let builder = store.query_builder();
-        let results = builder.query(&reader, "new york big ", 0..20).unwrap();
-        let mut iter = results.into_iter();
+        let SortResult { documents, .. } = builder.query(&reader, "new york big ", 0..20).unwrap();
+        let mut iter = documents.into_iter();
 
         assert_matches!(iter.next(), Some(Document { id: DocumentId(0), matches, .. }) => {
             let mut matches = matches.into_iter();
@@ -992,18 +1099,9 @@ mod tests {
         let reader = db.main_read_txn().unwrap();
 
         let builder = store.query_builder();
-        let results = builder.query(&reader, "NY subway ", 0..20).unwrap();
-        let mut iter = results.into_iter();
+        let SortResult { documents, .. } = builder.query(&reader, "NY subway ", 0..20).unwrap();
+        let mut iter = documents.into_iter();
 
-        assert_matches!(iter.next(), Some(Document { id: DocumentId(0), matches, .. }) => {
-            let mut matches = matches.into_iter();
-            assert_matches!(matches.next(), Some(SimpleMatch { query_index: 0, word_index: 0, is_exact: true,  .. })); // new
-            assert_matches!(matches.next(), Some(SimpleMatch { query_index: 1, word_index: 1, is_exact: true,  .. })); // york
-            assert_matches!(matches.next(), Some(SimpleMatch { query_index: 2, word_index: 2, is_exact: false,  .. })); // city
-            assert_matches!(matches.next(), Some(SimpleMatch { query_index: 2, word_index: 2, is_exact: true,  .. })); // city
-            assert_matches!(matches.next(), Some(SimpleMatch { query_index: 4, word_index: 3, is_exact: true,  .. })); // subway
-            assert_matches!(matches.next(), None);
-        });
         assert_matches!(iter.next(), Some(Document { id: DocumentId(2), matches, .. }) => {
             let mut matches = matches.into_iter();
             assert_matches!(matches.next(), Some(SimpleMatch { query_index: 0, word_index: 0, is_exact: true,  .. })); // new
@@ -1023,6 +1121,15 @@ mod tests {
             assert_matches!(matches.next(), Some(SimpleMatch { query_index: 4, word_index: 4, is_exact: true, .. })); // subway
             assert_matches!(matches.next(), None);
         });
+        assert_matches!(iter.next(), Some(Document { id: DocumentId(0), matches, .. }) => {
+            let mut matches = matches.into_iter();
+            assert_matches!(matches.next(), Some(SimpleMatch { query_index: 0, word_index: 0, is_exact: true,  .. })); // new
+            assert_matches!(matches.next(), Some(SimpleMatch { query_index: 1, word_index: 1, is_exact: true,  .. })); // york
+            assert_matches!(matches.next(), Some(SimpleMatch { query_index: 2, word_index: 2, is_exact: false,  .. })); // city
+            assert_matches!(matches.next(), Some(SimpleMatch { query_index: 2, word_index: 2, is_exact: true,  .. })); // city
+            assert_matches!(matches.next(), Some(SimpleMatch { query_index: 4, word_index: 3, is_exact: true,  .. })); // subway
+            assert_matches!(matches.next(), None);
+        });
         assert_matches!(iter.next(), None);
     }
 
@@ -1042,10 +1149,10 @@ mod tests {
         let reader = db.main_read_txn().unwrap();
 
         let builder = store.query_builder();
-        let results = builder
+        let SortResult { documents, .. } = builder
             .query(&reader, "new york city long subway cool ", 0..20)
             .unwrap();
-        let mut iter = results.into_iter();
+        let mut iter = documents.into_iter();
 
         assert_matches!(iter.next(), Some(Document { id: DocumentId(0), matches, .. }) => {
             let mut matches = matches.into_iter();
@@ -1075,8 +1182,8 @@ mod tests {
         let reader = db.main_read_txn().unwrap();
 
         let builder = store.query_builder();
-        let results = builder.query(&reader, "telephone", 0..20).unwrap();
-        let mut iter = results.into_iter();
+        let SortResult { documents, .. } = builder.query(&reader, "telephone", 0..20).unwrap();
+        let mut iter = documents.into_iter();
 
         assert_matches!(iter.next(), Some(Document { id: DocumentId(0), matches, .. }) => {
             let mut iter = matches.into_iter();
@@ -1092,8 +1199,8 @@ mod tests {
         assert_matches!(iter.next(), None);
 
         let builder = store.query_builder();
-        let results = builder.query(&reader, "téléphone", 0..20).unwrap();
-        let mut iter = results.into_iter();
+        let SortResult { documents, .. } = builder.query(&reader, "téléphone", 0..20).unwrap();
+        let mut iter = documents.into_iter();
 
         assert_matches!(iter.next(), Some(Document { id: DocumentId(0), matches, .. }) => {
             let mut iter = matches.into_iter();
@@ -1109,8 +1216,8 @@ mod tests {
         assert_matches!(iter.next(), None);
 
         let builder = store.query_builder();
-        let results = builder.query(&reader, "télephone", 0..20).unwrap();
-        let mut iter = results.into_iter();
+        let SortResult { documents, .. } = builder.query(&reader, "télephone", 0..20).unwrap();
+        let mut iter = documents.into_iter();
 
         assert_matches!(iter.next(), Some(Document { id: DocumentId(1), matches, .. }) => {
             let mut iter = matches.into_iter();
@@ -1136,8 +1243,8 @@ mod tests {
         let reader = db.main_read_txn().unwrap();
 
         let builder = store.query_builder();
-        let results = builder.query(&reader, "i phone case", 0..20).unwrap();
-        let mut iter = results.into_iter();
+        let SortResult { documents, .. } = builder.query(&reader, "i phone case", 0..20).unwrap();
+        let mut iter = documents.into_iter();
 
         assert_matches!(iter.next(), Some(Document { id: DocumentId(0), matches, .. }) => {
             let mut iter = matches.into_iter();
@@ -1165,8 +1272,8 @@ mod tests {
         let reader = db.main_read_txn().unwrap();
 
         let builder = store.query_builder();
-        let results = builder.query(&reader, "searchengine", 0..20).unwrap();
-        let mut iter = results.into_iter();
+        let SortResult { documents, .. } = builder.query(&reader, "searchengine", 0..20).unwrap();
+        let mut iter = documents.into_iter();
 
         assert_matches!(iter.next(), Some(Document { id: DocumentId(0), matches, .. }) => {
             let mut iter = matches.into_iter();
@@ -1205,8 +1312,8 @@ mod tests {
         let reader = db.main_read_txn().unwrap();
 
         let builder = store.query_builder();
-        let results = builder.query(&reader, "searchengine", 0..20).unwrap();
-        let mut iter = results.into_iter();
+        let SortResult { documents, .. } = builder.query(&reader, "searchengine", 0..20).unwrap();
+        let mut iter = documents.into_iter();
 
         assert_matches!(iter.next(), Some(Document { id: DocumentId(0), matches, .. }) => {
             let mut iter = matches.into_iter();
@@ -1237,8 +1344,8 @@ mod tests {
         let reader = db.main_read_txn().unwrap();
 
         let builder = store.query_builder();
-        let results = builder.query(&reader, "searchengine", 0..20).unwrap();
-        let mut iter = results.into_iter();
+        let SortResult { documents, .. } = builder.query(&reader, "searchengine", 0..20).unwrap();
+        let mut iter = documents.into_iter();
 
         assert_matches!(iter.next(), Some(Document { id: DocumentId(0), matches, .. }) => {
             let mut iter = matches.into_iter();
@@ -1254,4 +1361,694 @@ mod tests {
         });
         assert_matches!(iter.next(), None);
     }
+
+    #[test]
+    fn distinct_collapsed() {
+        let store = TempDatabase::from_iter(vec![
+            ("hello", &[doc_index(0, 0), doc_index(1, 0), doc_index(2, 0), doc_index(3, 0)][..]),
+        ]);
+
+        let db = &store.database;
+        let reader = db.main_read_txn().unwrap();
+
+        let mut builder = store.query_builder();
+        builder.with_distinct(|id| Some(id.0 % 2), 1);
+        let SortResult { documents, collapsed, .. } = builder.query(&reader, "hello", 0..20).unwrap();
+        let mut iter = documents.into_iter();
+
+        assert_matches!(iter.next(), Some(Document { id: DocumentId(0), .. }));
+        assert_matches!(iter.next(), Some(Document { id: DocumentId(1), .. }));
+        assert_matches!(iter.next(), None);
+
+        assert_eq!(collapsed.get(&DocumentId(0)), Some(&1));
+        assert_eq!(collapsed.get(&DocumentId(1)), Some(&1));
+    }
+
+    #[test]
+    fn placeholder_query() {
+        let store = TempDatabase::from_iter(vec![
+            ("hello", &[doc_index(2, 0), doc_index(3, 0)][..]),
+            ("world", &[doc_index(0, 0), doc_index(1, 0)][..]),
+        ]);
+
+        let db = &store.database;
+        let reader = db.main_read_txn().unwrap();
+
+        let mut builder = store.query_builder();
+        builder.with_filter(|id| id.0 != 1);
+        let SortResult { documents, .. } = builder.query(&reader, "  ", 0..20).unwrap();
+        let mut iter = documents.into_iter();
+
+        assert_matches!(iter.next(), Some(Document { id: DocumentId(0), matches, .. }) => {
+            assert!(matches.is_empty());
+        });
+        assert_matches!(iter.next(), Some(Document { id: DocumentId(2), .. }));
+        assert_matches!(iter.next(), Some(Document { id: DocumentId(3), .. }));
+        assert_matches!(iter.next(), None);
+    }
+
+    #[test]
+    fn quoted_phrase() {
+        let store = TempDatabase::from_iter(vec![
+            ("new", &[doc_index(0, 0), doc_index(1, 0), doc_index(2, 1)][..]),
+            ("york", &[doc_index(0, 1), doc_index(1, 1), doc_index(2, 0)][..]),
+            ("city", &[doc_index(0, 2), doc_index(1, 3), doc_index(2, 2)][..]),
+        ]);
+
+        let db = &store.database;
+        let reader = db.main_read_txn().unwrap();
+
+        let builder = store.query_builder();
+        let SortResult { documents, .. } = builder.query(&reader, "\"new york city\"", 0..20).unwrap();
+        let mut iter = documents.into_iter();
+
+        assert_matches!(iter.next(), Some(Document { id: DocumentId(0), matches, .. }) => {
+            let mut matches = matches.into_iter();
+            assert_matches!(matches.next(), Some(SimpleMatch { query_index: 0, word_index: 0, .. }));
+            assert_matches!(matches.next(), Some(SimpleMatch { query_index: 1, word_index: 1, .. }));
+            assert_matches!(matches.next(), Some(SimpleMatch { query_index: 2, word_index: 2, .. }));
+            assert_matches!(matches.next(), None);
+        });
+        assert_matches!(iter.next(), None);
+
+        let builder = store.query_builder();
+        let SortResult { documents, .. } = builder.query(&reader, "\"new york\" city", 0..20).unwrap();
+        let mut iter = documents.into_iter();
+
+        assert_matches!(iter.next(), Some(Document { id: DocumentId(0), .. }));
+        assert_matches!(iter.next(), Some(Document { id: DocumentId(1), .. }));
+        assert_matches!(iter.next(), None);
+    }
+
+    #[test]
+    fn quoted_phrase_with_stop_words() {
+        // the stop words are not indexed but are counted in the words positions
+        let store = TempDatabase::from_iter(vec![
+            ("lord", &[doc_index(0, 1), doc_index(1, 0), doc_index(2, 1)][..]),
+            ("rings", &[doc_index(0, 4), doc_index(1, 1), doc_index(2, 3)][..]),
+        ]);
+
+        let db = &store.database;
+        let mut writer = db.main_write_txn().unwrap();
+        let stop_words = Set::from_iter(vec!["of", "the"]).unwrap();
+        store.index.main.put_stop_words_fst(&mut writer, &stop_words).unwrap();
+        writer.commit().unwrap();
+
+        let reader = db.main_read_txn().unwrap();
+
+        let builder = store.query_builder();
+        let SortResult { documents, .. } = builder.query(&reader, "\"the lord of the rings\"", 0..20).unwrap();
+        let mut iter = documents.into_iter();
+
+        assert_matches!(iter.next(), Some(Document { id: DocumentId(0), matches, .. }) => {
+            let mut matches = matches.into_iter();
+            assert_matches!(matches.next(), Some(SimpleMatch { query_index: 1, word_index: 1, .. }));
+            assert_matches!(matches.next(), Some(SimpleMatch { query_index: 4, word_index: 4, .. }));
+            assert_matches!(matches.next(), None);
+        });
+        assert_matches!(iter.next(), None);
+    }
+
+    #[test]
+    fn excluded_words() {
+        let store = TempDatabase::from_iter(vec![
+            ("jaguar", &[doc_index(0, 0), doc_index(1, 0), doc_index(2, 0)][..]),
+            ("car", &[doc_index(0, 1), doc_index(3, 0)][..]),
+            ("cat", &[doc_index(1, 1)][..]),
+        ]);
+
+        let db = &store.database;
+        let reader = db.main_read_txn().unwrap();
+
+        let builder = store.query_builder();
+        let SortResult { documents, .. } = builder.query(&reader, "jaguar -car", 0..20).unwrap();
+        let mut iter = documents.into_iter();
+
+        assert_matches!(iter.next(), Some(Document { id: DocumentId(1), matches, .. }) => {
+            let mut matches = matches.into_iter();
+            assert_matches!(matches.next(), Some(SimpleMatch { query_index: 0, word_index: 0, .. }));
+            assert_matches!(matches.next(), None);
+        });
+        assert_matches!(iter.next(), Some(Document { id: DocumentId(2), .. }));
+        assert_matches!(iter.next(), None);
+
+        // negated words are not typo tolerant
+        let builder = store.query_builder();
+        let SortResult { documents, .. } = builder.query(&reader, "jaguar -cat", 0..20).unwrap();
+        let mut iter = documents.into_iter();
+
+        assert_matches!(iter.next(), Some(Document { id: DocumentId(0), .. }));
+        assert_matches!(iter.next(), Some(Document { id: DocumentId(2), .. }));
+        assert_matches!(iter.next(), None);
+    }
+
+    #[test]
+    fn only_excluded_words() {
+        let store = TempDatabase::from_iter(vec![
+            ("jaguar", &[doc_index(0, 0), doc_index(1, 0), doc_index(2, 0)][..]),
+            ("car", &[doc_index(0, 1), doc_index(3, 0)][..]),
+            ("cat", &[doc_index(1, 1)][..]),
+        ]);
+
+        let db = &store.database;
+        let reader = db.main_read_txn().unwrap();
+
+        // the query is a placeholder query without the excluded documents
+        let builder = store.query_builder();
+        let SortResult { documents, nb_hits, .. } = builder.query(&reader, "-car", 0..20).unwrap();
+        let mut iter = documents.into_iter();
+
+        assert_matches!(iter.next(), Some(Document { id: DocumentId(1), matches, .. }) => {
+            assert!(matches.is_empty());
+        });
+        assert_matches!(iter.next(), Some(Document { id: DocumentId(2), .. }));
+        assert_matches!(iter.next(), None);
+        assert_eq!(nb_hits, 2);
+    }
+
+    #[test]
+    fn nb_hits() {
+        let store = TempDatabase::from_iter(vec![
+            ("hello", &[doc_index(0, 0), doc_index(1, 0), doc_index(2, 0), doc_index(3, 0)][..]),
+        ]);
+
+        let db = &store.database;
+        let reader = db.main_read_txn().unwrap();
+
+        let builder = store.query_builder();
+        let SortResult { documents, nb_hits, exhaustive_nb_hits, .. } = builder.query(&reader, "hello", 0..1).unwrap();
+        assert_eq!(documents.len(), 1);
+        assert_eq!(nb_hits, 4);
+        assert!(exhaustive_nb_hits);
+
+        let mut builder = store.query_builder();
+        builder.with_filter(|id| id.0 % 2 == 0);
+        let SortResult { documents, nb_hits, exhaustive_nb_hits, .. } = builder.query(&reader, "hello", 0..1).unwrap();
+        assert_eq!(documents.len(), 1);
+        assert_eq!(nb_hits, 2);
+        assert!(exhaustive_nb_hits);
+
+        let mut builder = store.query_builder();
+        builder.with_distinct(|id| Some(id.0 % 3), 1);
+        let SortResult { nb_hits, .. } = builder.query(&reader, "hello", 0..1).unwrap();
+        assert_eq!(nb_hits, 3);
+    }
+
+    #[test]
+    fn nb_hits_estimated() {
+        let indexes: Vec<_> = (0..20).map(|id| doc_index(id, 0)).collect();
+        let store = TempDatabase::from_iter(vec![("hello", &indexes[..])]);
+
+        let db = &store.database;
+        let reader = db.main_read_txn().unwrap();
+
+        // once the timeout is reached, only the candidates needed to return the
+        // requested documents are checked and the count is estimated from them
+        let calls = Cell::new(0);
+        let mut builder = store.query_builder();
+        builder.with_filter(|_| { calls.set(calls.get() + 1); true });
+        builder.with_fetch_timeout(Duration::from_secs(0));
+        let SortResult { documents, nb_hits, exhaustive_nb_hits, .. } = builder.query(&reader, "hello", 0..1).unwrap();
+        assert_eq!(documents.len(), 1);
+        assert_eq!(nb_hits, 20);
+        assert!(!exhaustive_nb_hits);
+        assert!(calls.get() < 20);
+    }
+
+    #[test]
+    fn ranking_info() {
+        let store = TempDatabase::from_iter(vec![
+            ("hello", &[doc_index(0, 0), doc_index(1, 2)][..]),
+            ("world", &[doc_index(0, 1)][..]),
+        ]);
+
+        let db = &store.database;
+        let reader = db.main_read_txn().unwrap();
+
+        let mut builder = store.query_builder();
+        builder.with_ranking_info();
+        let SortResult { documents, .. } = builder.query(&reader, "hello world", 0..20).unwrap();
+        let mut iter = documents.into_iter();
+
+        assert_matches!(iter.next(), Some(Document { id: DocumentId(0), ranking_info: Some(info), .. }) => {
+            let info: HashMap<_, _> = info.into_iter().collect();
+            // the typo score of two words without typos followed by the two original words
+            assert_eq!(info.get("typo"), Some(&Number::Unsigned(2000 << 32 | 2)));
+            assert_eq!(info.get("words"), Some(&Number::Unsigned(2)));
+            assert_eq!(info.get("proximity"), Some(&Number::Unsigned(1)));
+            assert_eq!(info.get("stable document id"), Some(&Number::Unsigned(0)));
+        });
+        assert_matches!(iter.next(), Some(Document { id: DocumentId(1), ranking_info: Some(info), .. }) => {
+            let info: HashMap<_, _> = info.into_iter().collect();
+            assert_eq!(info.get("words"), Some(&Number::Unsigned(1)));
+            assert_eq!(info.get("words position"), Some(&Number::Unsigned(2)));
+        });
+        assert_matches!(iter.next(), None);
+
+        let builder = store.query_builder();
+        let SortResult { documents, .. } = builder.query(&reader, "hello world", 0..20).unwrap();
+        assert!(documents.iter().all(|d| d.ranking_info.is_none()));
+    }
+
+    #[test]
+    fn search_after() {
+        let store = TempDatabase::from_iter(vec![
+            ("hello", &[doc_index(0, 0), doc_index(1, 0), doc_index(2, 0), doc_index(3, 1), doc_index(4, 1)][..]),
+        ]);
+
+        let db = &store.database;
+        let reader = db.main_read_txn().unwrap();
+
+        let builder = store.query_builder();
+        let SortResult { documents, cursor, .. } = builder.query(&reader, "hello", 0..2).unwrap();
+        let ids: Vec<_> = documents.iter().map(|d| d.id).collect();
+        assert_eq!(ids, vec![DocumentId(0), DocumentId(1)]);
+
+        // the documents that are equal for all the criteria are not returned twice
+        let cursor = cursor.unwrap();
+        let mut builder = store.query_builder();
+        builder.with_search_after(cursor.clone());
+        let SortResult { documents, .. } = builder.query(&reader, "hello", 0..2).unwrap();
+        let ids: Vec<_> = documents.iter().map(|d| d.id).collect();
+        assert_eq!(ids, vec![DocumentId(2), DocumentId(3)]);
+
+        let mut builder = store.query_builder();
+        builder.with_search_after(cursor.clone());
+        builder.with_filter(|id| id.0 != 3);
+        let SortResult { documents, .. } = builder.query(&reader, "hello", 0..20).unwrap();
+        let ids: Vec<_> = documents.iter().map(|d| d.id).collect();
+        assert_eq!(ids, vec![DocumentId(2), DocumentId(4)]);
+
+        // the total number of hits is returned, not only the ones after the cursor
+        let mut builder = store.query_builder();
+        builder.with_search_after(cursor.clone());
+        builder.with_filter(|_| true);
+        let SortResult { documents, nb_hits, .. } = builder.query(&reader, "hello", 0..20).unwrap();
+        assert_eq!(documents.len(), 3);
+        assert_eq!(nb_hits, 5);
+
+        // the search is resumed even if the cursor document does not match anymore
+        let mut builder = store.query_builder();
+        builder.with_search_after(cursor.clone());
+        builder.with_filter(|id| id.0 != 1);
+        let SortResult { documents, .. } = builder.query(&reader, "hello", 0..20).unwrap();
+        let ids: Vec<_> = documents.iter().map(|d| d.id).collect();
+        assert_eq!(ids, vec![DocumentId(2), DocumentId(3), DocumentId(4)]);
+
+        // the search can not be resumed with other criteria
+        let mut builder = store.query_builder();
+        builder.with_search_after(SearchCursor { document_id: DocumentId(1), values: Vec::new() });
+        let result = builder.query(&reader, "hello", 0..20);
+        assert_matches!(result, Err(Error::SearchCursorCriteriaDiffer));
+
+        let mut builder = store.query_builder();
+        builder.with_search_after(cursor);
+        builder.with_distinct(|id| Some(id.0 % 2), 1);
+        let result = builder.query(&reader, "hello", 0..20);
+        assert_matches!(result, Err(Error::SearchCursorWithDistinct));
+    }
+
+    #[test]
+    fn typo_tolerance() {
+        let store = TempDatabase::from_iter(vec![
+            ("hello", &[doc_attr_index(0, 0, 0)][..]),
+            ("hallo", &[doc_attr_index(1, 0, 0), doc_attr_index(2, 1, 0)][..]),
+            ("2019", &[doc_attr_index(3, 0, 0)][..]),
+        ]);
+
+        let db = &store.database;
+
+        let mut builder = SchemaBuilder::with_identifier("id");
+        let title = builder.new_attribute("title", INDEXED);
+        let sku = builder.new_attribute("sku", INDEXED);
+        assert_eq!((title, sku), (SchemaAttr(0), SchemaAttr(1)));
+
+        let mut writer = db.main_write_txn().unwrap();
+        store.index.main.put_schema(&mut writer, &builder.build()).unwrap();
+        writer.commit().unwrap();
+
+        let reader = db.main_read_txn().unwrap();
+        let builder = store.query_builder();
+        let SortResult { documents, .. } = builder.query(&reader, "hello", 0..20).unwrap();
+        assert_eq!(documents.iter().map(|d| d.id.0).collect::<Vec<_>>(), vec![0, 1, 2]);
+        drop(reader);
+
+        let set_typo_tolerance = |typo_tolerance: TypoTolerance| {
+            let mut writer = db.main_write_txn().unwrap();
+            store.index.main.put_typo_tolerance(&mut writer, &typo_tolerance).unwrap();
+            writer.commit().unwrap();
+        };
+
+        set_typo_tolerance(TypoTolerance { disable_on_attributes: vec!["sku".to_string()], ..TypoTolerance::default() });
+        let reader = db.main_read_txn().unwrap();
+        let builder = store.query_builder();
+        let SortResult { documents, .. } = builder.query(&reader, "hello", 0..20).unwrap();
+        assert_eq!(documents.iter().map(|d| d.id.0).collect::<Vec<_>>(), vec![0, 1]);
+        drop(reader);
+
+        set_typo_tolerance(TypoTolerance { min_word_len_one_typo: 6, ..TypoTolerance::default() });
+        let reader = db.main_read_txn().unwrap();
+        let builder = store.query_builder();
+        let SortResult { documents, .. } = builder.query(&reader, "hello", 0..20).unwrap();
+        assert_eq!(documents.iter().map(|d| d.id.0).collect::<Vec<_>>(), vec![0]);
+        drop(reader);
+
+        set_typo_tolerance(TypoTolerance { enabled: false, ..TypoTolerance::default() });
+        let reader = db.main_read_txn().unwrap();
+        let builder = store.query_builder();
+        let SortResult { documents, .. } = builder.query(&reader, "hallo", 0..20).unwrap();
+        assert_eq!(documents.iter().map(|d| d.id.0).collect::<Vec<_>>(), vec![1, 2]);
+        drop(reader);
+
+        set_typo_tolerance(TypoTolerance { min_word_len_one_typo: 1, disable_on_numbers: true, ..TypoTolerance::default() });
+        let reader = db.main_read_txn().unwrap();
+        let builder = store.query_builder();
+        let SortResult { documents, .. } = builder.query(&reader, "2018", 0..20).unwrap();
+        assert!(documents.is_empty());
+    }
+
+    #[test]
+    fn matching_strategy() {
+        let store = TempDatabase::from_iter(vec![
+            ("hello", &[doc_index(0, 0), doc_index(1, 0), doc_index(2, 0)][..]),
+            ("world", &[doc_index(3, 0), doc_index(4, 0)][..]),
+            ("kitty", &[doc_index(0, 1)][..]),
+        ]);
+
+        let db = &store.database;
+        let reader = db.main_read_txn().unwrap();
+
+        let builder = store.query_builder();
+        let SortResult { documents, .. } = builder.query(&reader, "hello world", 0..20).unwrap();
+        assert!(documents.is_empty());
+
+        // no word is ignored when some documents contain all of them
+        let mut builder = store.query_builder();
+        builder.with_matching_strategy(MatchingStrategy::Last);
+        let SortResult { documents, .. } = builder.query(&reader, "hello kitty", 0..20).unwrap();
+        assert_eq!(documents.iter().map(|d| d.id.0).collect::<Vec<_>>(), vec![0]);
+
+        // "world" is the last word, it is ignored first
+        let mut builder = store.query_builder();
+        builder.with_matching_strategy(MatchingStrategy::Last);
+        let SortResult { documents, .. } = builder.query(&reader, "hello world", 0..20).unwrap();
+        assert_eq!(documents.iter().map(|d| d.id.0).collect::<Vec<_>>(), vec![0, 1, 2]);
+
+        // "hello" is the most frequent word, it is ignored first
+        let mut builder = store.query_builder();
+        builder.with_matching_strategy(MatchingStrategy::Frequency);
+        let SortResult { documents, .. } = builder.query(&reader, "hello world", 0..20).unwrap();
+        assert_eq!(documents.iter().map(|d| d.id.0).collect::<Vec<_>>(), vec![3, 4]);
+
+        // the quoted words are never ignored
+        let mut builder = store.query_builder();
+        builder.with_matching_strategy(MatchingStrategy::Last);
+        let SortResult { documents, .. } = builder.query(&reader, "hello \"world\"", 0..20).unwrap();
+        assert_eq!(documents.iter().map(|d| d.id.0).collect::<Vec<_>>(), vec![3, 4]);
+    }
+
+    #[test]
+    fn prefix_search() {
+        let store = TempDatabase::from_iter(vec![
+            ("hello", &[doc_index(0, 0)][..]),
+            ("world", &[doc_index(0, 1)][..]),
+        ]);
+
+        let db = &store.database;
+        let reader = db.main_read_txn().unwrap();
+
+        // the short prefixes are found even without the prefix postings lists cache
+        let builder = store.query_builder();
+        let SortResult { documents, .. } = builder.query(&reader, "he", 0..20).unwrap();
+        assert_eq!(documents.iter().map(|d| d.id.0).collect::<Vec<_>>(), vec![0]);
+
+        let builder = store.query_builder();
+        let SortResult { documents, .. } = builder.query(&reader, "hel wor", 0..20).unwrap();
+        assert!(documents.is_empty());
+
+        let mut builder = store.query_builder();
+        builder.with_prefix_search(PrefixSearch::All);
+        let SortResult { documents, .. } = builder.query(&reader, "hel wor", 0..20).unwrap();
+        assert_eq!(documents.iter().map(|d| d.id.0).collect::<Vec<_>>(), vec![0]);
+        drop(reader);
+
+        let mut writer = db.main_write_txn().unwrap();
+        store.index.main.put_prefix_search(&mut writer, PrefixSearch::Disabled).unwrap();
+        writer.commit().unwrap();
+
+        let reader = db.main_read_txn().unwrap();
+        let builder = store.query_builder();
+        let SortResult { documents, .. } = builder.query(&reader, "hel", 0..20).unwrap();
+        assert!(documents.is_empty());
+
+        let mut builder = store.query_builder();
+        builder.with_prefix_search(PrefixSearch::Last);
+        let SortResult { documents, .. } = builder.query(&reader, "hel", 0..20).unwrap();
+        assert_eq!(documents.iter().map(|d| d.id.0).collect::<Vec<_>>(), vec![0]);
+    }
+
+    #[test]
+    fn original_words_before_synonyms() {
+        let mut store = TempDatabase::from_iter(vec![
+            ("automobile", &[doc_index(0, 0)][..]),
+            ("red", &[doc_index(0, 1)][..]),
+            ("red", &[doc_index(1, 0)][..]),
+            ("car", &[doc_index(1, 4)][..]),
+        ]);
+
+        store.add_synonym("car", SetBuf::from_dirty(vec!["automobile"]));
+
+        let db = &store.database;
+        let reader = db.main_read_txn().unwrap();
+
+        // the synonym is better placed but "car" is what the user typed
+        let builder = store.query_builder();
+        let SortResult { documents, .. } = builder.query(&reader, "car red", 0..20).unwrap();
+        assert_eq!(documents.iter().map(|d| d.id.0).collect::<Vec<_>>(), vec![1, 0]);
+    }
+
+    #[test]
+    fn stemmed_words() {
+        let store = TempDatabase::from_iter(vec![
+            ("run", &[doc_index(0, 0)][..]),
+            ("runs", &[doc_index(1, 0)][..]),
+            ("running", &[doc_index(2, 0)][..]),
+        ]);
+
+        let db = &store.database;
+        let reader = db.main_read_txn().unwrap();
+
+        let builder = store.query_builder();
+        let SortResult { documents, .. } = builder.query(&reader, "running", 0..20).unwrap();
+        assert_eq!(documents.iter().map(|d| d.id.0).collect::<Vec<_>>(), vec![2]);
+        drop(reader);
+
+        let mut writer = db.main_write_txn().unwrap();
+        let stems = SetBuf::from_dirty(vec![doc_index(0, 0), doc_index(1, 0), doc_index(2, 0)]);
+        store.index.postings_lists.put_postings_list(&mut writer, &stem_key("run"), &stems).unwrap();
+        store.index.main.put_stemming_language(&mut writer, Language::English).unwrap();
+        writer.commit().unwrap();
+
+        // the document containing the word typed by the user is ranked first
+        let reader = db.main_read_txn().unwrap();
+        let builder = store.query_builder();
+        let SortResult { documents, .. } = builder.query(&reader, "running", 0..20).unwrap();
+        let mut ids: Vec<_> = documents.iter().map(|d| d.id.0).collect();
+        assert_eq!(ids.remove(0), 2);
+        ids.sort_unstable();
+        assert_eq!(ids, vec![0, 1]);
+    }
+
+    #[test]
+    fn stemmed_words_own_stem() {
+        let store = TempDatabase::from_iter(vec![
+            ("runs", &[doc_index(0, 0)][..]),
+        ]);
+
+        let db = &store.database;
+        let reader = db.main_read_txn().unwrap();
+
+        let mut builder = store.query_builder();
+        builder.with_prefix_search(PrefixSearch::Disabled);
+        let SortResult { documents, .. } = builder.query(&reader, "run", 0..20).unwrap();
+        assert!(documents.is_empty());
+        drop(reader);
+
+        let mut writer = db.main_write_txn().unwrap();
+        let stems = SetBuf::from_dirty(vec![doc_index(0, 0)]);
+        store.index.postings_lists.put_postings_list(&mut writer, &stem_key("run"), &stems).unwrap();
+        store.index.main.put_stemming_language(&mut writer, Language::English).unwrap();
+        writer.commit().unwrap();
+
+        // "run" is its own stem, it finds the documents containing "runs"
+        let reader = db.main_read_txn().unwrap();
+        let mut builder = store.query_builder();
+        builder.with_prefix_search(PrefixSearch::Disabled);
+        let SortResult { documents, .. } = builder.query(&reader, "run", 0..20).unwrap();
+        assert_eq!(documents.iter().map(|d| d.id.0).collect::<Vec<_>>(), vec![0]);
+    }
+
+    #[test]
+    fn custom_separators() {
+        let store = TempDatabase::from_iter(vec![
+            ("node.js", &[doc_index(0, 0)][..]),
+            ("node", &[doc_index(1, 0)][..]),
+            ("js", &[doc_index(1, 1)][..]),
+        ]);
+
+        let db = &store.database;
+        let reader = db.main_read_txn().unwrap();
+
+        let builder = store.query_builder();
+        let SortResult { documents, .. } = builder.query(&reader, "node.js", 0..20).unwrap();
+        assert_eq!(documents.iter().map(|d| d.id.0).collect::<Vec<_>>(), vec![1]);
+        drop(reader);
+
+        let mut separators = CustomSeparators::default();
+        separators.non_separators.insert('.');
+
+        let mut writer = db.main_write_txn().unwrap();
+        store.index.main.put_separators(&mut writer, &separators).unwrap();
+        writer.commit().unwrap();
+
+        let reader = db.main_read_txn().unwrap();
+        let builder = store.query_builder();
+        let SortResult { documents, .. } = builder.query(&reader, "node.js", 0..20).unwrap();
+        assert_eq!(documents.iter().map(|d| d.id.0).collect::<Vec<_>>(), vec![0]);
+    }
+
+    #[test]
+    fn suggested_query() {
+        let store = TempDatabase::from_iter(vec![
+            ("hello", &[doc_index(0, 0), doc_index(1, 0), doc_index(2, 0)][..]),
+            ("hallo", &[doc_index(3, 0)][..]),
+            ("world", &[doc_index(0, 1)][..]),
+            ("earthquakes", &[doc_index(4, 0)][..]),
+        ]);
+
+        let db = &store.database;
+        let reader = db.main_read_txn().unwrap();
+        let main = store.index.main;
+        let postings_lists = store.index.postings_lists;
+
+        // the closest words found in the most documents are suggested
+        let suggestion = suggest_query(&reader, main, postings_lists, "hullo wrld").unwrap();
+        assert_eq!(suggestion, Some(String::from("hello world")));
+
+        let suggestion = suggest_query(&reader, main, postings_lists, "hallo wrld").unwrap();
+        assert_eq!(suggestion, Some(String::from("hallo world")));
+
+        let suggestion = suggest_query(&reader, main, postings_lists, "hello world").unwrap();
+        assert_eq!(suggestion, None);
+
+        // the double quotes and the negated words are kept
+        let suggestion = suggest_query(&reader, main, postings_lists, "\"hullo wrld\" -hallo").unwrap();
+        assert_eq!(suggestion, Some(String::from("\"hello world\" -hallo")));
+
+        // the words that allow one typo are corrected with two
+        let suggestion = suggest_query(&reader, main, postings_lists, "hxllp").unwrap();
+        assert_eq!(suggestion, Some(String::from("hello")));
+
+        // the words are never corrected with more than two typos
+        let suggestion = suggest_query(&reader, main, postings_lists, "eartquaxez").unwrap();
+        assert_eq!(suggestion, None);
+    }
+
+    #[test]
+    fn autocompletion() {
+        let store = TempDatabase::from_iter(vec![
+            ("new", &[doc_index(0, 0), doc_index(1, 0), doc_index(2, 0)][..]),
+            ("york", &[doc_index(0, 1), doc_index(1, 1)][..]),
+            ("yorkshire", &[doc_index(3, 0), doc_index(4, 0), doc_index(5, 0)][..]),
+            ("young", &[doc_index(2, 1)][..]),
+        ]);
+
+        let db = &store.database;
+        let reader = db.main_read_txn().unwrap();
+        let main = store.index.main;
+        let postings_lists = store.index.postings_lists;
+        let no_filter = None::<fn(DocumentId) -> bool>;
+
+        let completions = autocomplete(&reader, main, postings_lists, "yo", no_filter, 2).unwrap();
+        assert_eq!(completions, vec![
+            Completion { completion: String::from("yorkshire"), nb_documents: 3 },
+            Completion { completion: String::from("york"), nb_documents: 2 },
+        ]);
+
+        // only the documents containing the previous words are counted
+        let completions = autocomplete(&reader, main, postings_lists, "new yo", no_filter, 10).unwrap();
+        assert_eq!(completions, vec![
+            Completion { completion: String::from("new york"), nb_documents: 2 },
+            Completion { completion: String::from("new young"), nb_documents: 1 },
+        ]);
+
+        let filter = Some(|id: DocumentId| id.0 != 0);
+        let completions = autocomplete(&reader, main, postings_lists, "new yo", filter, 10).unwrap();
+        assert_eq!(completions, vec![
+            Completion { completion: String::from("new york"), nb_documents: 1 },
+            Completion { completion: String::from("new young"), nb_documents: 1 },
+        ]);
+
+        // the prefixes of a single character are not completed
+        let completions = autocomplete(&reader, main, postings_lists, "new y", no_filter, 10).unwrap();
+        assert!(completions.is_empty());
+    }
+
+    #[test]
+    fn similar() {
+        let store = TempDatabase::from_iter(vec![
+            ("jaguar", &[doc_index(0, 0), doc_index(1, 0), doc_index(2, 0)][..]),
+            ("car", &[doc_index(0, 1), doc_index(3, 0)][..]),
+            ("the", &[doc_index(0, 2), doc_index(1, 1), doc_index(2, 1), doc_index(3, 1), doc_index(4, 0)][..]),
+            ("unique", &[doc_index(0, 3)][..]),
+        ]);
+
+        let db = &store.database;
+        let mut writer = db.main_write_txn().unwrap();
+        let words = Set::from_iter(vec!["car", "jaguar", "the", "unique"]).unwrap();
+        store.index.docs_words.put_doc_words(&mut writer, DocumentId(0), &words).unwrap();
+        store.index.main.put_number_of_documents(&mut writer, |_| 5).unwrap();
+        writer.commit().unwrap();
+
+        let reader = db.main_read_txn().unwrap();
+
+        // the rarest shared words are the most important ones
+        let builder = store.query_builder();
+        let SortResult { documents, nb_hits, .. } = builder.similar(&reader, DocumentId(0), 0..20).unwrap();
+        assert_eq!(documents.iter().map(|d| d.id.0).collect::<Vec<_>>(), vec![3, 1, 2]);
+        assert_eq!(nb_hits, 3);
+
+        let mut builder = store.query_builder();
+        builder.with_filter(|id| id.0 != 3);
+        let SortResult { documents, .. } = builder.similar(&reader, DocumentId(0), 0..20).unwrap();
+        assert_eq!(documents.iter().map(|d| d.id.0).collect::<Vec<_>>(), vec![1, 2]);
+
+        let mut builder = store.query_builder();
+        builder.with_distinct(|id| Some(id.0 % 2), 1);
+        let SortResult { documents, .. } = builder.similar(&reader, DocumentId(0), 0..20).unwrap();
+        assert_eq!(documents.iter().map(|d| d.id.0).collect::<Vec<_>>(), vec![3, 2]);
+    }
+
+    #[test]
+    fn similar_accented_words() {
+        let store = TempDatabase::from_iter(vec![
+            ("café", &[doc_index(0, 0), doc_index(1, 0)][..]),
+            ("cafe", &[doc_index(0, 0), doc_index(1, 0), doc_index(2, 0)][..]),
+            ("tea", &[doc_index(0, 1), doc_index(3, 0)][..]),
+        ]);
+
+        let db = &store.database;
+        let mut writer = db.main_write_txn().unwrap();
+        let words = Set::from_iter(vec!["cafe", "café", "tea"]).unwrap();
+        store.index.docs_words.put_doc_words(&mut writer, DocumentId(0), &words).unwrap();
+        store.index.main.put_number_of_documents(&mut writer, |_| 5).unwrap();
+        writer.commit().unwrap();
+
+        let reader = db.main_read_txn().unwrap();
+
+        // the accented word is only weighted once, under its unidecoded form
+        let builder = store.query_builder();
+        let SortResult { documents, .. } = builder.similar(&reader, DocumentId(0), 0..20).unwrap();
+        assert_eq!(documents.iter().map(|d| d.id.0).collect::<Vec<_>>(), vec![3, 1, 2]);
+    }
 }
@@ -7,19 +7,30 @@ use std::{cmp, fmt, iter::once};
 
 use fst::{IntoStreamer, Streamer};
 use itertools::{EitherOrBoth, merge_join_by};
-use meilisearch_tokenizer::split_query_string;
+use meilisearch_schema::SchemaAttr;
+use meilisearch_tokenizer::{split_query_string, Tokenizer};
 use sdset::{Set, SetBuf, SetOperation};
+use serde::{Deserialize, Serialize};
+use slice_group_by::GroupBy;
 use log::debug;
 
 use crate::database::MainT;
-use crate::{store, DocumentId, DocIndex, MResult};
+use crate::{store, DocumentId, DocIndex, MResult, Stemmer, TypoTolerance};
 use crate::automaton::{normalize_str, build_dfa, build_prefix_dfa, build_exact_dfa};
-use crate::QueryWordsMapper;
+use crate::stemming::stem_key;
+use crate::tokenizers::ArcTokenizer;
+use crate::{QueryWordsMapper, WordOrigin};
 
 #[derive(Clone, PartialEq, Eq, Hash)]
 pub enum Operation {
     And(Vec<Operation>),
     Or(Vec<Operation>),
+    /// The documents matching this operation are removed
+    /// from the documents of the parent `And` operation.
+    Not(Box<Operation>),
+    /// The documents of the first operation that matches documents,
+    /// the next operations are only evaluated if it matches none.
+    Fallback(Vec<Operation>),
     Query(Query),
 }
 
@@ -35,6 +46,14 @@ impl fmt::Debug for Operation {
                     writeln!(f, "{:1$}OR", "", depth * 2)?;
                     children.iter().try_for_each(|c| pprint_tree(f, c, depth + 1))
                 },
+                Operation::Not(child) => {
+                    writeln!(f, "{:1$}NOT", "", depth * 2)?;
+                    pprint_tree(f, child, depth + 1)
+                },
+                Operation::Fallback(children) => {
+                    writeln!(f, "{:1$}FALLBACK", "", depth * 2)?;
+                    children.iter().try_for_each(|c| pprint_tree(f, c, depth + 1))
+                },
                 Operation::Query(query) => writeln!(f, "{:2$}{:?}", "", query, depth * 2),
             }
         }
@@ -52,10 +71,19 @@ impl Operation {
         Operation::Query(Query { id, prefix, exact: true, kind: QueryKind::NonTolerant(s.to_string()) })
     }
 
+    fn stem(id: QueryId, s: &str) -> Operation {
+        Operation::Query(Query { id, prefix: false, exact: false, kind: QueryKind::Stem(s.to_string()) })
+    }
+
     fn phrase2(id: QueryId, prefix: bool, (left, right): (&str, &str)) -> Operation {
         let kind = QueryKind::Phrase(vec![left.to_owned(), right.to_owned()]);
         Operation::Query(Query { id, prefix, exact: true, kind })
     }
+
+    fn quoted_phrase(id: QueryId, words: &[(usize, String)]) -> Operation {
+        let kind = QueryKind::QuotedPhrase(words.iter().map(|(_, w)| w.clone()).collect());
+        Operation::Query(Query { id, prefix: false, exact: true, kind })
+    }
 }
 
 pub type QueryId = usize;
@@ -85,7 +113,12 @@ impl Hash for Query {
 pub enum QueryKind {
     Tolerant(String),
     NonTolerant(String),
+    /// The stem of a query word, it is searched in the stems of the documents words.
+    Stem(String),
     Phrase(Vec<String>),
+    /// The words written between double quotes, the matches of each word are
+    /// given the query id of the phrase plus the position of the word in it.
+    QuotedPhrase(Vec<String>),
 }
 
 impl fmt::Debug for Query {
@@ -99,9 +132,15 @@ impl fmt::Debug for Query {
             QueryKind::Tolerant(word) => {
                 f.debug_struct(&(prefix + "Tolerant")).field("id", &id).field("word", &word).finish()
             },
+            QueryKind::Stem(stem) => {
+                f.debug_struct(&(prefix + "Stem")).field("id", &id).field("stem", &stem).finish()
+            },
             QueryKind::Phrase(words) => {
                 f.debug_struct(&(prefix + "Phrase")).field("id", &id).field("words", &words).finish()
             },
+            QueryKind::QuotedPhrase(words) => {
+                f.debug_struct(&(prefix + "QuotedPhrase")).field("id", &id).field("words", &words).finish()
+            },
         }
     }
 }
@@ -117,6 +156,40 @@ pub struct Context {
     pub synonyms: store::Synonyms,
     pub postings_lists: store::PostingsLists,
     pub prefix_postings_lists: store::PrefixPostingsListsCache,
+    pub typo_tolerance: TypoTolerance,
+    /// The attributes in which the matches containing typos are ignored.
+    pub exact_attributes: Vec<SchemaAttr>,
+    pub matching_strategy: MatchingStrategy,
+    pub prefix_search: PrefixSearch,
+    /// The stemmer of the index language, if the stemming is enabled.
+    pub stemmer: Option<Stemmer>,
+    /// The tokenizer that split the documents texts of the index.
+    pub tokenizer: ArcTokenizer,
+    /// The stop words of the index, they are never indexed.
+    pub stop_words: fst::Set,
+}
+
+/// Defines the query words that can be ignored when no document contains all of
+/// them, the words are ignored one by one until some documents contain all the
+/// remaining ones.
+///
+/// The words written between double quotes are never ignored.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchingStrategy {
+    /// All the query words must be found in the documents.
+    All,
+    /// The query words are ignored one by one starting from the last one.
+    Last,
+    /// The query words are ignored one by one starting from the one
+    /// that appears in the most documents, the least discriminant one.
+    Frequency,
+}
+
+impl Default for MatchingStrategy {
+    fn default() -> MatchingStrategy {
+        MatchingStrategy::All
+    }
 }
 
 fn split_best_frequency<'a>(reader: &heed::RoTxn<MainT>, ctx: &Context, word: &'a str) -> MResult<Option<(&'a str, &'a str)>> {
@@ -171,15 +244,135 @@ where I: IntoIterator<Item=Operation>,
     }
 }
 
+/// Defines the query words that can match the words they are a prefix of.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PrefixSearch {
+    /// The query words only match the exact same words,
+    /// it saves latency on very large indexes.
+    Disabled,
+    /// Only the last query word is a prefix, the one being typed.
+    Last,
+    /// All the query words are prefixes, it helps the
+    /// autocompletion widgets that reorder the words.
+    All,
+}
+
+impl Default for PrefixSearch {
+    fn default() -> PrefixSearch {
+        PrefixSearch::Last
+    }
+}
+
+/// Returns the successive sets of query words ids to search for, from all
+/// the query words down to a single one, by ignoring the words in the order
+/// defined by the matching strategy.
+///
+/// The sets are evaluated one after the other, the documents of
+/// the first set that matches documents are the ones returned.
+fn matching_words(
+    reader: &heed::RoTxn<MainT>,
+    ctx: &Context,
+    words: &[(usize, String)],
+    phrases: &[Range<usize>],
+) -> MResult<Vec<Vec<usize>>>
+{
+    let optional = words.iter()
+        .map(|(id, _)| *id)
+        .filter(|id| !phrases.iter().any(|r| r.contains(id)));
+
+    let removal_order: Vec<_> = match ctx.matching_strategy {
+        MatchingStrategy::All => Vec::new(),
+        MatchingStrategy::Last => optional.rev().collect(),
+        MatchingStrategy::Frequency => {
+            let mut frequencies = Vec::new();
+            for id in optional {
+                let word = &words[id].1;
+                let frequency = ctx.postings_lists
+                    .postings_list(reader, word.as_bytes())?
+                    .map_or(0, |p| p.docids.len());
+                frequencies.push((frequency, id));
+            }
+
+            frequencies.sort_unstable_by_key(|&(frequency, id)| (cmp::Reverse(frequency), id));
+            frequencies.into_iter().map(|(_, id)| id).collect()
+        },
+    };
+
+    let mut kept: Vec<_> = words.iter().map(|(id, _)| *id).collect();
+    let mut sets = vec![kept.clone()];
+
+    for id in removal_order {
+        if kept.len() == 1 { break }
+        kept.retain(|k| *k != id);
+        sets.push(kept.clone());
+    }
+
+    Ok(sets)
+}
+
 const MAX_NGRAM: usize = 3;
 
+/// Splits the query into lowercased words and returns the ranges
+/// of the words that were written between double quotes along with
+/// the words that were prefixed by a minus sign and must be excluded.
+///
+/// A double quote that is not closed is ignored.
+pub(crate) fn split_query_phrases<T>(tokenizer: &T, query: &str) -> (Vec<String>, Vec<Range<usize>>, Vec<String>)
+where
+    T: Tokenizer + ?Sized,
+{
+    let mut words = Vec::new();
+    let mut phrases = Vec::new();
+    let mut negated = Vec::new();
+
+    // the segments at odd indexes are between double quotes,
+    // there is an even number of segments if the last quote is not closed
+    let segments: Vec<_> = query.split('"').collect();
+    let unclosed = segments.len() % 2 == 0;
+
+    for (i, segment) in segments.iter().enumerate() {
+        let quoted = i % 2 == 1 && !(unclosed && i == segments.len() - 1);
+        let start = words.len();
+
+        if quoted {
+            words.extend(split_query_string(tokenizer, segment).map(str::to_lowercase));
+            if words.len() > start {
+                phrases.push(start..words.len());
+            }
+        } else {
+            for part in segment.split_whitespace() {
+                if part.len() > 1 && part.starts_with('-') {
+                    negated.extend(split_query_string(tokenizer, &part[1..]).map(str::to_lowercase));
+                } else {
+                    words.extend(split_query_string(tokenizer, part).map(str::to_lowercase));
+                }
+            }
+        }
+    }
+
+    (words, phrases, negated)
+}
+
+/// Returns whether the query does not contain any word to search for, the documents
+/// of such a query are only restricted by the words it excludes, if any.
+pub fn is_placeholder_query<T>(tokenizer: &T, query: &str) -> bool
+where
+    T: Tokenizer + ?Sized,
+{
+    let (words, _, _) = split_query_phrases(tokenizer, query);
+    words.is_empty()
+}
+
+/// Creates the query tree of the words of the query, a query that only contains
+/// negated words is represented by a single `Not` operation of these words.
 pub fn create_query_tree(
     reader: &heed::RoTxn<MainT>,
     ctx: &Context,
     query: &str,
-) -> MResult<(Operation, HashMap<QueryId, Range<usize>>)>
+) -> MResult<(Operation, HashMap<QueryId, Range<usize>>, HashMap<QueryId, WordOrigin>)>
 {
-    let words = split_query_string(query).map(str::to_lowercase);
+    let (words, phrases, negated) = split_query_phrases(&*ctx.tokenizer, query);
     let words: Vec<_> = words.into_iter().enumerate().collect();
 
     let mut mapper = QueryWordsMapper::new(words.iter().map(|(_, w)| w));
@@ -189,16 +382,51 @@ pub fn create_query_tree(
         ctx: &Context,
         mapper: &mut QueryWordsMapper,
         words: &[(usize, String)],
+        phrases: &[Range<usize>],
+        last_id: usize,
     ) -> MResult<Vec<Operation>>
     {
         let mut alts = Vec::new();
 
+        // the quoted words are neither typo tolerant nor grouped with the
+        // other words, they must be found consecutively in the same attribute
+        let phrase = words.first().and_then(|(id, _)| phrases.iter().find(|r| r.start == *id));
+        if let Some(range) = phrase {
+            let (group, tail) = words.split_at(range.len());
+            let mut group_ops = Vec::new();
+
+            let operation = match group {
+                [(id, word)] => Operation::non_tolerant(*id, false, word),
+                group => Operation::quoted_phrase(group[0].0, group),
+            };
+            group_ops.push(operation);
+
+            if !tail.is_empty() {
+                let tail_ops = create_inner(reader, ctx, mapper, tail, phrases, last_id)?;
+                group_ops.push(create_operation(tail_ops, Operation::Or));
+            }
+
+            alts.push(create_operation(group_ops, Operation::And));
+            return Ok(alts);
+        }
+
         for ngram in 1..=MAX_NGRAM {
             if let Some(group) = words.get(..ngram) {
+                if group.iter().any(|(id, _)| phrases.iter().any(|r| r.contains(id))) {
+                    break;
+                }
+
                 let mut group_ops = Vec::new();
 
+                // only the last word of the query is a prefix, the words
+                // that become the last one of a shorter set of words are not
                 let tail = &words[ngram..];
-                let is_last = tail.is_empty();
+                let is_last = tail.is_empty() && group[ngram - 1].0 == last_id;
+                let prefix = match ctx.prefix_search {
+                    PrefixSearch::Disabled => false,
+                    PrefixSearch::Last => is_last,
+                    PrefixSearch::All => true,
+                };
 
                 let mut group_alts = Vec::new();
                 match group {
@@ -210,8 +438,16 @@ pub fn create_query_tree(
                             .map(|ws| {
                                 let id = idgen.next().unwrap();
                                 idgen.next().unwrap();
-                                mapper.declare(range.clone(), id, &[ws.0, ws.1]);
-                                Operation::phrase2(id, is_last, ws)
+                                mapper.declare(range.clone(), id, WordOrigin::Split, &[ws.0, ws.1]);
+                                Operation::phrase2(id, prefix, ws)
+                            });
+
+                        let stem = ctx.stemmer.as_ref()
+                            .and_then(|stemmer| stemmer.stem(word))
+                            .map(|stem| {
+                                let id = idgen.next().unwrap();
+                                mapper.declare(range.clone(), id, WordOrigin::Stem, &[&stem]);
+                                Operation::stem(id, &stem)
                             });
 
                         let synonyms = fetch_synonyms(reader, ctx, &[word])?
@@ -219,7 +455,7 @@ pub fn create_query_tree(
                             .map(|alts| {
                                 let exact = alts.len() == 1;
                                 let id = idgen.next().unwrap();
-                                mapper.declare(range.clone(), id, &alts);
+                                mapper.declare(range.clone(), id, WordOrigin::Synonym, &alts);
 
                                 let mut idgen = once(id).chain(&mut idgen);
                                 let iter = alts.into_iter().map(|w| {
@@ -231,10 +467,10 @@ pub fn create_query_tree(
                                 create_operation(iter, Operation::And)
                             });
 
-                        let original = Operation::tolerant(*id, is_last, word);
+                        let original = Operation::tolerant(*id, prefix, word);
 
                         group_alts.push(original);
-                        group_alts.extend(synonyms.chain(phrase));
+                        group_alts.extend(synonyms.chain(phrase).chain(stem));
                     },
                     words => {
                         let id = words[0].0;
@@ -246,7 +482,7 @@ pub fn create_query_tree(
                         for synonym in fetch_synonyms(reader, ctx, &words)? {
                             let exact = synonym.len() == 1;
                             let id = idgen.next().unwrap();
-                            mapper.declare(range.clone(), id, &synonym);
+                            mapper.declare(range.clone(), id, WordOrigin::Synonym, &synonym);
 
                             let mut idgen = once(id).chain(&mut idgen);
                             let synonym = synonym.into_iter().map(|s| {
@@ -259,15 +495,15 @@ pub fn create_query_tree(
 
                         let id = idgen.next().unwrap();
                         let concat = words.concat();
-                        mapper.declare(range.clone(), id, &[&concat]);
-                        group_alts.push(Operation::non_tolerant(id, is_last, &concat));
+                        mapper.declare(range.clone(), id, WordOrigin::Concatenation, &[&concat]);
+                        group_alts.push(Operation::non_tolerant(id, prefix, &concat));
                     }
                 }
 
                 group_ops.push(create_operation(group_alts, Operation::Or));
 
                 if !tail.is_empty() {
-                    let tail_ops = create_inner(reader, ctx, mapper, tail)?;
+                    let tail_ops = create_inner(reader, ctx, mapper, tail, phrases, last_id)?;
                     group_ops.push(create_operation(tail_ops, Operation::Or));
                 }
 
@@ -278,16 +514,52 @@ pub fn create_query_tree(
         Ok(alts)
     }
 
-    let alternatives = create_inner(reader, ctx, &mut mapper, &words)?;
-    let operation = Operation::Or(alternatives);
+    let last_id = words.last().map_or(0, |(id, _)| *id);
+
+    let mut sets_operations = Vec::new();
+    for kept in matching_words(reader, ctx, &words, &phrases)? {
+        // the ignored words split the query words into groups of
+        // consecutive words that are not allowed to form n-grams together
+        let mut groups_ops = Vec::new();
+        for ids in kept.linear_group_by(|a, b| a + 1 == *b) {
+            let group = &words[ids[0]..ids[ids.len() - 1] + 1];
+            groups_ops.push(create_inner(reader, ctx, &mut mapper, group, &phrases, last_id)?);
+        }
+
+        let alternatives = if groups_ops.len() == 1 {
+            groups_ops.pop().unwrap()
+        } else {
+            let iter = groups_ops.into_iter().map(|ops| create_operation(ops, Operation::Or));
+            vec![create_operation(iter, Operation::And)]
+        };
+
+        sets_operations.push(Operation::Or(alternatives));
+    }
+
+    // the smaller sets of words are only searched when the bigger ones match nothing
+    let mut operation = create_operation(sets_operations, Operation::Fallback);
+
+    // the negated words are not typo tolerant and are not part of the
+    // query words mapping as they are never used to rank the documents
+    if !negated.is_empty() {
+        let mut idgen = words.len()..;
+        let negated = negated.iter().map(|w| Operation::non_tolerant(idgen.next().unwrap(), false, w));
+        let not = Operation::Not(Box::new(create_operation(negated, Operation::Or)));
+        operation = if words.is_empty() { not } else { Operation::And(vec![operation, not]) };
+    }
+
+    let origins = mapper.origins();
     let mapping = mapper.mapping();
 
-    Ok((operation, mapping))
+    Ok((operation, mapping, origins))
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct PostingsKey<'o> {
     pub query: &'o Query,
+    /// The position of the matched word in a quoted phrase, the
+    /// matches are given the query id plus this position.
+    pub phrase_offset: usize,
     pub input: Vec<u8>,
     pub distance: u8,
     pub is_exact: bool,
@@ -301,6 +573,44 @@ pub struct QueryResult<'o, 'txn> {
     pub queries: Postings<'o, 'txn>,
 }
 
+/// Returns the chains of matches, in the same attribute, of the words of a phrase
+/// given with their position in the phrase, the matches of two words must be as far
+/// from each other as the words are in the phrase.
+fn phrase_chains(reader: &heed::RoTxn<MainT>, ctx: &Context, words: &[(usize, &str)]) -> MResult<Vec<Vec<DocIndex>>> {
+    // we keep the chains of matches of the phrase words
+    // found until now and extend them word by word
+    let mut chains: Vec<Vec<DocIndex>> = Vec::new();
+    for (i, (position, word)) in words.iter().enumerate() {
+        let postings_list = ctx.postings_lists.postings_list(reader, word.as_bytes())?.unwrap_or_default();
+
+        if i == 0 {
+            chains = postings_list.matches.iter().map(|m| vec![*m]).collect();
+            continue;
+        }
+
+        let gap = (position - words[i - 1].0) as u32;
+        let iter = merge_join_by(chains, postings_list.matches.as_slice(), |chain, b| {
+            let a = chain[chain.len() - 1];
+            let x = (a.document_id, a.attribute, (a.word_index as u32) + gap);
+            let y = (b.document_id, b.attribute, b.word_index as u32);
+            x.cmp(&y)
+        });
+
+        chains = iter
+            .filter_map(EitherOrBoth::both)
+            .map(|(mut chain, b)| { chain.push(*b); chain })
+            .collect();
+    }
+
+    Ok(chains)
+}
+
+fn chains_docids(chains: &[Vec<DocIndex>]) -> SetBuf<DocumentId> {
+    let mut docids: Vec<_> = chains.iter().map(|c| c[0].document_id).collect();
+    docids.dedup();
+    SetBuf::new(docids).unwrap()
+}
+
 pub fn traverse_query_tree<'o, 'txn>(
     reader: &'txn heed::RoTxn<MainT>,
     ctx: &Context,
@@ -326,20 +636,31 @@ pub fn traverse_query_tree<'o, 'txn>(
                 let docids = match op {
                     Operation::And(ops) => execute_and(reader, ctx, cache, postings, depth + 1, &ops)?,
                     Operation::Or(ops) => execute_or(reader, ctx, cache, postings, depth + 1, &ops)?,
+                    Operation::Not(op) => execute_not(reader, ctx, depth + 1, &op)?,
+                    Operation::Fallback(ops) => execute_fallback(reader, ctx, cache, postings, depth + 1, &ops)?,
                     Operation::Query(query) => execute_query(reader, ctx, postings, depth + 1, &query)?,
                 };
                 cache.insert(op, docids);
             }
         }
 
+        let mut excluded = Vec::new();
         for op in operations {
             if let Some(docids) = cache.get(op) {
-                results.push(docids.as_ref());
+                match op {
+                    Operation::Not(_) => excluded.push(docids.as_ref()),
+                    _ => results.push(docids.as_ref()),
+                }
             }
         }
 
         let op = sdset::multi::Intersection::new(results);
-        let docids = op.into_set_buf();
+        let mut docids = op.into_set_buf();
+
+        if !excluded.is_empty() {
+            let excluded = sdset::multi::Union::new(excluded).into_set_buf();
+            docids = sdset::duo::Difference::new(&docids, &excluded).into_set_buf();
+        }
 
         debug!("{:3$}--- AND fetched {} documents in {:.02?}", "", docids.len(), before.elapsed(), depth * 2);
 
@@ -365,6 +686,8 @@ pub fn traverse_query_tree<'o, 'txn>(
                 let docids = match op {
                     Operation::And(ops) => execute_and(reader, ctx, cache, postings, depth + 1, &ops)?,
                     Operation::Or(ops) => execute_or(reader, ctx, cache, postings, depth + 1, &ops)?,
+                    Operation::Not(_) => continue,
+                    Operation::Fallback(ops) => execute_fallback(reader, ctx, cache, postings, depth + 1, &ops)?,
                     Operation::Query(query) => execute_query(reader, ctx, postings, depth + 1, &query)?,
                 };
                 cache.insert(op, docids);
@@ -385,6 +708,64 @@ pub fn traverse_query_tree<'o, 'txn>(
         Ok(Cow::Owned(docids))
     }
 
+    fn execute_fallback<'o, 'txn>(
+        reader: &'txn heed::RoTxn<MainT>,
+        ctx: &Context,
+        cache: &mut Cache<'o, 'txn>,
+        postings: &mut Postings<'o, 'txn>,
+        depth: usize,
+        operations: &'o [Operation],
+    ) -> MResult<Cow<'txn, Set<DocumentId>>>
+    {
+        debug!("{:1$}FALLBACK", "", depth * 2);
+
+        let before = Instant::now();
+
+        for op in operations {
+            if cache.get(op).is_none() {
+                let docids = match op {
+                    Operation::And(ops) => execute_and(reader, ctx, cache, postings, depth + 1, &ops)?,
+                    Operation::Or(ops) => execute_or(reader, ctx, cache, postings, depth + 1, &ops)?,
+                    Operation::Not(_) => continue,
+                    Operation::Fallback(ops) => execute_fallback(reader, ctx, cache, postings, depth + 1, &ops)?,
+                    Operation::Query(query) => execute_query(reader, ctx, postings, depth + 1, &query)?,
+                };
+                cache.insert(op, docids);
+            }
+
+            if let Some(docids) = cache.get(op).filter(|docids| !docids.is_empty()) {
+                debug!("{:3$}--- FALLBACK fetched {} documents in {:.02?}", "", docids.len(), before.elapsed(), depth * 2);
+                return Ok(docids.clone());
+            }
+        }
+
+        Ok(Cow::default())
+    }
+
+    // A negation is only meaningful in an `And` operation, it returns the documents
+    // to exclude. These are computed with their own cache and postings lists as the
+    // negated words must not be used to rank nor to highlight the documents.
+    fn execute_not<'o, 'txn>(
+        reader: &'txn heed::RoTxn<MainT>,
+        ctx: &Context,
+        depth: usize,
+        operation: &'o Operation,
+    ) -> MResult<Cow<'txn, Set<DocumentId>>>
+    {
+        debug!("{:1$}NOT", "", depth * 2);
+
+        let mut cache = Cache::new();
+        let mut postings = Postings::new();
+
+        match operation {
+            Operation::And(ops) => execute_and(reader, ctx, &mut cache, &mut postings, depth + 1, &ops),
+            Operation::Or(ops) => execute_or(reader, ctx, &mut cache, &mut postings, depth + 1, &ops),
+            Operation::Not(_) => Ok(Cow::default()),
+            Operation::Fallback(ops) => execute_fallback(reader, ctx, &mut cache, &mut postings, depth + 1, &ops),
+            Operation::Query(query) => execute_query(reader, ctx, &mut postings, depth + 1, &query),
+        }
+    }
+
     fn execute_query<'o, 'txn>(
         reader: &'txn heed::RoTxn<MainT>,
         ctx: &Context,
@@ -398,7 +779,10 @@ pub fn traverse_query_tree<'o, 'txn>(
         let Query { prefix, kind, exact, .. } = query;
         let docids: Cow<Set<_>> = match kind {
             QueryKind::Tolerant(word) => {
-                if *prefix && word.len() <= 2 {
+                // We retrieve the cached postings lists for all the words that
+                // starts with this short prefix, the cache is not computed when
+                // the prefix search of the index is disabled.
+                let cached = if *prefix && word.len() <= 2 {
                     let prefix = {
                         let mut array = [0; 4];
                         let bytes = word.as_bytes();
@@ -406,17 +790,20 @@ pub fn traverse_query_tree<'o, 'txn>(
                         array
                     };
 
-                    // We retrieve the cached postings lists for all
-                    // the words that starts with this short prefix.
-                    let result = ctx.prefix_postings_lists.prefix_postings_list(reader, prefix)?.unwrap_or_default();
-                    let key = PostingsKey { query, input: word.clone().into_bytes(), distance: 0, is_exact: false };
+                    ctx.prefix_postings_lists.prefix_postings_list(reader, prefix)?
+                } else {
+                    None
+                };
+
+                if let Some(result) = cached {
+                    let key = PostingsKey { query, phrase_offset: 0, input: word.clone().into_bytes(), distance: 0, is_exact: false };
                     postings.insert(key, result.matches);
                     let prefix_docids = &result.docids;
 
                     // We retrieve the exact postings list for the prefix,
                     // because we must consider these matches as exact.
                     let result = ctx.postings_lists.postings_list(reader, word.as_bytes())?.unwrap_or_default();
-                    let key = PostingsKey { query, input: word.clone().into_bytes(), distance: 0, is_exact: true };
+                    let key = PostingsKey { query, phrase_offset: 0, input: word.clone().into_bytes(), distance: 0, is_exact: true };
                     postings.insert(key, result.matches);
                     let exact_docids = &result.docids;
 
@@ -428,7 +815,8 @@ pub fn traverse_query_tree<'o, 'txn>(
                     Cow::Owned(docids)
 
                 } else {
-                    let dfa = if *prefix { build_prefix_dfa(word) } else { build_dfa(word) };
+                    let typos = ctx.typo_tolerance.max_typos(word);
+                    let dfa = if *prefix { build_prefix_dfa(word, typos) } else { build_dfa(word, typos) };
 
                     let byte = word.as_bytes()[0];
                     let mut stream = if byte == u8::max_value() {
@@ -440,11 +828,23 @@ pub fn traverse_query_tree<'o, 'txn>(
                     let before = Instant::now();
                     let mut results = Vec::new();
                     while let Some(input) = stream.next() {
-                        if let Some(result) = ctx.postings_lists.postings_list(reader, input)? {
+                        if let Some(mut result) = ctx.postings_lists.postings_list(reader, input)? {
                             let distance = dfa.eval(input).to_u8();
                             let is_exact = *exact && distance == 0 && input.len() == word.len();
+
+                            // matches with typos are not accepted in the exact attributes
+                            if distance > 0 && !ctx.exact_attributes.is_empty() {
+                                let matches: Vec<_> = result.matches.iter()
+                                    .filter(|m| !ctx.exact_attributes.contains(&SchemaAttr(m.attribute)))
+                                    .cloned()
+                                    .collect();
+                                let docids = matches.linear_group_by_key(|m| m.document_id).map(|g| g[0].document_id).collect();
+                                result.docids = Cow::Owned(SetBuf::new_unchecked(docids));
+                                result.matches = Cow::Owned(SetBuf::new_unchecked(matches));
+                            }
+
                             results.push(result.docids);
-                            let key = PostingsKey { query, input: input.to_owned(), distance, is_exact };
+                            let key = PostingsKey { query, phrase_offset: 0, input: input.to_owned(), distance, is_exact };
                             postings.insert(key, result.matches);
                         }
                     }
@@ -484,7 +884,7 @@ pub fn traverse_query_tree<'o, 'txn>(
                     if let Some(result) = ctx.postings_lists.postings_list(reader, input)? {
                         let distance = dfa.eval(input).to_u8();
                         results.push(result.docids);
-                        let key = PostingsKey { query, input: input.to_owned(), distance, is_exact: *exact };
+                        let key = PostingsKey { query, phrase_offset: 0, input: input.to_owned(), distance, is_exact: *exact };
                         postings.insert(key, result.matches);
                     }
                 }
@@ -506,31 +906,32 @@ pub fn traverse_query_tree<'o, 'txn>(
 
                 Cow::Owned(docids)
             },
+            QueryKind::Stem(stem) => {
+                // the stems are not part of the words fst, they are directly retrieved
+                let stems = match ctx.postings_lists.postings_list(reader, &stem_key(stem))? {
+                    Some(stems) => stems,
+                    None => return Ok(Cow::default()),
+                };
+
+                // the stem matches are never considered exact
+                let key = PostingsKey { query, phrase_offset: 0, input: stem.clone().into_bytes(), distance: 0, is_exact: false };
+                postings.insert(key, stems.matches);
+                stems.docids
+            },
             QueryKind::Phrase(words) => {
                 // TODO support prefix and non-prefix exact DFA
-                if let [first, second] = words.as_slice() {
-                    let first = ctx.postings_lists.postings_list(reader, first.as_bytes())?.unwrap_or_default();
-                    let second = ctx.postings_lists.postings_list(reader, second.as_bytes())?.unwrap_or_default();
-
-                    let iter = merge_join_by(first.matches.as_slice(), second.matches.as_slice(), |a, b| {
-                        let x = (a.document_id, a.attribute, (a.word_index as u32) + 1);
-                        let y = (b.document_id, b.attribute, b.word_index as u32);
-                        x.cmp(&y)
-                    });
-
-                    let matches: Vec<_> = iter
-                        .filter_map(EitherOrBoth::both)
-                        .flat_map(|(a, b)| once(*a).chain(Some(*b)))
-                        .collect();
+                if words.len() >= 2 {
+                    let words: Vec<_> = words.iter().map(String::as_str).enumerate().collect();
+                    let chains = phrase_chains(reader, ctx, &words)?;
 
                     let before = Instant::now();
-                    let mut docids: Vec<_> = matches.iter().map(|m| m.document_id).collect();
-                    docids.dedup();
-                    let docids = SetBuf::new(docids).unwrap();
+                    let docids = chains_docids(&chains);
                     debug!("{:2$}docids construction took {:.02?}", "", before.elapsed(), depth * 2);
 
-                    let matches = Cow::Owned(SetBuf::new(matches).unwrap());
-                    let key = PostingsKey { query, input: vec![], distance: 0, is_exact: true };
+                    // chains can overlap when a phrase repeats the same word
+                    let matches = chains.into_iter().flatten().collect();
+                    let matches = Cow::Owned(SetBuf::from_dirty(matches));
+                    let key = PostingsKey { query, phrase_offset: 0, input: vec![], distance: 0, is_exact: true };
                     postings.insert(key, matches);
 
                     Cow::Owned(docids)
@@ -539,6 +940,33 @@ pub fn traverse_query_tree<'o, 'txn>(
                     Cow::default()
                 }
             },
+            QueryKind::QuotedPhrase(words) => {
+                // the stop words are never indexed, they are skipped but
+                // still separate the other words of the phrase
+                let words: Vec<_> = words
+                    .iter()
+                    .map(String::as_str)
+                    .enumerate()
+                    .filter(|(_, word)| !ctx.stop_words.contains(word))
+                    .collect();
+
+                let chains = if words.is_empty() { Vec::new() } else { phrase_chains(reader, ctx, &words)? };
+
+                let before = Instant::now();
+                let docids = chains_docids(&chains);
+                debug!("{:2$}docids construction took {:.02?}", "", before.elapsed(), depth * 2);
+
+                // every word of the phrase is matched under its own query id
+                // to be counted by the criteria and highlighted like the others
+                for (i, (offset, word)) in words.into_iter().enumerate() {
+                    let matches = chains.iter().map(|chain| chain[i]).collect();
+                    let matches = Cow::Owned(SetBuf::from_dirty(matches));
+                    let key = PostingsKey { query, phrase_offset: offset, input: word.as_bytes().to_vec(), distance: 0, is_exact: true };
+                    postings.insert(key, matches);
+                }
+
+                Cow::Owned(docids)
+            },
         };
 
         debug!("{:4$}{:?} fetched {:?} documents in {:.02?}", "", query, docids.len(), before.elapsed(), depth * 2);
@@ -551,6 +979,8 @@ pub fn traverse_query_tree<'o, 'txn>(
     let docids = match tree {
         Operation::And(ops) => execute_and(reader, ctx, &mut cache, &mut postings, 0, &ops)?,
         Operation::Or(ops) => execute_or(reader, ctx, &mut cache, &mut postings, 0, &ops)?,
+        Operation::Not(_) => Cow::default(),
+        Operation::Fallback(ops) => execute_fallback(reader, ctx, &mut cache, &mut postings, 0, &ops)?,
         Operation::Query(query) => execute_query(reader, ctx, &mut postings, 0, &query)?,
     };
 
@@ -5,9 +5,21 @@ use intervaltree::{Element, IntervalTree};
 
 pub type QueryId = usize;
 
+/// Where a query word comes from, the words typed by the
+/// user are considered more relevant than the derived ones.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum WordOrigin {
+    Original,
+    Synonym,
+    Split,
+    Concatenation,
+    Stem,
+}
+
 pub struct QueryWordsMapper {
     originals: Vec<String>,
     mappings: HashMap<QueryId, (Range<usize>, Vec<String>)>,
+    origins: HashMap<QueryId, WordOrigin>,
 }
 
 impl QueryWordsMapper {
@@ -16,10 +28,10 @@ impl QueryWordsMapper {
           A: ToString,
     {
         let originals = originals.into_iter().map(|s| s.to_string()).collect();
-        QueryWordsMapper { originals, mappings: HashMap::new() }
+        QueryWordsMapper { originals, mappings: HashMap::new(), origins: HashMap::new() }
     }
 
-    pub fn declare<I, A>(&mut self, range: Range<usize>, id: QueryId, replacement: I)
+    pub fn declare<I, A>(&mut self, range: Range<usize>, id: QueryId, origin: WordOrigin, replacement: I)
     where I: IntoIterator<Item = A>,
           A: ToString,
     {
@@ -31,6 +43,10 @@ impl QueryWordsMapper {
 
         assert!(!replacement.is_empty());
 
+        for i in 0..replacement.len() {
+            self.origins.insert(id + i, origin);
+        }
+
         // We detect words at the end and at the front of the
         // replacement that are common with the originals:
         //
@@ -65,6 +81,16 @@ impl QueryWordsMapper {
         }
     }
 
+    /// Returns the origin of every query id, the
+    /// ids of the original words included.
+    pub fn origins(&self) -> HashMap<QueryId, WordOrigin> {
+        let mut origins = self.origins.clone();
+        for id in 0..self.originals.len() {
+            origins.insert(id, WordOrigin::Original);
+        }
+        origins
+    }
+
     pub fn mapping(self) -> HashMap<QueryId, Range<usize>> {
         let mappings = self.mappings.into_iter().map(|(i, (r, v))| (r, (i, v)));
         let intervals = IntervalTree::from_iter(mappings);
@@ -141,11 +167,11 @@ mod tests {
         let mut builder = QueryWordsMapper::new(&query);
 
         // new york = new york city
-        builder.declare(0..2, 4, &["new", "york", "city"]);
+        builder.declare(0..2, 4, WordOrigin::Synonym, &["new", "york", "city"]);
         //                    ^      4       5       6
 
         // new = new york city
-        builder.declare(0..1, 7, &["new", "york", "city"]);
+        builder.declare(0..1, 7, WordOrigin::Synonym, &["new", "york", "city"]);
         //                    ^      7       8       9
 
         let mapping = builder.mapping();
@@ -171,7 +197,7 @@ mod tests {
         let mut builder = QueryWordsMapper::new(&query);
 
         // city subway = new york city underground train
-        builder.declare(2..4, 4, &["new", "york", "city", "underground", "train"]);
+        builder.declare(2..4, 4, WordOrigin::Synonym, &["new", "york", "city", "underground", "train"]);
         //                    ^      4      5       6           7           8
 
         let mapping = builder.mapping();
@@ -195,7 +221,7 @@ mod tests {
         let mut builder = QueryWordsMapper::new(&query);
 
         // c d = a b x c d k j e f
-        builder.declare(6..8, 11, &["a", "b", "x", "c", "d", "k", "j", "e", "f"]);
+        builder.declare(6..8, 11, WordOrigin::Synonym, &["a", "b", "x", "c", "d", "k", "j", "e", "f"]);
         //                    ^^    11   12   13   14   15   16   17   18   19
 
         let mapping = builder.mapping();
@@ -230,7 +256,7 @@ mod tests {
         let mut builder = QueryWordsMapper::new(&query);
 
         // new york = new york city
-        builder.declare(0..2, 3, &["new", "york", "city"]);
+        builder.declare(0..2, 3, WordOrigin::Synonym, &["new", "york", "city"]);
         //                    ^      3       4       5
 
         let mapping = builder.mapping();
@@ -250,23 +276,23 @@ mod tests {
         let mut builder = QueryWordsMapper::new(&query);
 
         // NY = new york
-        builder.declare(0..1, 2, &["new", "york"]);
+        builder.declare(0..1, 2, WordOrigin::Synonym, &["new", "york"]);
         //                    ^      2       3
 
         // NY = new york city
-        builder.declare(0..1, 4, &["new", "york", "city"]);
+        builder.declare(0..1, 4, WordOrigin::Synonym, &["new", "york", "city"]);
         //                    ^      4       5       6
 
         // NY = NYC
-        builder.declare(0..1, 7, &["NYC"]);
+        builder.declare(0..1, 7, WordOrigin::Synonym, &["NYC"]);
         //                    ^      7
 
         // NY = new york city
-        builder.declare(0..1, 8, &["new", "york", "city"]);
+        builder.declare(0..1, 8, WordOrigin::Synonym, &["new", "york", "city"]);
         //                    ^      8       9      10
 
         // subway = underground train
-        builder.declare(1..2, 11, &["underground", "train"]);
+        builder.declare(1..2, 11, WordOrigin::Synonym, &["underground", "train"]);
         //                    ^          11          12
 
         let mapping = builder.mapping();
@@ -293,7 +319,7 @@ mod tests {
         let mut builder = QueryWordsMapper::new(&query);
 
         // NYC = new york city
-        builder.declare(0..1, 2, &["new", "york", "city"]);
+        builder.declare(0..1, 2, WordOrigin::Synonym, &["new", "york", "city"]);
         //                    ^      2       3       4
 
         let mapping = builder.mapping();
@@ -312,7 +338,7 @@ mod tests {
         let mut builder = QueryWordsMapper::new(&query);
 
         // NYC = new york city
-        builder.declare(2..3, 4, &["new", "york", "city"]);
+        builder.declare(2..3, 4, WordOrigin::Synonym, &["new", "york", "city"]);
         //                    ^      4       5       6
 
         let mapping = builder.mapping();
@@ -333,7 +359,7 @@ mod tests {
         let mut builder = QueryWordsMapper::new(&query);
 
         // NYC = new york city
-        builder.declare(1..2, 2, &["underground", "train"]);
+        builder.declare(1..2, 2, WordOrigin::Synonym, &["underground", "train"]);
         //                    ^         2            3
 
         let mapping = builder.mapping();
@@ -351,11 +377,11 @@ mod tests {
         let mut builder = QueryWordsMapper::new(&query);
 
         // NYC = new york city
-        builder.declare(2..3, 4, &["new", "york", "city"]);
+        builder.declare(2..3, 4, WordOrigin::Synonym, &["new", "york", "city"]);
         //                    ^      4       5       6
 
         // subway = underground train
-        builder.declare(3..4, 7, &["underground", "train"]);
+        builder.declare(3..4, 7, WordOrigin::Synonym, &["underground", "train"]);
         //                    ^          7           8
 
         let mapping = builder.mapping();
@@ -378,23 +404,23 @@ mod tests {
         let mut builder = QueryWordsMapper::new(&query);
 
         // NYC = new york city
-        builder.declare(2..3, 4, &["new", "york", "city"]);
+        builder.declare(2..3, 4, WordOrigin::Synonym, &["new", "york", "city"]);
         //                    ^      4       5       6
 
         // subway = underground train
-        builder.declare(3..4, 7, &["underground", "train"]);
+        builder.declare(3..4, 7, WordOrigin::Synonym, &["underground", "train"]);
         //                    ^          7           8
 
         // great awesome = good
-        builder.declare(0..2, 9, &["good"]);
+        builder.declare(0..2, 9, WordOrigin::Synonym, &["good"]);
         //                    ^       9
 
         // awesome NYC = NY
-        builder.declare(1..3, 10, &["NY"]);
+        builder.declare(1..3, 10, WordOrigin::Synonym, &["NY"]);
         //                    ^^     10
 
         // NYC subway = metro
-        builder.declare(2..4, 11, &["metro"]);
+        builder.declare(2..4, 11, WordOrigin::Synonym, &["metro"]);
         //                    ^^      11
 
         let mapping = builder.mapping();
@@ -48,4 +48,16 @@ impl<'a, 'tag> RawDocument<'a, 'tag> {
             contains_one_word_field: false,
         }
     }
+
+    /// Creates a document without any match, it is used to represent
+    /// the documents returned by a placeholder (empty) query.
+    pub fn placeholder(id: crate::DocumentId) -> RawDocument<'a, 'tag> {
+        RawDocument {
+            id,
+            bare_matches: &mut [],
+            processed_matches: Vec::new(),
+            processed_distances: Vec::new(),
+            contains_one_word_field: false,
+        }
+    }
 }
@@ -1,10 +1,14 @@
 use std::collections::{BTreeMap, HashMap};
 use std::convert::TryFrom;
 
+use std::sync::Arc;
+
+use crate::stemming::{stem_key, Stemmer};
+use crate::tokenizers::ArcTokenizer;
 use crate::{DocIndex, DocumentId};
 use deunicode::deunicode_with_tofu;
 use meilisearch_schema::SchemaAttr;
-use meilisearch_tokenizer::{is_cjk, SeqTokenizer, Token, Tokenizer};
+use meilisearch_tokenizer::{is_cjk, DefaultTokenizer, SeqTokenizer, Token, Tokenizer};
 use sdset::SetBuf;
 
 const WORD_LENGTH_LIMIT: usize = 80;
@@ -14,6 +18,8 @@ type Word = Vec<u8>; // TODO make it be a SmallVec
 pub struct RawIndexer {
     word_limit: usize, // the maximum number of indexed words
     stop_words: fst::Set,
+    tokenizer: ArcTokenizer,
+    stemmer: Option<Stemmer>,
     words_doc_indexes: BTreeMap<Word, Vec<DocIndex>>,
     docs_words: HashMap<DocumentId, Vec<Word>>,
 }
@@ -32,21 +38,39 @@ impl RawIndexer {
         RawIndexer {
             word_limit: limit,
             stop_words,
+            tokenizer: Arc::new(DefaultTokenizer::default()),
+            stemmer: None,
             words_doc_indexes: BTreeMap::new(),
             docs_words: HashMap::new(),
         }
     }
 
+    /// Also indexes the stems of the words, under keys that are
+    /// distinct from the words ones, when a stemmer is given.
+    pub fn with_stemmer(stop_words: fst::Set, stemmer: Option<Stemmer>) -> RawIndexer {
+        RawIndexer { stemmer, ..RawIndexer::new(stop_words) }
+    }
+
+    /// Splits the texts into words with the given tokenizer instead of the default one.
+    pub fn with_tokenizer(
+        stop_words: fst::Set,
+        tokenizer: ArcTokenizer,
+        stemmer: Option<Stemmer>,
+    ) -> RawIndexer {
+        RawIndexer { tokenizer, stemmer, ..RawIndexer::new(stop_words) }
+    }
+
     pub fn index_text(&mut self, id: DocumentId, attr: SchemaAttr, text: &str) -> usize {
         let mut number_of_words = 0;
 
-        for token in Tokenizer::new(text) {
+        for token in self.tokenizer.tokenize(text) {
             let must_continue = index_token(
                 token,
                 id,
                 attr,
                 self.word_limit,
                 &self.stop_words,
+                self.stemmer.as_ref(),
                 &mut self.words_doc_indexes,
                 &mut self.docs_words,
             );
@@ -66,13 +90,14 @@ impl RawIndexer {
         I: IntoIterator<Item = &'a str>,
     {
         let iter = iter.into_iter();
-        for token in SeqTokenizer::new(iter) {
+        for token in SeqTokenizer::new(&*self.tokenizer, iter) {
             let must_continue = index_token(
                 token,
                 id,
                 attr,
                 self.word_limit,
                 &self.stop_words,
+                self.stemmer.as_ref(),
                 &mut self.words_doc_indexes,
                 &mut self.docs_words,
             );
@@ -113,6 +138,7 @@ fn index_token(
     attr: SchemaAttr,
     word_limit: usize,
     stop_words: &fst::Set,
+    stemmer: Option<&Stemmer>,
     words_doc_indexes: &mut BTreeMap<Word, Vec<DocIndex>>,
     docs_words: &mut HashMap<DocumentId, Vec<Word>>,
 ) -> bool {
@@ -141,7 +167,7 @@ fn index_token(
                     if !lower.contains(is_cjk) {
                         let unidecoded = deunicode_with_tofu(&lower, "");
                         if unidecoded != lower && !unidecoded.is_empty() {
-                            let word = Vec::from(unidecoded);
+                            let word = Vec::from(unidecoded.as_str());
                             if word.len() <= WORD_LENGTH_LIMIT {
                                 words_doc_indexes
                                     .entry(word.clone())
@@ -150,6 +176,23 @@ fn index_token(
                                 docs_words.entry(id).or_insert_with(Vec::new).push(word);
                             }
                         }
+
+                        // the stems of both the original and the unidecoded words
+                        // are indexed as the query words are always unidecoded
+                        if let Some(stemmer) = stemmer {
+                            let mut stems: Vec<_> = stemmer.stem(&lower).into_iter().collect();
+                            stems.extend(stemmer.stem(&unidecoded));
+                            stems.dedup();
+
+                            for stem in stems {
+                                let word = stem_key(&stem);
+                                words_doc_indexes
+                                    .entry(word.clone())
+                                    .or_insert_with(Vec::new)
+                                    .push(docindex);
+                                docs_words.entry(id).or_insert_with(Vec::new).push(word);
+                            }
+                        }
                     }
                 }
             }
@@ -179,6 +222,7 @@ fn token_to_docindex(id: DocumentId, attr: SchemaAttr, token: Token) -> Option<D
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::stemming::Language;
 
     #[test]
     fn strange_apostrophe() {
@@ -268,4 +312,57 @@ mod tests {
             .get(&"🇯🇵".to_owned().into_bytes())
             .is_some());
     }
+
+    #[test]
+    fn stemmed_words() {
+        let stemmer = Stemmer::new(Language::English);
+        let mut indexer = RawIndexer::with_stemmer(fst::Set::default(), Some(stemmer));
+
+        let docid = DocumentId(0);
+        let attr = SchemaAttr(0);
+        let text = "The runner runs";
+        indexer.index_text(docid, attr, text);
+
+        let Indexed {
+            words_doc_indexes, ..
+        } = indexer.build();
+
+        assert!(words_doc_indexes.get(&b"runs"[..]).is_some());
+        assert!(words_doc_indexes.get(&b"run"[..]).is_none());
+        assert_eq!(words_doc_indexes.get(&stem_key("run")).map(|s| s.len()), Some(1));
+        // the words that are their own stem are also indexed as stems
+        assert_eq!(words_doc_indexes.get(&stem_key("runner")).map(|s| s.len()), Some(1));
+    }
+
+    struct WhitespaceTokenizer;
+
+    impl Tokenizer for WhitespaceTokenizer {
+        fn tokenize<'a>(&'a self, text: &'a str) -> Box<dyn Iterator<Item = Token<'a>> + 'a> {
+            let iter = text.split_whitespace().enumerate().map(move |(word_index, word)| {
+                let offset = word.as_ptr() as usize - text.as_ptr() as usize;
+                let char_index = text[..offset].chars().count();
+                Token { word, word_index, char_index }
+            });
+            Box::new(iter)
+        }
+    }
+
+    #[test]
+    fn custom_tokenizer() {
+        let tokenizer = Arc::new(WhitespaceTokenizer);
+        let mut indexer = RawIndexer::with_tokenizer(fst::Set::default(), tokenizer, None);
+
+        let docid = DocumentId(0);
+        let attr = SchemaAttr(0);
+        let text = "Bolt AB-1234/X";
+        indexer.index_text(docid, attr, text);
+
+        let Indexed {
+            words_doc_indexes, ..
+        } = indexer.build();
+
+        assert!(words_doc_indexes.get(&b"ab-1234/x"[..]).is_some());
+        assert!(words_doc_indexes.get(&b"ab"[..]).is_none());
+        assert!(words_doc_indexes.get(&b"1234"[..]).is_none());
+    }
 }
@@ -0,0 +1,67 @@
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{DocumentId, Number};
+
+/// The position of a document in the results of a query, given to the query
+/// builder to return the documents that are sorted after this one.
+///
+/// The cursor is built from the last document of a page and keeps the value of
+/// each criterion for this document, resuming a search from it does not require
+/// to sort the documents of the previous pages nor the document to still exist.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchCursor {
+    pub document_id: DocumentId,
+    /// The name of each criterion along with the value it computed for the document.
+    pub values: Vec<(String, Option<Number>)>,
+}
+
+impl SearchCursor {
+    /// Encodes the cursor into an opaque string that can be sent to the clients.
+    pub fn encode(&self) -> String {
+        let bytes = bincode::serialize(self).unwrap();
+        let mut encoded = String::with_capacity(bytes.len() * 2);
+        for byte in bytes {
+            write!(encoded, "{:02x}", byte).unwrap();
+        }
+        encoded
+    }
+
+    /// Decodes a cursor previously encoded, returns `None` if the string is not a valid cursor.
+    pub fn decode(encoded: &str) -> Option<SearchCursor> {
+        if encoded.len() % 2 != 0 || !encoded.is_ascii() {
+            return None;
+        }
+
+        let mut bytes = Vec::with_capacity(encoded.len() / 2);
+        for i in (0..encoded.len()).step_by(2) {
+            let byte = u8::from_str_radix(&encoded[i..i + 2], 16).ok()?;
+            bytes.push(byte);
+        }
+
+        bincode::deserialize(&bytes).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_decode() {
+        let values = vec![
+            ("typo".to_string(), Some(Number::Unsigned(2 << 32 | 1))),
+            ("asc(price)".to_string(), None),
+            ("stable document id".to_string(), Some(Number::Unsigned(42))),
+        ];
+        let cursor = SearchCursor { document_id: DocumentId(42), values };
+
+        let encoded = cursor.encode();
+        assert!(encoded.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_eq!(SearchCursor::decode(&encoded), Some(cursor));
+
+        assert_eq!(SearchCursor::decode("not a cursor"), None);
+        assert_eq!(SearchCursor::decode("abc"), None);
+    }
+}
@@ -4,7 +4,7 @@ use serde::ser;
 use crate::database::MainT;
 use crate::raw_indexer::RawIndexer;
 use crate::store::{DocumentsFields, DocumentsFieldsCounts};
-use crate::{DocumentId, RankedMap};
+use crate::{DocumentId, GeoMap, GeoPoint, RankedMap};
 
 use super::{ConvertToNumber, ConvertToString, Indexer, SerializerError};
 
@@ -15,6 +15,7 @@ pub struct Serializer<'a, 'b> {
     pub document_fields_counts: DocumentsFieldsCounts,
     pub indexer: &'a mut RawIndexer,
     pub ranked_map: &'a mut RankedMap,
+    pub geo_map: &'a mut GeoMap,
     pub document_id: DocumentId,
 }
 
@@ -158,6 +159,7 @@ impl<'a, 'b> ser::Serializer for Serializer<'a, 'b> {
             document_fields_counts: self.document_fields_counts,
             indexer: self.indexer,
             ranked_map: self.ranked_map,
+            geo_map: self.geo_map,
             current_key_name: None,
         })
     }
@@ -175,6 +177,7 @@ impl<'a, 'b> ser::Serializer for Serializer<'a, 'b> {
             document_fields_counts: self.document_fields_counts,
             indexer: self.indexer,
             ranked_map: self.ranked_map,
+            geo_map: self.geo_map,
         })
     }
 
@@ -199,6 +202,7 @@ pub struct MapSerializer<'a, 'b> {
     document_fields_counts: DocumentsFieldsCounts,
     indexer: &'a mut RawIndexer,
     ranked_map: &'a mut RankedMap,
+    geo_map: &'a mut GeoMap,
     current_key_name: Option<String>,
 }
 
@@ -243,6 +247,7 @@ impl<'a, 'b> ser::SerializeMap for MapSerializer<'a, 'b> {
                 self.document_fields_counts,
                 self.indexer,
                 self.ranked_map,
+                self.geo_map,
                 value,
             ),
             None => Ok(()),
@@ -262,6 +267,7 @@ pub struct StructSerializer<'a, 'b> {
     document_fields_counts: DocumentsFieldsCounts,
     indexer: &'a mut RawIndexer,
     ranked_map: &'a mut RankedMap,
+    geo_map: &'a mut GeoMap,
 }
 
 impl<'a, 'b> ser::SerializeStruct for StructSerializer<'a, 'b> {
@@ -286,6 +292,7 @@ impl<'a, 'b> ser::SerializeStruct for StructSerializer<'a, 'b> {
                 self.document_fields_counts,
                 self.indexer,
                 self.ranked_map,
+                self.geo_map,
                 value,
             ),
             None => Ok(()),
@@ -306,6 +313,7 @@ pub fn serialize_value<T: ?Sized>(
     documents_fields_counts: DocumentsFieldsCounts,
     indexer: &mut RawIndexer,
     ranked_map: &mut RankedMap,
+    geo_map: &mut GeoMap,
     value: &T,
 ) -> Result<(), SerializerError>
 where
@@ -335,5 +343,14 @@ where
         ranked_map.insert(document_id, attribute, number);
     }
 
+    // a malformed geo point is not stored in the geo map, the document is still
+    // indexed and is ranked as a document without geo point
+    if props.is_geo() {
+        let value = serde_json::to_value(value)?;
+        if let Some(point) = GeoPoint::from_value(&value) {
+            geo_map.insert(document_id, attribute, point);
+        }
+    }
+
     Ok(())
 }
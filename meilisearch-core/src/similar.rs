@@ -0,0 +1,140 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+
+use fst::Streamer;
+use ordered_float::OrderedFloat;
+use slice_group_by::GroupBy;
+
+use crate::automaton::normalize_str;
+use crate::database::MainT;
+use crate::distinct_map::{BufferedDistinctMap, DistinctMap};
+use crate::reordered_attrs::ReorderedAttrs;
+use crate::stemming::is_stem_key;
+use crate::{store, Document, DocumentId, MResult, Number, SortResult};
+
+/// The number of words of the document, the most distinctive
+/// ones, that are searched in the other documents.
+const MAX_WORDS: usize = 25;
+
+/// Returns the documents that share the most distinctive words of a document,
+/// the rarer the words shared with the document the better the ranking.
+///
+/// The words of the document are weighted by their inverse document frequency,
+/// the words found in all the documents or only in this one are ignored.
+pub fn similar_documents<FI, FD>(
+    reader: &heed::RoTxn<MainT>,
+    document_id: DocumentId,
+    range: Range<usize>,
+    filter: Option<FI>,
+    distinct: Option<(FD, usize)>,
+    ranking_info: bool,
+    searchable_attrs: Option<&ReorderedAttrs>,
+    main_store: store::Main,
+    postings_lists_store: store::PostingsLists,
+    docs_words_store: store::DocsWords,
+) -> MResult<SortResult>
+where
+    FI: Fn(DocumentId) -> bool,
+    FD: Fn(DocumentId) -> Option<u64>,
+{
+    let words = match docs_words_store.doc_words(reader, document_id)? {
+        Some(words) => words,
+        None => return Ok(SortResult::default()),
+    };
+
+    let number_of_documents = main_store.number_of_documents(reader)? as f64;
+
+    let mut seen = HashSet::new();
+    let mut weighted_words = Vec::new();
+    let mut stream = words.stream();
+    while let Some(word) = stream.next() {
+        if is_stem_key(word) { continue }
+
+        // the accented words are also indexed under their unidecoded form, the
+        // words are weighted once under this form that matches both of them
+        let word = match std::str::from_utf8(word).map(normalize_str) {
+            Ok(normalized) if !normalized.is_empty() => normalized.into_bytes(),
+            _ => word.to_vec(),
+        };
+        if !seen.insert(word.clone()) { continue }
+
+        let frequency = match postings_lists_store.postings_list(reader, &word)? {
+            Some(postings) => postings.docids.len(),
+            None => continue,
+        };
+
+        let weight = (number_of_documents / frequency as f64).ln();
+        if frequency > 1 && weight > 0.0 {
+            weighted_words.push((OrderedFloat(weight), word));
+        }
+    }
+
+    weighted_words.sort_unstable_by(|a, b| b.cmp(a));
+    weighted_words.truncate(MAX_WORDS);
+
+    // the sum of the weights of the shared words along with their number
+    let mut scores: HashMap<DocumentId, (f64, u64)> = HashMap::new();
+    for (weight, word) in weighted_words {
+        let postings = match postings_lists_store.postings_list(reader, &word)? {
+            Some(postings) => postings,
+            None => continue,
+        };
+
+        for matches in postings.matches.linear_group_by_key(|m| m.document_id) {
+            let id = matches[0].document_id;
+            if id == document_id { continue }
+
+            let searchable = match searchable_attrs {
+                Some(attrs) => matches.iter().any(|m| attrs.get(m.attribute).is_some()),
+                None => true,
+            };
+
+            if searchable {
+                let score = scores.entry(id).or_insert((0.0, 0));
+                score.0 += weight.0;
+                score.1 += 1;
+            }
+        }
+    }
+
+    let mut candidates: Vec<_> = scores.into_iter().collect();
+    candidates.sort_unstable_by(|(ida, (wa, ca)), (idb, (wb, cb))| {
+        wb.partial_cmp(wa).unwrap_or(Ordering::Equal)
+            .then_with(|| cb.cmp(ca))
+            .then_with(|| ida.cmp(idb))
+    });
+
+    let (distinct, distinct_size) = match distinct {
+        Some((distinct, size)) => (Some(distinct), size),
+        None => (None, 1),
+    };
+
+    let mut distinct_map = DistinctMap::new(distinct_size);
+    let mut seen = BufferedDistinctMap::new(&mut distinct_map);
+    let mut documents = Vec::with_capacity(range.len());
+
+    for (id, (weight, count)) in candidates {
+        let filter_accepted = filter.as_ref().map_or(true, |filter| (filter)(id));
+        if !filter_accepted { continue }
+
+        let key = distinct.as_ref().and_then(|distinct| (distinct)(id));
+        let distinct_accepted = match key {
+            Some(key) => seen.register(key),
+            None => seen.register_without_key(),
+        };
+
+        if distinct_accepted && seen.len() > range.start && documents.len() < range.len() {
+            let mut document = Document::from_highlights(id, &[]);
+            if ranking_info {
+                document.ranking_info = Some(vec![
+                    (String::from("similarity"), Number::Float(OrderedFloat(weight))),
+                    (String::from("shared words"), Number::Unsigned(count)),
+                ]);
+            }
+            documents.push(document);
+        }
+    }
+
+    Ok(SortResult { documents, nb_hits: seen.len(), ..SortResult::default() })
+}
@@ -0,0 +1,87 @@
+use rust_stemmers::Algorithm;
+use serde::{Deserialize, Serialize};
+
+/// The byte that prefixes the stems in the postings lists, it can't
+/// be the first byte of an UTF-8 string and therefore of an indexed word.
+const STEM_KEY_PREFIX: u8 = 0xFF;
+
+/// The languages for which the words can be reduced to their stems.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    #[serde(alias = "en")]
+    English,
+    #[serde(alias = "fr")]
+    French,
+}
+
+/// Reduces the words to their stems using the Snowball algorithms,
+/// "running" and "runs" are both reduced to "run" in english.
+pub struct Stemmer {
+    inner: rust_stemmers::Stemmer,
+}
+
+impl Stemmer {
+    pub fn new(language: Language) -> Stemmer {
+        let algorithm = match language {
+            Language::English => Algorithm::English,
+            Language::French => Algorithm::French,
+        };
+
+        Stemmer { inner: rust_stemmers::Stemmer::create(algorithm) }
+    }
+
+    /// Returns the stem of a lowercased word, the word itself when it is already
+    /// a stem, "run" is the stem of "runs" but also of "run".
+    pub fn stem(&self, word: &str) -> Option<String> {
+        let stem = self.inner.stem(word);
+        if stem.is_empty() {
+            None
+        } else {
+            Some(stem.into_owned())
+        }
+    }
+}
+
+/// Returns the key under which the postings list of a stem is stored,
+/// the stems are not part of the words fst and can't be found by the typo
+/// tolerant and prefix searches.
+pub fn stem_key(stem: &str) -> Vec<u8> {
+    let mut key = Vec::with_capacity(stem.len() + 1);
+    key.push(STEM_KEY_PREFIX);
+    key.extend_from_slice(stem.as_bytes());
+    key
+}
+
+pub fn is_stem_key(key: &[u8]) -> bool {
+    key.first() == Some(&STEM_KEY_PREFIX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn english_stems() {
+        let stemmer = Stemmer::new(Language::English);
+        assert_eq!(stemmer.stem("running"), Some(String::from("run")));
+        assert_eq!(stemmer.stem("runs"), Some(String::from("run")));
+        assert_eq!(stemmer.stem("run"), Some(String::from("run")));
+    }
+
+    #[test]
+    fn french_stems() {
+        let stemmer = Stemmer::new(Language::French);
+        assert_eq!(stemmer.stem("continuelles"), Some(String::from("continuel")));
+        assert_eq!(stemmer.stem("continuellement"), Some(String::from("continuel")));
+        assert_eq!(stemmer.stem("continuel"), Some(String::from("continuel")));
+    }
+
+    #[test]
+    fn stem_keys() {
+        let key = stem_key("run");
+        assert!(is_stem_key(&key));
+        assert!(!is_stem_key(b"run"));
+        assert!(std::str::from_utf8(&key).is_err());
+    }
+}
@@ -0,0 +1,102 @@
+use std::borrow::Cow;
+use std::ops::Bound;
+
+use heed::types::{ByteSlice, CowSlice};
+use heed::Result as ZResult;
+use meilisearch_schema::SchemaAttr;
+use sdset::{Set, SetBuf};
+
+use crate::database::MainT;
+use crate::DocumentId;
+
+#[derive(Copy, Clone)]
+pub struct Facets {
+    pub(crate) facets: heed::Database<ByteSlice, CowSlice<DocumentId>>,
+}
+
+fn facet_key(attribute: SchemaAttr, value: &str) -> Vec<u8> {
+    let mut key = Vec::with_capacity(2 + value.len());
+    key.extend_from_slice(&attribute.0.to_be_bytes());
+    key.extend_from_slice(value.as_bytes());
+    key
+}
+
+impl Facets {
+    pub fn put_facet_documents(
+        self,
+        writer: &mut heed::RwTxn<MainT>,
+        attribute: SchemaAttr,
+        value: &str,
+        documents_ids: &Set<DocumentId>,
+    ) -> ZResult<()> {
+        let key = facet_key(attribute, value);
+        self.facets.put(writer, &key, documents_ids)
+    }
+
+    pub fn del_facet_documents(
+        self,
+        writer: &mut heed::RwTxn<MainT>,
+        attribute: SchemaAttr,
+        value: &str,
+    ) -> ZResult<bool> {
+        let key = facet_key(attribute, value);
+        self.facets.delete(writer, &key)
+    }
+
+    pub fn clear(self, writer: &mut heed::RwTxn<MainT>) -> ZResult<()> {
+        self.facets.clear(writer)
+    }
+
+    pub fn facet_documents<'txn>(
+        self,
+        reader: &'txn heed::RoTxn<MainT>,
+        attribute: SchemaAttr,
+        value: &str,
+    ) -> ZResult<Option<Cow<'txn, Set<DocumentId>>>> {
+        let key = facet_key(attribute, value);
+        match self.facets.get(reader, &key)? {
+            Some(Cow::Borrowed(slice)) => Ok(Some(Cow::Borrowed(Set::new_unchecked(slice)))),
+            Some(Cow::Owned(vec)) => Ok(Some(Cow::Owned(SetBuf::new_unchecked(vec)))),
+            None => Ok(None),
+        }
+    }
+
+    pub fn attribute_facets<'txn>(
+        self,
+        reader: &'txn heed::RoTxn<MainT>,
+        attribute: SchemaAttr,
+    ) -> ZResult<FacetsIter<'txn>> {
+        let start = facet_key(attribute, "");
+        let range = match attribute.next() {
+            Some(next) => {
+                let end = facet_key(next, "");
+                self.facets.range(reader, (Bound::Included(&start[..]), Bound::Excluded(&end[..])))?
+            }
+            None => self.facets.range(reader, (Bound::Included(&start[..]), Bound::Unbounded))?,
+        };
+        Ok(FacetsIter { iter: range })
+    }
+}
+
+pub struct FacetsIter<'txn> {
+    iter: heed::RoRange<'txn, ByteSlice, CowSlice<DocumentId>>,
+}
+
+impl<'txn> Iterator for FacetsIter<'txn> {
+    type Item = ZResult<(&'txn str, Cow<'txn, Set<DocumentId>>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.iter.next() {
+            Some(Ok((key, documents_ids))) => {
+                let value = std::str::from_utf8(&key[2..]).unwrap_or_default();
+                let documents_ids = match documents_ids {
+                    Cow::Borrowed(slice) => Cow::Borrowed(Set::new_unchecked(slice)),
+                    Cow::Owned(vec) => Cow::Owned(SetBuf::new_unchecked(vec)),
+                };
+                Some(Ok((value, documents_ids)))
+            }
+            Some(Err(e)) => Some(Err(e)),
+            None => None,
+        }
+    }
+}
@@ -1,21 +1,33 @@
 use crate::database::MainT;
-use crate::RankedMap;
+use crate::update::SynonymsDefinitions;
+use crate::{CustomSeparators, GeoMap, Language, PrefixSearch, RankedMap, TypoTolerance};
 use chrono::{DateTime, Utc};
 use heed::types::{ByteSlice, OwnedType, SerdeBincode, Str};
 use heed::Result as ZResult;
-use meilisearch_schema::Schema;
+use indexmap::IndexMap;
+use meilisearch_schema::{Schema, SchemaAttr, SchemaBuilder, SchemaProps};
+use serde::Deserialize;
 use std::collections::HashMap;
 use std::sync::Arc;
 
+const ATTRIBUTES_FOR_FACETING_KEY: &str = "attributes-for-faceting";
 const CREATED_AT_KEY: &str = "created-at";
 const CUSTOMS_KEY: &str = "customs-key";
 const FIELDS_FREQUENCY_KEY: &str = "fields-frequency";
+const GEO_MAP_KEY: &str = "geo-map";
+const LEGACY_SCHEMA_KEY: &str = "schema";
 const NAME_KEY: &str = "name";
 const NUMBER_OF_DOCUMENTS_KEY: &str = "number-of-documents";
+const PREFIX_SEARCH_KEY: &str = "prefix-search";
 const RANKED_MAP_KEY: &str = "ranked-map";
-const SCHEMA_KEY: &str = "schema";
+const SCHEMA_KEY: &str = "schema-v2";
+const SEPARATORS_KEY: &str = "separators";
+const STEMMING_LANGUAGE_KEY: &str = "stemming-language";
 const STOP_WORDS_KEY: &str = "stop-words";
 const SYNONYMS_KEY: &str = "synonyms";
+const SYNONYMS_DEFINITIONS_KEY: &str = "synonyms-definitions";
+const TOKENIZER_KEY: &str = "tokenizer";
+const TYPO_TOLERANCE_KEY: &str = "typo-tolerance";
 const UPDATED_AT_KEY: &str = "updated-at";
 const WORDS_KEY: &str = "words";
 
@@ -23,6 +35,32 @@ pub type FreqsMap = HashMap<String, usize>;
 type SerdeFreqsMap = SerdeBincode<FreqsMap>;
 type SerdeDatetime = SerdeBincode<DateTime<Utc>>;
 
+/// The bincode layout of the schemas stored before the geo attribute kind,
+/// these schemas are read under the legacy key and written back under the new one.
+#[derive(Deserialize)]
+struct LegacySchema {
+    identifier: String,
+    attributes: IndexMap<String, LegacySchemaProps>,
+}
+
+#[derive(Deserialize)]
+struct LegacySchemaProps {
+    displayed: bool,
+    indexed: bool,
+    ranked: bool,
+}
+
+impl From<LegacySchema> for Schema {
+    fn from(legacy: LegacySchema) -> Schema {
+        let mut builder = SchemaBuilder::with_identifier(legacy.identifier);
+        for (name, props) in legacy.attributes {
+            let LegacySchemaProps { displayed, indexed, ranked } = props;
+            builder.new_attribute(name, SchemaProps { displayed, indexed, ranked, geo: false });
+        }
+        builder.build()
+    }
+}
+
 #[derive(Copy, Clone)]
 pub struct Main {
     pub(crate) main: heed::PolyDatabase,
@@ -91,13 +129,19 @@ impl Main {
     }
 
     pub fn put_schema(self, writer: &mut heed::RwTxn<MainT>, schema: &Schema) -> ZResult<()> {
+        self.main.delete::<_, Str>(writer, LEGACY_SCHEMA_KEY)?;
         self.main
             .put::<_, Str, SerdeBincode<Schema>>(writer, SCHEMA_KEY, schema)
     }
 
     pub fn schema(self, reader: &heed::RoTxn<MainT>) -> ZResult<Option<Schema>> {
-        self.main
-            .get::<_, Str, SerdeBincode<Schema>>(reader, SCHEMA_KEY)
+        match self.main.get::<_, Str, SerdeBincode<Schema>>(reader, SCHEMA_KEY)? {
+            Some(schema) => Ok(Some(schema)),
+            None => Ok(self
+                .main
+                .get::<_, Str, SerdeBincode<LegacySchema>>(reader, LEGACY_SCHEMA_KEY)?
+                .map(Schema::from)),
+        }
     }
 
     pub fn put_ranked_map(self, writer: &mut heed::RwTxn<MainT>, ranked_map: &RankedMap) -> ZResult<()> {
@@ -110,6 +154,111 @@ impl Main {
             .get::<_, Str, SerdeBincode<RankedMap>>(reader, RANKED_MAP_KEY)
     }
 
+    pub fn put_geo_map(self, writer: &mut heed::RwTxn<MainT>, geo_map: &GeoMap) -> ZResult<()> {
+        self.main
+            .put::<_, Str, SerdeBincode<GeoMap>>(writer, GEO_MAP_KEY, &geo_map)
+    }
+
+    pub fn geo_map(self, reader: &heed::RoTxn<MainT>) -> ZResult<Option<GeoMap>> {
+        self.main
+            .get::<_, Str, SerdeBincode<GeoMap>>(reader, GEO_MAP_KEY)
+    }
+
+    pub fn put_attributes_for_faceting(
+        self,
+        writer: &mut heed::RwTxn<MainT>,
+        attributes: &[SchemaAttr],
+    ) -> ZResult<()> {
+        self.main
+            .put::<_, Str, SerdeBincode<Vec<SchemaAttr>>>(writer, ATTRIBUTES_FOR_FACETING_KEY, &attributes.to_vec())
+    }
+
+    pub fn attributes_for_faceting(self, reader: &heed::RoTxn<MainT>) -> ZResult<Option<Vec<SchemaAttr>>> {
+        self.main
+            .get::<_, Str, SerdeBincode<Vec<SchemaAttr>>>(reader, ATTRIBUTES_FOR_FACETING_KEY)
+    }
+
+    pub fn put_typo_tolerance(
+        self,
+        writer: &mut heed::RwTxn<MainT>,
+        typo_tolerance: &TypoTolerance,
+    ) -> ZResult<()> {
+        self.main
+            .put::<_, Str, SerdeBincode<TypoTolerance>>(writer, TYPO_TOLERANCE_KEY, typo_tolerance)
+    }
+
+    pub fn typo_tolerance(self, reader: &heed::RoTxn<MainT>) -> ZResult<Option<TypoTolerance>> {
+        self.main
+            .get::<_, Str, SerdeBincode<TypoTolerance>>(reader, TYPO_TOLERANCE_KEY)
+    }
+
+    pub fn put_prefix_search(self, writer: &mut heed::RwTxn<MainT>, prefix_search: PrefixSearch) -> ZResult<()> {
+        self.main
+            .put::<_, Str, SerdeBincode<PrefixSearch>>(writer, PREFIX_SEARCH_KEY, &prefix_search)
+    }
+
+    pub fn prefix_search(self, reader: &heed::RoTxn<MainT>) -> ZResult<Option<PrefixSearch>> {
+        self.main
+            .get::<_, Str, SerdeBincode<PrefixSearch>>(reader, PREFIX_SEARCH_KEY)
+    }
+
+    pub fn put_stemming_language(self, writer: &mut heed::RwTxn<MainT>, language: Language) -> ZResult<()> {
+        self.main
+            .put::<_, Str, SerdeBincode<Language>>(writer, STEMMING_LANGUAGE_KEY, &language)
+    }
+
+    pub fn delete_stemming_language(self, writer: &mut heed::RwTxn<MainT>) -> ZResult<bool> {
+        self.main.delete::<_, Str>(writer, STEMMING_LANGUAGE_KEY)
+    }
+
+    pub fn stemming_language(self, reader: &heed::RoTxn<MainT>) -> ZResult<Option<Language>> {
+        self.main
+            .get::<_, Str, SerdeBincode<Language>>(reader, STEMMING_LANGUAGE_KEY)
+    }
+
+    pub fn put_separators(self, writer: &mut heed::RwTxn<MainT>, separators: &CustomSeparators) -> ZResult<()> {
+        self.main
+            .put::<_, Str, SerdeBincode<CustomSeparators>>(writer, SEPARATORS_KEY, separators)
+    }
+
+    pub fn delete_separators(self, writer: &mut heed::RwTxn<MainT>) -> ZResult<bool> {
+        self.main.delete::<_, Str>(writer, SEPARATORS_KEY)
+    }
+
+    pub fn separators(self, reader: &heed::RoTxn<MainT>) -> ZResult<Option<CustomSeparators>> {
+        self.main
+            .get::<_, Str, SerdeBincode<CustomSeparators>>(reader, SEPARATORS_KEY)
+    }
+
+    pub fn put_tokenizer(self, writer: &mut heed::RwTxn<MainT>, name: &str) -> ZResult<()> {
+        self.main.put::<_, Str, Str>(writer, TOKENIZER_KEY, name)
+    }
+
+    pub fn delete_tokenizer(self, writer: &mut heed::RwTxn<MainT>) -> ZResult<bool> {
+        self.main.delete::<_, Str>(writer, TOKENIZER_KEY)
+    }
+
+    pub fn tokenizer(self, reader: &heed::RoTxn<MainT>) -> ZResult<Option<String>> {
+        Ok(self
+            .main
+            .get::<_, Str, Str>(reader, TOKENIZER_KEY)?
+            .map(|name| name.to_owned()))
+    }
+
+    pub fn put_synonyms_definitions(
+        self,
+        writer: &mut heed::RwTxn<MainT>,
+        definitions: &SynonymsDefinitions,
+    ) -> ZResult<()> {
+        self.main
+            .put::<_, Str, SerdeBincode<SynonymsDefinitions>>(writer, SYNONYMS_DEFINITIONS_KEY, definitions)
+    }
+
+    pub fn synonyms_definitions(self, reader: &heed::RoTxn<MainT>) -> ZResult<Option<SynonymsDefinitions>> {
+        self.main
+            .get::<_, Str, SerdeBincode<SynonymsDefinitions>>(reader, SYNONYMS_DEFINITIONS_KEY)
+    }
+
     pub fn put_synonyms_fst(self, writer: &mut heed::RwTxn<MainT>, fst: &fst::Set) -> ZResult<()> {
         let bytes = fst.as_fst().as_bytes();
         self.main.put::<_, Str, ByteSlice>(writer, SYNONYMS_KEY, bytes)
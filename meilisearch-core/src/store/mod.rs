@@ -3,6 +3,7 @@ mod prefix_documents_cache;
 mod prefix_postings_lists_cache;
 mod documents_fields;
 mod documents_fields_counts;
+mod facets;
 mod main;
 mod postings_lists;
 mod synonyms;
@@ -16,6 +17,7 @@ pub use self::documents_fields::{DocumentFieldsIter, DocumentsFields};
 pub use self::documents_fields_counts::{
     DocumentFieldsCountsIter, DocumentsFieldsCounts, DocumentsIdsIter,
 };
+pub use self::facets::{Facets, FacetsIter};
 pub use self::main::Main;
 pub use self::postings_lists::PostingsLists;
 pub use self::synonyms::Synonyms;
@@ -38,7 +40,7 @@ use crate::criterion::Criteria;
 use crate::database::{UpdateEvent, UpdateEventsEmitter};
 use crate::database::{MainT, UpdateT};
 use crate::serde::Deserializer;
-use crate::{query_builder::QueryBuilder, update, DocIndex, DocumentId, Error, MResult};
+use crate::{query_builder::QueryBuilder, update, CustomSeparators, DocIndex, DocumentId, Error, Language, MResult, PrefixSearch, TypoTolerance};
 
 type BEU64 = zerocopy::U64<byteorder::BigEndian>;
 type BEU16 = zerocopy::U16<byteorder::BigEndian>;
@@ -172,6 +174,10 @@ fn prefix_postings_lists_cache_name(name: &str) -> String {
     format!("store-{}-prefix-postings-lists-cache", name)
 }
 
+fn facets_name(name: &str) -> String {
+    format!("store-{}-facets", name)
+}
+
 fn updates_name(name: &str) -> String {
     format!("store-{}-updates", name)
 }
@@ -190,6 +196,7 @@ pub struct Index {
     pub docs_words: DocsWords,
     pub prefix_documents_cache: PrefixDocumentsCache,
     pub prefix_postings_lists_cache: PrefixPostingsListsCache,
+    pub facets: Facets,
 
     pub updates: Updates,
     pub updates_results: UpdatesResults,
@@ -287,6 +294,83 @@ impl Index {
         )
     }
 
+    pub fn synonyms_addition(&self) -> update::SynonymsAddition {
+        update::SynonymsAddition::new(
+            self.updates,
+            self.updates_results,
+            self.updates_notifier.clone(),
+        )
+    }
+
+    pub fn synonyms_deletion(&self) -> update::SynonymsDeletion {
+        update::SynonymsDeletion::new(
+            self.updates,
+            self.updates_results,
+            self.updates_notifier.clone(),
+        )
+    }
+
+    /// Returns the synonyms of the index grouped by type.
+    pub fn synonyms_definitions(&self, reader: &heed::RoTxn<MainT>) -> MResult<update::SynonymsDefinitions> {
+        update::synonyms_definitions(reader, self.main, self.synonyms)
+    }
+
+    pub fn facets_update(&self, writer: &mut heed::RwTxn<UpdateT>, attributes: Vec<String>) -> MResult<u64> {
+        let _ = self.updates_notifier.send(UpdateEvent::NewUpdate);
+        update::push_facets_update(writer, self.updates, self.updates_results, attributes)
+    }
+
+    pub fn typo_tolerance_update(
+        &self,
+        writer: &mut heed::RwTxn<UpdateT>,
+        typo_tolerance: TypoTolerance,
+    ) -> MResult<u64> {
+        let _ = self.updates_notifier.send(UpdateEvent::NewUpdate);
+        update::push_typo_tolerance_update(writer, self.updates, self.updates_results, typo_tolerance)
+    }
+
+    pub fn prefix_search_update(
+        &self,
+        writer: &mut heed::RwTxn<UpdateT>,
+        prefix_search: PrefixSearch,
+    ) -> MResult<u64> {
+        let _ = self.updates_notifier.send(UpdateEvent::NewUpdate);
+        update::push_prefix_search_update(writer, self.updates, self.updates_results, prefix_search)
+    }
+
+    /// Enables the stemming of the words with the given language or disables it,
+    /// all the documents are reindexed when the language changes.
+    pub fn stemming_update(
+        &self,
+        writer: &mut heed::RwTxn<UpdateT>,
+        language: Option<Language>,
+    ) -> MResult<u64> {
+        let _ = self.updates_notifier.send(UpdateEvent::NewUpdate);
+        update::push_stemming_update(writer, self.updates, self.updates_results, language)
+    }
+
+    /// Selects the registered tokenizer that splits the documents texts and the queries,
+    /// `None` selects the default one, all the documents are reindexed when it changes.
+    pub fn tokenizer_update(
+        &self,
+        writer: &mut heed::RwTxn<UpdateT>,
+        name: Option<String>,
+    ) -> MResult<u64> {
+        let _ = self.updates_notifier.send(UpdateEvent::NewUpdate);
+        update::push_tokenizer_update(writer, self.updates, self.updates_results, name)
+    }
+
+    /// Declares the characters that split the words or never split them when the
+    /// default tokenizer is used, all the documents are reindexed when they change.
+    pub fn separators_update(
+        &self,
+        writer: &mut heed::RwTxn<UpdateT>,
+        separators: CustomSeparators,
+    ) -> MResult<u64> {
+        let _ = self.updates_notifier.send(UpdateEvent::NewUpdate);
+        update::push_separators_update(writer, self.updates, self.updates_results, separators)
+    }
+
     pub fn stop_words_addition(&self) -> update::StopWordsAddition {
         update::StopWordsAddition::new(
             self.updates,
@@ -352,8 +436,10 @@ impl Index {
             self.postings_lists,
             self.documents_fields_counts,
             self.synonyms,
+            self.docs_words,
             self.prefix_documents_cache,
             self.prefix_postings_lists_cache,
+            self.facets,
         )
     }
 
@@ -366,8 +452,10 @@ impl Index {
             self.postings_lists,
             self.documents_fields_counts,
             self.synonyms,
+            self.docs_words,
             self.prefix_documents_cache,
             self.prefix_postings_lists_cache,
+            self.facets,
             criteria,
         )
     }
@@ -388,6 +476,7 @@ pub fn create(
     let docs_words_name = docs_words_name(name);
     let prefix_documents_cache_name = prefix_documents_cache_name(name);
     let prefix_postings_lists_cache_name = prefix_postings_lists_cache_name(name);
+    let facets_name = facets_name(name);
     let updates_name = updates_name(name);
     let updates_results_name = updates_results_name(name);
 
@@ -400,6 +489,7 @@ pub fn create(
     let docs_words = env.create_database(Some(&docs_words_name))?;
     let prefix_documents_cache = env.create_database(Some(&prefix_documents_cache_name))?;
     let prefix_postings_lists_cache = env.create_database(Some(&prefix_postings_lists_cache_name))?;
+    let facets = env.create_database(Some(&facets_name))?;
     let updates = update_env.create_database(Some(&updates_name))?;
     let updates_results = update_env.create_database(Some(&updates_results_name))?;
 
@@ -412,6 +502,7 @@ pub fn create(
         docs_words: DocsWords { docs_words },
         prefix_postings_lists_cache: PrefixPostingsListsCache { prefix_postings_lists_cache },
         prefix_documents_cache: PrefixDocumentsCache { prefix_documents_cache },
+        facets: Facets { facets },
         updates: Updates { updates },
         updates_results: UpdatesResults { updates_results },
         updates_notifier,
@@ -433,6 +524,7 @@ pub fn open(
     let docs_words_name = docs_words_name(name);
     let prefix_documents_cache_name = prefix_documents_cache_name(name);
     let prefix_postings_lists_cache_name = prefix_postings_lists_cache_name(name);
+    let facets_name = facets_name(name);
     let updates_name = updates_name(name);
     let updates_results_name = updates_results_name(name);
 
@@ -469,6 +561,10 @@ pub fn open(
         Some(prefix_postings_lists_cache) => prefix_postings_lists_cache,
         None => return Ok(None),
     };
+    let facets = match env.open_database(Some(&facets_name))? {
+        Some(facets) => facets,
+        None => return Ok(None),
+    };
     let updates = match update_env.open_database(Some(&updates_name))? {
         Some(updates) => updates,
         None => return Ok(None),
@@ -507,6 +603,7 @@ pub fn clear(
     index.docs_words.clear(writer)?;
     index.prefix_documents_cache.clear(writer)?;
     index.prefix_postings_lists_cache.clear(writer)?;
+    index.facets.clear(writer)?;
     index.updates.clear(update_writer)?;
     index.updates_results.clear(update_writer)?;
     Ok(())
@@ -0,0 +1,122 @@
+use std::cmp;
+
+use fst::{IntoStreamer, Streamer};
+
+use crate::automaton::build_dfa;
+use crate::database::MainT;
+use crate::query_tree::split_query_phrases;
+use crate::tokenizers::index_tokenizer;
+use crate::{store, MResult};
+
+/// The query words shorter than this number of bytes are never corrected,
+/// too many words of the index are close to them.
+const MIN_WORD_LEN: usize = 3;
+
+/// The maximum number of typos used to correct a query word.
+const MAX_TYPOS: u8 = 2;
+
+/// The maximum number of index words visited to correct a query word.
+const MAX_VISITED_WORDS: usize = 1000;
+
+/// The maximum number of closest index words of which the
+/// postings list is read to find the most frequent one.
+const MAX_CANDIDATES: usize = 20;
+
+/// Suggests a corrected version of the query, the query words that are not part
+/// of the index are replaced by the closest and most frequent words of the index.
+///
+/// The words are corrected with one more typo than the search allows, up to two
+/// typos, it returns `None` if no word of the query could be corrected. The double quotes of the
+/// query are kept and the negated words are kept as they are.
+pub fn suggest_query(
+    reader: &heed::RoTxn<MainT>,
+    main_store: store::Main,
+    postings_lists_store: store::PostingsLists,
+    query: &str,
+) -> MResult<Option<String>> {
+    let words_set = match main_store.words_fst(reader)? {
+        Some(words) => words,
+        None => return Ok(None),
+    };
+
+    let typo_tolerance = main_store.typo_tolerance(reader)?.unwrap_or_default();
+    let tokenizer = index_tokenizer(reader, main_store)?;
+
+    let (query_words, phrases, negated) = split_query_phrases(&*tokenizer, query);
+
+    let mut corrected = false;
+    let mut words = Vec::new();
+
+    for word in query_words {
+        if word.len() < MIN_WORD_LEN || words_set.contains(&word) {
+            words.push(word);
+            continue;
+        }
+
+        let typos = cmp::min(typo_tolerance.max_typos(&word) + 1, MAX_TYPOS);
+        let dfa = build_dfa(&word, typos);
+        let mut stream = words_set.search(&dfa).into_stream();
+
+        // the closest words are preferred, only the postings lists
+        // of these ones are read to find the most frequent one
+        let mut best_distance = u8::max_value();
+        let mut candidates = Vec::new();
+        let mut visited = 0;
+        while let Some(input) = stream.next() {
+            visited += 1;
+            if visited > MAX_VISITED_WORDS { break }
+
+            let distance = dfa.eval(input).to_u8();
+            if distance < best_distance {
+                best_distance = distance;
+                candidates.clear();
+            }
+
+            if distance == best_distance && candidates.len() < MAX_CANDIDATES {
+                candidates.push(input.to_vec());
+            }
+        }
+
+        let mut best = None;
+        for input in candidates {
+            let frequency = match postings_lists_store.postings_list(reader, &input)? {
+                Some(postings) => postings.docids.len(),
+                None => continue,
+            };
+
+            if best.as_ref().map_or(true, |(best_frequency, _)| frequency > *best_frequency) {
+                best = Some((frequency, input));
+            }
+        }
+
+        match best.and_then(|(_, input)| String::from_utf8(input).ok()) {
+            Some(suggestion) => {
+                corrected = true;
+                words.push(suggestion);
+            }
+            None => words.push(word),
+        }
+    }
+
+    if !corrected {
+        return Ok(None);
+    }
+
+    let mut parts = Vec::new();
+    let mut i = 0;
+    while i < words.len() {
+        match phrases.iter().find(|range| range.start == i) {
+            Some(range) => {
+                parts.push(format!("\"{}\"", words[range.clone()].join(" ")));
+                i = range.end;
+            }
+            None => {
+                parts.push(words[i].clone());
+                i += 1;
+            }
+        }
+    }
+    parts.extend(negated.iter().map(|word| format!("-{}", word)));
+
+    Ok(Some(parts.join(" ")))
+}
@@ -0,0 +1,60 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use meilisearch_tokenizer::{DefaultTokenizer, Tokenizer};
+use once_cell::sync::OnceCell;
+
+use crate::database::MainT;
+use crate::{store, Error, MResult};
+
+/// The name of the tokenizer used by the indexes that did not select one.
+pub const DEFAULT_TOKENIZER: &str = "default";
+
+pub type ArcTokenizer = Arc<dyn Tokenizer + Send + Sync>;
+
+static TOKENIZERS: OnceCell<RwLock<HashMap<String, ArcTokenizer>>> = OnceCell::new();
+
+fn tokenizers() -> &'static RwLock<HashMap<String, ArcTokenizer>> {
+    TOKENIZERS.get_or_init(|| {
+        let mut tokenizers = HashMap::new();
+        let default: ArcTokenizer = Arc::new(DefaultTokenizer::default());
+        tokenizers.insert(DEFAULT_TOKENIZER.to_string(), default);
+        RwLock::new(tokenizers)
+    })
+}
+
+/// Registers a tokenizer under a name that the indexes can select in their settings.
+///
+/// The tokenizers must be registered before the updates and the searches of the
+/// indexes that select them are processed, registering a tokenizer under an already
+/// used name replaces it but does not reindex the documents of these indexes.
+pub fn register_tokenizer<T>(name: &str, tokenizer: T)
+where
+    T: Tokenizer + Send + Sync + 'static,
+{
+    let mut tokenizers = tokenizers().write().unwrap();
+    tokenizers.insert(name.to_string(), Arc::new(tokenizer));
+}
+
+/// Returns the tokenizer registered under this name.
+pub fn tokenizer(name: &str) -> Option<ArcTokenizer> {
+    let tokenizers = tokenizers().read().unwrap();
+    tokenizers.get(name).cloned()
+}
+
+/// Returns the tokenizer selected by the index, the default tokenizer along
+/// with the custom separators of the index if none was selected.
+///
+/// The registered tokenizers define their own separators rules.
+pub(crate) fn index_tokenizer(
+    reader: &heed::RoTxn<MainT>,
+    main_store: store::Main,
+) -> MResult<ArcTokenizer> {
+    match main_store.tokenizer(reader)? {
+        Some(name) => tokenizer(&name).ok_or(Error::TokenizerNotFound(name)),
+        None => {
+            let separators = main_store.separators(reader)?.unwrap_or_default();
+            Ok(Arc::new(DefaultTokenizer::with_separators(separators)))
+        }
+    }
+}
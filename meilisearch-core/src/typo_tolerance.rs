@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+
+/// The rules that decide how many typos a query word can contain.
+///
+/// By default a word can contain one typo from 5 bytes long
+/// and two typos from 9 bytes long.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct TypoTolerance {
+    pub enabled: bool,
+    pub min_word_len_one_typo: usize,
+    pub min_word_len_two_typos: usize,
+    /// The attributes in which only exact matches are accepted, like SKUs or ISBNs.
+    pub disable_on_attributes: Vec<String>,
+    /// Whether the words only made of digits must be matched exactly.
+    pub disable_on_numbers: bool,
+}
+
+impl Default for TypoTolerance {
+    fn default() -> TypoTolerance {
+        TypoTolerance {
+            enabled: true,
+            min_word_len_one_typo: 5,
+            min_word_len_two_typos: 9,
+            disable_on_attributes: Vec::new(),
+            disable_on_numbers: false,
+        }
+    }
+}
+
+impl TypoTolerance {
+    /// Whether a word can be long enough for two typos without being long enough for one.
+    pub fn has_valid_word_lengths(&self) -> bool {
+        self.min_word_len_two_typos >= self.min_word_len_one_typo
+    }
+
+    /// Returns the maximum number of typos allowed for this query word.
+    pub fn max_typos(&self, word: &str) -> u8 {
+        if !self.enabled {
+            return 0;
+        }
+
+        if self.disable_on_numbers && !word.is_empty() && word.bytes().all(|b| b.is_ascii_digit()) {
+            return 0;
+        }
+
+        if word.len() >= self.min_word_len_two_typos {
+            2
+        } else if word.len() >= self.min_word_len_one_typo {
+            1
+        } else {
+            0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn max_typos() {
+        let tolerance = TypoTolerance::default();
+        assert_eq!(tolerance.max_typos("hell"), 0);
+        assert_eq!(tolerance.max_typos("hello"), 1);
+        assert_eq!(tolerance.max_typos("hellohello"), 2);
+        assert_eq!(tolerance.max_typos("0123456789"), 2);
+
+        let tolerance = TypoTolerance { disable_on_numbers: true, ..TypoTolerance::default() };
+        assert_eq!(tolerance.max_typos("0123456789"), 0);
+        assert_eq!(tolerance.max_typos("hellohello"), 2);
+
+        let tolerance = TypoTolerance { min_word_len_one_typo: 3, ..TypoTolerance::default() };
+        assert_eq!(tolerance.max_typos("hel"), 1);
+
+        let tolerance = TypoTolerance { enabled: false, ..TypoTolerance::default() };
+        assert_eq!(tolerance.max_typos("hellohello"), 0);
+    }
+
+    #[test]
+    fn word_lengths() {
+        assert!(TypoTolerance::default().has_valid_word_lengths());
+
+        let tolerance = TypoTolerance { min_word_len_one_typo: 4, min_word_len_two_typos: 4, ..TypoTolerance::default() };
+        assert!(tolerance.has_valid_word_lengths());
+
+        let tolerance = TypoTolerance { min_word_len_one_typo: 8, min_word_len_two_typos: 6, ..TypoTolerance::default() };
+        assert!(!tolerance.has_valid_word_lengths());
+    }
+}
@@ -1,6 +1,6 @@
 use crate::database::{MainT, UpdateT};
 use crate::update::{next_update_id, Update};
-use crate::{store, MResult, RankedMap};
+use crate::{store, GeoMap, MResult, RankedMap};
 
 pub fn apply_clear_all(
     writer: &mut heed::RwTxn<MainT>,
@@ -8,6 +8,7 @@ pub fn apply_clear_all(
 ) -> MResult<()> {
     index.main.put_words_fst(writer, &fst::Set::default())?;
     index.main.put_ranked_map(writer, &RankedMap::default())?;
+    index.main.put_geo_map(writer, &GeoMap::default())?;
     index.main.put_number_of_documents(writer, |_| 0)?;
     index.documents_fields.clear(writer)?;
     index.documents_fields_counts.clear(writer)?;
@@ -15,6 +16,7 @@ pub fn apply_clear_all(
     index.docs_words.clear(writer)?;
     index.prefix_documents_cache.clear(writer)?;
     index.prefix_postings_lists_cache.clear(writer)?;
+    index.facets.clear(writer)?;
 
     Ok(())
 }
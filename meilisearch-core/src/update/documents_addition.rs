@@ -6,11 +6,14 @@ use serde::{Deserialize, Serialize};
 
 use crate::database::{MainT, UpdateT};
 use crate::database::{UpdateEvent, UpdateEventsEmitter};
+use crate::facets::{apply_facets_addition, register_facet_values, FacetsDelta};
 use crate::raw_indexer::RawIndexer;
 use crate::serde::{extract_document_id, serialize_value, Deserializer, Serializer};
+use crate::stemming::is_stem_key;
 use crate::store;
+use crate::tokenizers::index_tokenizer;
 use crate::update::{apply_documents_deletion, compute_short_prefixes, next_update_id, Update};
-use crate::{Error, MResult, RankedMap};
+use crate::{Error, GeoMap, MResult, RankedMap, Stemmer};
 
 pub struct DocumentsAddition<D> {
     updates_store: store::Updates,
@@ -136,15 +139,32 @@ pub fn apply_documents_addition<'a, 'b>(
         None => RankedMap::default(),
     };
 
+    let mut geo_map = match index.main.geo_map(writer)? {
+        Some(geo_map) => geo_map,
+        None => GeoMap::default(),
+    };
+
     let stop_words = match index.main.stop_words_fst(writer)? {
         Some(stop_words) => stop_words,
         None => fst::Set::default(),
     };
 
+    let tokenizer = index_tokenizer(writer, index.main)?;
+    let stemmer = index.main.stemming_language(writer)?.map(Stemmer::new);
+
+    let facet_attributes = index.main.attributes_for_faceting(writer)?.unwrap_or_default();
+    let mut facets_delta = FacetsDelta::new();
+
     // 3. index the documents fields in the stores
-    let mut indexer = RawIndexer::new(stop_words);
+    let mut indexer = RawIndexer::with_tokenizer(stop_words, tokenizer, stemmer);
 
     for (document_id, document) in documents_additions {
+        for attribute in &facet_attributes {
+            if let Some(value) = document.get(schema.attribute_name(*attribute)) {
+                register_facet_values(&mut facets_delta, *attribute, document_id, value);
+            }
+        }
+
         let serializer = Serializer {
             txn: writer,
             schema: &schema,
@@ -152,6 +172,7 @@ pub fn apply_documents_addition<'a, 'b>(
             document_fields_counts: index.documents_fields_counts,
             indexer: &mut indexer,
             ranked_map: &mut ranked_map,
+            geo_map: &mut geo_map,
             document_id,
         };
 
@@ -162,10 +183,13 @@ pub fn apply_documents_addition<'a, 'b>(
         writer,
         index,
         &ranked_map,
+        &geo_map,
         number_of_inserted_documents,
         indexer,
     )?;
 
+    apply_facets_addition(writer, index.facets, facets_delta)?;
+
     compute_short_prefixes(writer, index)?;
 
     Ok(())
@@ -222,15 +246,32 @@ pub fn apply_documents_partial_addition<'a, 'b>(
         None => RankedMap::default(),
     };
 
+    let mut geo_map = match index.main.geo_map(writer)? {
+        Some(geo_map) => geo_map,
+        None => GeoMap::default(),
+    };
+
     let stop_words = match index.main.stop_words_fst(writer)? {
         Some(stop_words) => stop_words,
         None => fst::Set::default(),
     };
 
+    let tokenizer = index_tokenizer(writer, index.main)?;
+    let stemmer = index.main.stemming_language(writer)?.map(Stemmer::new);
+
+    let facet_attributes = index.main.attributes_for_faceting(writer)?.unwrap_or_default();
+    let mut facets_delta = FacetsDelta::new();
+
     // 3. index the documents fields in the stores
-    let mut indexer = RawIndexer::new(stop_words);
+    let mut indexer = RawIndexer::with_tokenizer(stop_words, tokenizer, stemmer);
 
     for (document_id, document) in documents_additions {
+        for attribute in &facet_attributes {
+            if let Some(value) = document.get(schema.attribute_name(*attribute)) {
+                register_facet_values(&mut facets_delta, *attribute, document_id, value);
+            }
+        }
+
         let serializer = Serializer {
             txn: writer,
             schema: &schema,
@@ -238,6 +279,7 @@ pub fn apply_documents_partial_addition<'a, 'b>(
             document_fields_counts: index.documents_fields_counts,
             indexer: &mut indexer,
             ranked_map: &mut ranked_map,
+            geo_map: &mut geo_map,
             document_id,
         };
 
@@ -248,10 +290,13 @@ pub fn apply_documents_partial_addition<'a, 'b>(
         writer,
         index,
         &ranked_map,
+        &geo_map,
         number_of_inserted_documents,
         indexer,
     )?;
 
+    apply_facets_addition(writer, index.facets, facets_delta)?;
+
     compute_short_prefixes(writer, index)?;
 
     Ok(())
@@ -264,6 +309,7 @@ pub fn reindex_all_documents(writer: &mut heed::RwTxn<MainT>, index: &store::Ind
     };
 
     let mut ranked_map = RankedMap::default();
+    let mut geo_map = GeoMap::default();
 
     // 1. retrieve all documents ids
     let mut documents_ids_to_reindex = Vec::new();
@@ -275,6 +321,7 @@ pub fn reindex_all_documents(writer: &mut heed::RwTxn<MainT>, index: &store::Ind
     // 2. remove the documents posting lists
     index.main.put_words_fst(writer, &fst::Set::default())?;
     index.main.put_ranked_map(writer, &ranked_map)?;
+    index.main.put_geo_map(writer, &geo_map)?;
     index.main.put_number_of_documents(writer, |_| 0)?;
     index.postings_lists.clear(writer)?;
     index.docs_words.clear(writer)?;
@@ -286,8 +333,11 @@ pub fn reindex_all_documents(writer: &mut heed::RwTxn<MainT>, index: &store::Ind
             None => fst::Set::default(),
         };
 
+        let tokenizer = index_tokenizer(writer, index.main)?;
+        let stemmer = index.main.stemming_language(writer)?.map(Stemmer::new);
+
         let number_of_inserted_documents = documents_ids.len();
-        let mut indexer = RawIndexer::new(stop_words);
+        let mut indexer = RawIndexer::with_tokenizer(stop_words, tokenizer, stemmer);
         let mut ram_store = HashMap::new();
 
         for document_id in documents_ids {
@@ -307,6 +357,7 @@ pub fn reindex_all_documents(writer: &mut heed::RwTxn<MainT>, index: &store::Ind
                     index.documents_fields_counts,
                     &mut indexer,
                     &mut ranked_map,
+                    &mut geo_map,
                     &value,
                 )?;
             }
@@ -317,6 +368,7 @@ pub fn reindex_all_documents(writer: &mut heed::RwTxn<MainT>, index: &store::Ind
             writer,
             index,
             &ranked_map,
+            &geo_map,
             number_of_inserted_documents,
             indexer,
         )?;
@@ -331,6 +383,7 @@ pub fn write_documents_addition_index(
     writer: &mut heed::RwTxn<MainT>,
     index: &store::Index,
     ranked_map: &RankedMap,
+    geo_map: &GeoMap,
     number_of_inserted_documents: usize,
     indexer: RawIndexer,
 ) -> MResult<()> {
@@ -338,7 +391,10 @@ pub fn write_documents_addition_index(
     let mut delta_words_builder = SetBuilder::memory();
 
     for (word, delta_set) in indexed.words_doc_indexes {
-        delta_words_builder.insert(&word).unwrap();
+        // the stems are only searched by their keys
+        if !is_stem_key(&word) {
+            delta_words_builder.insert(&word).unwrap();
+        }
 
         let set = match index.postings_lists.postings_list(writer, &word)? {
             Some(postings) => Union::new(&postings.matches, &delta_set).into_set_buf(),
@@ -376,6 +432,7 @@ pub fn write_documents_addition_index(
 
     index.main.put_words_fst(writer, &words)?;
     index.main.put_ranked_map(writer, ranked_map)?;
+    index.main.put_geo_map(writer, geo_map)?;
     index.main.put_number_of_documents(writer, |old| old + number_of_inserted_documents as u64)?;
 
     compute_short_prefixes(writer, index)?;
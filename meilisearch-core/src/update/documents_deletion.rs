@@ -6,10 +6,11 @@ use sdset::{duo::DifferenceByKey, SetBuf, SetOperation};
 
 use crate::database::{MainT, UpdateT};
 use crate::database::{UpdateEvent, UpdateEventsEmitter};
+use crate::facets::{apply_facets_deletion, register_facet_values, FacetsDelta};
 use crate::serde::extract_document_id;
 use crate::store;
 use crate::update::{next_update_id, compute_short_prefixes, Update};
-use crate::{DocumentId, Error, MResult, RankedMap};
+use crate::{DocumentId, Error, GeoMap, MResult, RankedMap};
 
 pub struct DocumentsDeletion {
     updates_store: store::Updates,
@@ -100,6 +101,11 @@ pub fn apply_documents_deletion(
         None => RankedMap::default(),
     };
 
+    let mut geo_map = match index.main.geo_map(writer)? {
+        Some(geo_map) => geo_map,
+        None => GeoMap::default(),
+    };
+
     // collect the ranked attributes according to the schema
     let ranked_attrs: Vec<_> = schema
         .iter()
@@ -114,6 +120,15 @@ pub fn apply_documents_deletion(
         )
         .collect();
 
+    // collect the geo attributes according to the schema
+    let geo_attrs: Vec<_> = schema
+        .iter()
+        .filter_map(|(_, attr, prop)| if prop.is_geo() { Some(attr) } else { None })
+        .collect();
+
+    let facet_attributes = index.main.attributes_for_faceting(writer)?.unwrap_or_default();
+    let mut facets_delta = FacetsDelta::new();
+
     let mut words_document_ids = HashMap::new();
     for id in idset {
         // remove all the ranked attributes from the ranked_map
@@ -121,6 +136,19 @@ pub fn apply_documents_deletion(
             ranked_map.remove(id, *ranked_attr);
         }
 
+        // remove all the geo attributes from the geo_map
+        for geo_attr in &geo_attrs {
+            geo_map.remove(id, *geo_attr);
+        }
+
+        // collect the facet values of the document before its fields are removed
+        for attribute in &facet_attributes {
+            if let Some(bytes) = index.documents_fields.document_attribute(writer, id, *attribute)? {
+                let value: serde_json::Value = serde_json::from_slice(bytes)?;
+                register_facet_values(&mut facets_delta, *attribute, id, &value);
+            }
+        }
+
         if let Some(words) = index.docs_words.doc_words(writer, id)? {
             let mut stream = words.stream();
             while let Some(word) = stream.next() {
@@ -183,8 +211,11 @@ pub fn apply_documents_deletion(
 
     index.main.put_words_fst(writer, &words)?;
     index.main.put_ranked_map(writer, &ranked_map)?;
+    index.main.put_geo_map(writer, &geo_map)?;
     index.main.put_number_of_documents(writer, |old| old - deleted_documents_len)?;
 
+    apply_facets_deletion(writer, index.facets, facets_delta)?;
+
     compute_short_prefixes(writer, index)?;
 
     Ok(())
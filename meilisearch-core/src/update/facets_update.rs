@@ -0,0 +1,60 @@
+use crate::database::{MainT, UpdateT};
+use crate::facets::{apply_facets_addition, register_facet_values, FacetsDelta};
+use crate::update::{next_update_id, Update};
+use crate::{store, Error, MResult};
+
+pub fn apply_facets_update(
+    writer: &mut heed::RwTxn<MainT>,
+    index: &store::Index,
+    attributes: Vec<String>,
+) -> MResult<()> {
+    let schema = match index.main.schema(writer)? {
+        Some(schema) => schema,
+        None => return Err(Error::SchemaMissing),
+    };
+
+    let mut facet_attributes = Vec::with_capacity(attributes.len());
+    for name in attributes {
+        match schema.attribute(&name) {
+            Some(attribute) => facet_attributes.push(attribute),
+            None => return Err(Error::FacetAttributeNotFound(name)),
+        }
+    }
+
+    index.main.put_attributes_for_faceting(writer, &facet_attributes)?;
+    index.facets.clear(writer)?;
+
+    // retrieve all the documents ids to compute the facet values
+    let mut documents_ids = Vec::new();
+    for result in index.documents_fields_counts.documents_ids(writer)? {
+        let document_id = result?;
+        documents_ids.push(document_id);
+    }
+
+    let mut delta = FacetsDelta::new();
+    for document_id in documents_ids {
+        for attribute in &facet_attributes {
+            let bytes = index.documents_fields.document_attribute(writer, document_id, *attribute)?;
+            if let Some(bytes) = bytes {
+                let value: serde_json::Value = serde_json::from_slice(bytes)?;
+                register_facet_values(&mut delta, *attribute, document_id, &value);
+            }
+        }
+    }
+
+    apply_facets_addition(writer, index.facets, delta)
+}
+
+pub fn push_facets_update(
+    writer: &mut heed::RwTxn<UpdateT>,
+    updates_store: store::Updates,
+    updates_results_store: store::UpdatesResults,
+    attributes: Vec<String>,
+) -> MResult<u64> {
+    let last_update_id = next_update_id(writer, updates_store, updates_results_store)?;
+
+    let update = Update::facets_update(attributes);
+    updates_store.put_update(writer, last_update_id, &update)?;
+
+    Ok(last_update_id)
+}
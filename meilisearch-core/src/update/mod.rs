@@ -2,10 +2,18 @@ mod clear_all;
 mod customs_update;
 mod documents_addition;
 mod documents_deletion;
+mod facets_update;
+mod prefix_search_update;
 mod schema_update;
+mod separators_update;
+mod stemming_update;
 mod stop_words_addition;
 mod stop_words_deletion;
+mod synonyms_addition;
+mod synonyms_deletion;
 mod synonyms_update;
+mod tokenizer_update;
+mod typo_tolerance_update;
 
 pub use self::clear_all::{apply_clear_all, push_clear_all};
 pub use self::customs_update::{apply_customs_update, push_customs_update};
@@ -13,10 +21,18 @@ pub use self::documents_addition::{
     apply_documents_addition, apply_documents_partial_addition, DocumentsAddition,
 };
 pub use self::documents_deletion::{apply_documents_deletion, DocumentsDeletion};
+pub use self::facets_update::{apply_facets_update, push_facets_update};
+pub use self::prefix_search_update::{apply_prefix_search_update, push_prefix_search_update};
 pub use self::schema_update::{apply_schema_update, push_schema_update};
+pub use self::separators_update::{apply_separators_update, push_separators_update};
+pub use self::stemming_update::{apply_stemming_update, push_stemming_update};
 pub use self::stop_words_addition::{apply_stop_words_addition, StopWordsAddition};
 pub use self::stop_words_deletion::{apply_stop_words_deletion, StopWordsDeletion};
-pub use self::synonyms_update::{apply_synonyms_update, SynonymsUpdate};
+pub use self::synonyms_addition::{apply_synonyms_addition, SynonymsAddition};
+pub use self::synonyms_deletion::{apply_synonyms_deletion, SynonymsDeletion};
+pub use self::synonyms_update::{apply_synonyms_update, synonyms_definitions, SynonymsDefinitions, SynonymsUpdate};
+pub use self::tokenizer_update::{apply_tokenizer_update, push_tokenizer_update};
+pub use self::typo_tolerance_update::{apply_typo_tolerance_update, push_typo_tolerance_update};
 
 use std::cmp;
 use std::collections::{BTreeMap, BTreeSet, HashMap};
@@ -29,7 +45,7 @@ use serde::{Deserialize, Serialize};
 use fst::{IntoStreamer, Streamer};
 use sdset::Set;
 
-use crate::{store, DocumentId, MResult};
+use crate::{store, CustomSeparators, DocumentId, Language, MResult, PrefixSearch, TypoTolerance};
 use crate::database::{MainT, UpdateT};
 use meilisearch_schema::Schema;
 
@@ -82,23 +98,79 @@ impl Update {
         }
     }
 
-    fn synonyms_update(data: BTreeMap<String, Vec<String>>) -> Update {
+    fn stop_words_addition(data: BTreeSet<String>) -> Update {
         Update {
-            data: UpdateData::SynonymsUpdate(data),
+            data: UpdateData::StopWordsAddition(data),
             enqueued_at: Utc::now(),
         }
     }
 
-    fn stop_words_addition(data: BTreeSet<String>) -> Update {
+    fn stop_words_deletion(data: BTreeSet<String>) -> Update {
         Update {
-            data: UpdateData::StopWordsAddition(data),
+            data: UpdateData::StopWordsDeletion(data),
             enqueued_at: Utc::now(),
         }
     }
 
-    fn stop_words_deletion(data: BTreeSet<String>) -> Update {
+    fn facets_update(data: Vec<String>) -> Update {
         Update {
-            data: UpdateData::StopWordsDeletion(data),
+            data: UpdateData::FacetsUpdate(data),
+            enqueued_at: Utc::now(),
+        }
+    }
+
+    fn typo_tolerance_update(data: TypoTolerance) -> Update {
+        Update {
+            data: UpdateData::TypoToleranceUpdate(data),
+            enqueued_at: Utc::now(),
+        }
+    }
+
+    fn prefix_search_update(data: PrefixSearch) -> Update {
+        Update {
+            data: UpdateData::PrefixSearchUpdate(data),
+            enqueued_at: Utc::now(),
+        }
+    }
+
+    fn synonyms_definitions_update(data: SynonymsDefinitions) -> Update {
+        Update {
+            data: UpdateData::SynonymsDefinitionsUpdate(data),
+            enqueued_at: Utc::now(),
+        }
+    }
+
+    fn synonyms_addition(data: SynonymsDefinitions) -> Update {
+        Update {
+            data: UpdateData::SynonymsAddition(data),
+            enqueued_at: Utc::now(),
+        }
+    }
+
+    fn synonyms_deletion(data: SynonymsDefinitions) -> Update {
+        Update {
+            data: UpdateData::SynonymsDeletion(data),
+            enqueued_at: Utc::now(),
+        }
+    }
+
+    fn stemming_update(data: Option<Language>) -> Update {
+        Update {
+            data: UpdateData::StemmingUpdate(data),
+            enqueued_at: Utc::now(),
+        }
+    }
+
+    fn tokenizer_update(data: Option<String>) -> Update {
+        Update {
+            data: UpdateData::TokenizerUpdate(data),
+            enqueued_at: Utc::now(),
+        }
+    }
+
+    fn separators_update(data: CustomSeparators) -> Update {
+        Update {
+            data: UpdateData::SeparatorsUpdate(data),
             enqueued_at: Utc::now(),
         }
     }
@@ -112,9 +184,19 @@ pub enum UpdateData {
     DocumentsAddition(Vec<HashMap<String, serde_json::Value>>),
     DocumentsPartial(Vec<HashMap<String, serde_json::Value>>),
     DocumentsDeletion(Vec<DocumentId>),
+    /// The one-way synonyms updates enqueued before the synonyms types existed.
     SynonymsUpdate(BTreeMap<String, Vec<String>>),
     StopWordsAddition(BTreeSet<String>),
     StopWordsDeletion(BTreeSet<String>),
+    FacetsUpdate(Vec<String>),
+    TypoToleranceUpdate(TypoTolerance),
+    PrefixSearchUpdate(PrefixSearch),
+    SynonymsDefinitionsUpdate(SynonymsDefinitions),
+    SynonymsAddition(SynonymsDefinitions),
+    SynonymsDeletion(SynonymsDefinitions),
+    StemmingUpdate(Option<Language>),
+    TokenizerUpdate(Option<String>),
+    SeparatorsUpdate(CustomSeparators),
 }
 
 impl UpdateData {
@@ -141,6 +223,23 @@ impl UpdateData {
             UpdateData::StopWordsDeletion(deletion) => UpdateType::StopWordsDeletion {
                 number: deletion.len(),
             },
+            UpdateData::FacetsUpdate(attributes) => UpdateType::FacetsUpdate {
+                number: attributes.len(),
+            },
+            UpdateData::TypoToleranceUpdate(_) => UpdateType::TypoToleranceUpdate,
+            UpdateData::PrefixSearchUpdate(_) => UpdateType::PrefixSearchUpdate,
+            UpdateData::SynonymsDefinitionsUpdate(definitions) => UpdateType::SynonymsUpdate {
+                number: definitions.len(),
+            },
+            UpdateData::SynonymsAddition(addition) => UpdateType::SynonymsAddition {
+                number: addition.len(),
+            },
+            UpdateData::SynonymsDeletion(deletion) => UpdateType::SynonymsDeletion {
+                number: deletion.len(),
+            },
+            UpdateData::StemmingUpdate(_) => UpdateType::StemmingUpdate,
+            UpdateData::TokenizerUpdate(_) => UpdateType::TokenizerUpdate,
+            UpdateData::SeparatorsUpdate(_) => UpdateType::SeparatorsUpdate,
         }
     }
 }
@@ -157,6 +256,14 @@ pub enum UpdateType {
     SynonymsUpdate { number: usize },
     StopWordsAddition { number: usize },
     StopWordsDeletion { number: usize },
+    FacetsUpdate { number: usize },
+    TypoToleranceUpdate,
+    PrefixSearchUpdate,
+    SynonymsAddition { number: usize },
+    SynonymsDeletion { number: usize },
+    StemmingUpdate,
+    TokenizerUpdate,
+    SeparatorsUpdate,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -317,7 +424,7 @@ pub fn update_task<'a, 'b>(
                 number: synonyms.len(),
             };
 
-            let result = apply_synonyms_update(writer, index.main, index.synonyms, synonyms);
+            let result = apply_synonyms_update(writer, index.main, index.synonyms, synonyms.into());
 
             (update_type, result, start.elapsed())
         }
@@ -342,6 +449,95 @@ pub fn update_task<'a, 'b>(
 
             let result = apply_stop_words_deletion(writer, index, stop_words);
 
+            (update_type, result, start.elapsed())
+        }
+        UpdateData::FacetsUpdate(attributes) => {
+            let start = Instant::now();
+
+            let update_type = UpdateType::FacetsUpdate {
+                number: attributes.len(),
+            };
+
+            let result = apply_facets_update(writer, index, attributes);
+
+            (update_type, result, start.elapsed())
+        }
+        UpdateData::TypoToleranceUpdate(typo_tolerance) => {
+            let start = Instant::now();
+
+            let update_type = UpdateType::TypoToleranceUpdate;
+
+            let result = apply_typo_tolerance_update(writer, index, typo_tolerance);
+
+            (update_type, result, start.elapsed())
+        }
+        UpdateData::PrefixSearchUpdate(prefix_search) => {
+            let start = Instant::now();
+
+            let update_type = UpdateType::PrefixSearchUpdate;
+
+            let result = apply_prefix_search_update(writer, index, prefix_search);
+
+            (update_type, result, start.elapsed())
+        }
+        UpdateData::SynonymsDefinitionsUpdate(definitions) => {
+            let start = Instant::now();
+
+            let update_type = UpdateType::SynonymsUpdate {
+                number: definitions.len(),
+            };
+
+            let result = apply_synonyms_update(writer, index.main, index.synonyms, definitions);
+
+            (update_type, result, start.elapsed())
+        }
+        UpdateData::SynonymsAddition(addition) => {
+            let start = Instant::now();
+
+            let update_type = UpdateType::SynonymsAddition {
+                number: addition.len(),
+            };
+
+            let result = apply_synonyms_addition(writer, index.main, index.synonyms, addition);
+
+            (update_type, result, start.elapsed())
+        }
+        UpdateData::SynonymsDeletion(deletion) => {
+            let start = Instant::now();
+
+            let update_type = UpdateType::SynonymsDeletion {
+                number: deletion.len(),
+            };
+
+            let result = apply_synonyms_deletion(writer, index.main, index.synonyms, deletion);
+
+            (update_type, result, start.elapsed())
+        }
+        UpdateData::StemmingUpdate(language) => {
+            let start = Instant::now();
+
+            let update_type = UpdateType::StemmingUpdate;
+
+            let result = apply_stemming_update(writer, index, language);
+
+            (update_type, result, start.elapsed())
+        }
+        UpdateData::TokenizerUpdate(name) => {
+            let start = Instant::now();
+
+            let update_type = UpdateType::TokenizerUpdate;
+
+            let result = apply_tokenizer_update(writer, index, name);
+
+            (update_type, result, start.elapsed())
+        }
+        UpdateData::SeparatorsUpdate(separators) => {
+            let start = Instant::now();
+
+            let update_type = UpdateType::SeparatorsUpdate;
+
+            let result = apply_separators_update(writer, index, separators);
+
             (update_type, result, start.elapsed())
         }
     };
@@ -374,6 +570,11 @@ fn compute_short_prefixes(writer: &mut heed::RwTxn<MainT>, index: &store::Index)
     let pplc_store = index.prefix_postings_lists_cache;
     pplc_store.clear(writer)?;
 
+    // the prefixes are useless when the prefix search is disabled
+    if let Some(PrefixSearch::Disabled) = index.main.prefix_search(writer)? {
+        return Ok(());
+    }
+
     for prefix_len in 1..=2 {
         // compute prefixes and store those in the PrefixPostingsListsCache store.
         let mut previous_prefix: Option<([u8; 4], Vec<_>)> = None;
@@ -0,0 +1,28 @@
+use crate::database::{MainT, UpdateT};
+use crate::update::{compute_short_prefixes, next_update_id, Update};
+use crate::{store, MResult, PrefixSearch};
+
+pub fn apply_prefix_search_update(
+    writer: &mut heed::RwTxn<MainT>,
+    index: &store::Index,
+    prefix_search: PrefixSearch,
+) -> MResult<()> {
+    index.main.put_prefix_search(writer, prefix_search)?;
+
+    // the short prefixes cache is only computed when the prefix search is enabled
+    compute_short_prefixes(writer, index)
+}
+
+pub fn push_prefix_search_update(
+    writer: &mut heed::RwTxn<UpdateT>,
+    updates_store: store::Updates,
+    updates_results_store: store::UpdatesResults,
+    prefix_search: PrefixSearch,
+) -> MResult<u64> {
+    let last_update_id = next_update_id(writer, updates_store, updates_results_store)?;
+
+    let update = Update::prefix_search_update(prefix_search);
+    updates_store.put_update(writer, last_update_id, &update)?;
+
+    Ok(last_update_id)
+}
@@ -29,6 +29,9 @@ pub fn apply_schema_update(
                     if new.ranked != old.ranked {
                         need_full_reindexing = true;
                     }
+                    if new.geo != old.geo {
+                        need_full_reindexing = true;
+                    }
                 }
                 Diff::NewAttr { pos, .. } => {
                     // new attribute not at the end of the schema
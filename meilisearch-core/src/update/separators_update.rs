@@ -0,0 +1,44 @@
+use crate::database::{MainT, UpdateT};
+use crate::update::documents_addition::reindex_all_documents;
+use crate::update::{next_update_id, Update};
+use crate::{store, CustomSeparators, MResult};
+
+pub fn apply_separators_update(
+    writer: &mut heed::RwTxn<MainT>,
+    index: &store::Index,
+    separators: CustomSeparators,
+) -> MResult<()> {
+    if index.main.separators(writer)?.unwrap_or_default() == separators {
+        return Ok(());
+    }
+
+    if separators.is_empty() {
+        index.main.delete_separators(writer)?;
+    } else {
+        index.main.put_separators(writer, &separators)?;
+    }
+
+    // the documents texts must be split
+    // again with the new separators
+    if let Ok(number) = index.main.number_of_documents(writer) {
+        if number > 0 {
+            reindex_all_documents(writer, index)?;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn push_separators_update(
+    writer: &mut heed::RwTxn<UpdateT>,
+    updates_store: store::Updates,
+    updates_results_store: store::UpdatesResults,
+    separators: CustomSeparators,
+) -> MResult<u64> {
+    let last_update_id = next_update_id(writer, updates_store, updates_results_store)?;
+
+    let update = Update::separators_update(separators);
+    updates_store.put_update(writer, last_update_id, &update)?;
+
+    Ok(last_update_id)
+}
@@ -0,0 +1,43 @@
+use crate::database::{MainT, UpdateT};
+use crate::update::documents_addition::reindex_all_documents;
+use crate::update::{next_update_id, Update};
+use crate::{store, Language, MResult};
+
+pub fn apply_stemming_update(
+    writer: &mut heed::RwTxn<MainT>,
+    index: &store::Index,
+    language: Option<Language>,
+) -> MResult<()> {
+    if index.main.stemming_language(writer)? == language {
+        return Ok(());
+    }
+
+    match language {
+        Some(language) => index.main.put_stemming_language(writer, language)?,
+        None => { index.main.delete_stemming_language(writer)?; },
+    }
+
+    // the stems of the documents words must be
+    // computed again with the new stemmer
+    if let Ok(number) = index.main.number_of_documents(writer) {
+        if number > 0 {
+            reindex_all_documents(writer, index)?;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn push_stemming_update(
+    writer: &mut heed::RwTxn<UpdateT>,
+    updates_store: store::Updates,
+    updates_results_store: store::UpdatesResults,
+    language: Option<Language>,
+) -> MResult<u64> {
+    let last_update_id = next_update_id(writer, updates_store, updates_results_store)?;
+
+    let update = Update::stemming_update(language);
+    updates_store.put_update(writer, last_update_id, &update)?;
+
+    Ok(last_update_id)
+}
@@ -0,0 +1,80 @@
+use crate::database::{MainT, UpdateT};
+use crate::database::{UpdateEvent, UpdateEventsEmitter};
+use crate::update::synonyms_update::{apply_synonyms_update, synonyms_definitions, DefinitionsBuilder};
+use crate::update::{next_update_id, SynonymsDefinitions, Update};
+use crate::{store, MResult};
+
+pub struct SynonymsAddition {
+    updates_store: store::Updates,
+    updates_results_store: store::UpdatesResults,
+    updates_notifier: UpdateEventsEmitter,
+    synonyms: DefinitionsBuilder,
+}
+
+impl SynonymsAddition {
+    pub fn new(
+        updates_store: store::Updates,
+        updates_results_store: store::UpdatesResults,
+        updates_notifier: UpdateEventsEmitter,
+    ) -> SynonymsAddition {
+        SynonymsAddition {
+            updates_store,
+            updates_results_store,
+            updates_notifier,
+            synonyms: DefinitionsBuilder::default(),
+        }
+    }
+
+    pub fn add_synonym<S, T, I>(&mut self, synonym: S, alternatives: I)
+    where
+        S: AsRef<str>,
+        T: AsRef<str>,
+        I: IntoIterator<Item = T>,
+    {
+        self.synonyms.add_one_way(synonym, alternatives)
+    }
+
+    pub fn add_multi_way_synonyms<T, I>(&mut self, synonyms: I)
+    where
+        T: AsRef<str>,
+        I: IntoIterator<Item = T>,
+    {
+        self.synonyms.add_multi_way(synonyms)
+    }
+
+    pub fn finalize(self, writer: &mut heed::RwTxn<UpdateT>) -> MResult<u64> {
+        let _ = self.updates_notifier.send(UpdateEvent::NewUpdate);
+        let update_id = push_synonyms_addition(
+            writer,
+            self.updates_store,
+            self.updates_results_store,
+            self.synonyms.build(),
+        )?;
+        Ok(update_id)
+    }
+}
+
+pub fn push_synonyms_addition(
+    writer: &mut heed::RwTxn<UpdateT>,
+    updates_store: store::Updates,
+    updates_results_store: store::UpdatesResults,
+    addition: SynonymsDefinitions,
+) -> MResult<u64> {
+    let last_update_id = next_update_id(writer, updates_store, updates_results_store)?;
+
+    let update = Update::synonyms_addition(addition);
+    updates_store.put_update(writer, last_update_id, &update)?;
+
+    Ok(last_update_id)
+}
+
+pub fn apply_synonyms_addition(
+    writer: &mut heed::RwTxn<MainT>,
+    main_store: store::Main,
+    synonyms_store: store::Synonyms,
+    addition: SynonymsDefinitions,
+) -> MResult<()> {
+    let mut definitions = synonyms_definitions(writer, main_store, synonyms_store)?;
+    definitions.extend(addition);
+    apply_synonyms_update(writer, main_store, synonyms_store, definitions)
+}
@@ -0,0 +1,87 @@
+use crate::database::{MainT, UpdateT};
+use crate::database::{UpdateEvent, UpdateEventsEmitter};
+use crate::update::synonyms_update::{apply_synonyms_update, synonyms_definitions, DefinitionsBuilder};
+use crate::update::{next_update_id, SynonymsDefinitions, Update};
+use crate::{store, MResult};
+
+pub struct SynonymsDeletion {
+    updates_store: store::Updates,
+    updates_results_store: store::UpdatesResults,
+    updates_notifier: UpdateEventsEmitter,
+    synonyms: DefinitionsBuilder,
+}
+
+impl SynonymsDeletion {
+    pub fn new(
+        updates_store: store::Updates,
+        updates_results_store: store::UpdatesResults,
+        updates_notifier: UpdateEventsEmitter,
+    ) -> SynonymsDeletion {
+        SynonymsDeletion {
+            updates_store,
+            updates_results_store,
+            updates_notifier,
+            synonyms: DefinitionsBuilder::default(),
+        }
+    }
+
+    /// Deletes all the one-way synonyms of this word.
+    pub fn delete_all_alternatives_of<S: AsRef<str>>(&mut self, synonym: S) {
+        self.synonyms.add_bare_one_way(synonym)
+    }
+
+    /// Deletes only the given one-way synonyms of this word.
+    pub fn delete_specific_alternatives_of<S, T, I>(&mut self, synonym: S, alternatives: I)
+    where
+        S: AsRef<str>,
+        T: AsRef<str>,
+        I: IntoIterator<Item = T>,
+    {
+        self.synonyms.add_one_way(synonym, alternatives)
+    }
+
+    /// Deletes the group of multi-way synonyms made of exactly these words.
+    pub fn delete_multi_way_synonyms<T, I>(&mut self, synonyms: I)
+    where
+        T: AsRef<str>,
+        I: IntoIterator<Item = T>,
+    {
+        self.synonyms.add_multi_way(synonyms)
+    }
+
+    pub fn finalize(self, writer: &mut heed::RwTxn<UpdateT>) -> MResult<u64> {
+        let _ = self.updates_notifier.send(UpdateEvent::NewUpdate);
+        let update_id = push_synonyms_deletion(
+            writer,
+            self.updates_store,
+            self.updates_results_store,
+            self.synonyms.build(),
+        )?;
+        Ok(update_id)
+    }
+}
+
+pub fn push_synonyms_deletion(
+    writer: &mut heed::RwTxn<UpdateT>,
+    updates_store: store::Updates,
+    updates_results_store: store::UpdatesResults,
+    deletion: SynonymsDefinitions,
+) -> MResult<u64> {
+    let last_update_id = next_update_id(writer, updates_store, updates_results_store)?;
+
+    let update = Update::synonyms_deletion(deletion);
+    updates_store.put_update(writer, last_update_id, &update)?;
+
+    Ok(last_update_id)
+}
+
+pub fn apply_synonyms_deletion(
+    writer: &mut heed::RwTxn<MainT>,
+    main_store: store::Main,
+    synonyms_store: store::Synonyms,
+    deletion: SynonymsDefinitions,
+) -> MResult<()> {
+    let mut definitions = synonyms_definitions(writer, main_store, synonyms_store)?;
+    definitions.remove(&deletion);
+    apply_synonyms_update(writer, main_store, synonyms_store, definitions)
+}
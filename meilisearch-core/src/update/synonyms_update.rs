@@ -1,7 +1,7 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use fst::SetBuilder;
-use sdset::SetBuf;
+use serde::{Deserialize, Serialize};
 
 use crate::database::{MainT, UpdateT};
 use crate::automaton::normalize_str;
@@ -9,11 +9,138 @@ use crate::database::{UpdateEvent, UpdateEventsEmitter};
 use crate::update::{next_update_id, Update};
 use crate::{store, MResult};
 
+/// The synonyms of an index grouped by type.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SynonymsDefinitions {
+    /// Groups of words in which every word is a synonym of every other.
+    pub multi_way: Vec<BTreeSet<String>>,
+    /// Words that have synonyms, the synonyms do not have the word as synonym.
+    pub one_way: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl SynonymsDefinitions {
+    pub fn len(&self) -> usize {
+        self.multi_way.len() + self.one_way.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.multi_way.is_empty() && self.one_way.is_empty()
+    }
+
+    fn add_multi_way(&mut self, group: BTreeSet<String>) {
+        if group.len() > 1 && !self.multi_way.contains(&group) {
+            self.multi_way.push(group);
+        }
+    }
+
+    fn add_one_way(&mut self, input: String, alternatives: BTreeSet<String>) {
+        if !alternatives.is_empty() {
+            self.one_way.entry(input).or_default().extend(alternatives);
+        }
+    }
+
+    /// Adds the synonyms of `other` to these ones.
+    pub fn extend(&mut self, other: SynonymsDefinitions) {
+        for group in other.multi_way {
+            self.add_multi_way(group);
+        }
+        for (input, alternatives) in other.one_way {
+            self.add_one_way(input, alternatives);
+        }
+    }
+
+    /// Removes the synonyms of `other` from these ones, a one-way
+    /// synonym without alternatives removes all the alternatives of the word.
+    pub fn remove(&mut self, other: &SynonymsDefinitions) {
+        self.multi_way.retain(|group| !other.multi_way.contains(group));
+
+        for (input, alternatives) in &other.one_way {
+            if alternatives.is_empty() {
+                self.one_way.remove(input);
+            } else if let Some(previous) = self.one_way.get_mut(input) {
+                previous.retain(|alt| !alternatives.contains(alt));
+                if previous.is_empty() {
+                    self.one_way.remove(input);
+                }
+            }
+        }
+    }
+
+    /// Returns every word along with all of its synonyms.
+    fn alternatives(&self) -> BTreeMap<String, BTreeSet<String>> {
+        let mut alternatives = BTreeMap::<_, BTreeSet<_>>::new();
+
+        for group in &self.multi_way {
+            for word in group {
+                let others = group.iter().filter(|w| *w != word).cloned();
+                alternatives.entry(normalize_str(word)).or_default().extend(others);
+            }
+        }
+
+        for (input, alts) in &self.one_way {
+            alternatives.entry(normalize_str(input)).or_default().extend(alts.iter().cloned());
+        }
+
+        alternatives
+    }
+}
+
+impl From<BTreeMap<String, Vec<String>>> for SynonymsDefinitions {
+    fn from(one_way: BTreeMap<String, Vec<String>>) -> SynonymsDefinitions {
+        let mut definitions = SynonymsDefinitions::default();
+        for (input, alternatives) in one_way {
+            definitions.add_one_way(input, alternatives.into_iter().collect());
+        }
+        definitions
+    }
+}
+
+/// Collects synonyms definitions by normalizing the words the same way
+/// the query words are, it is shared by the synonyms update builders.
+#[derive(Default)]
+pub(crate) struct DefinitionsBuilder {
+    definitions: SynonymsDefinitions,
+}
+
+impl DefinitionsBuilder {
+    pub fn add_multi_way<T, I>(&mut self, synonyms: I)
+    where
+        T: AsRef<str>,
+        I: IntoIterator<Item = T>,
+    {
+        let group = synonyms.into_iter().map(|s| s.as_ref().to_lowercase()).collect();
+        self.definitions.add_multi_way(group);
+    }
+
+    pub fn add_one_way<S, T, I>(&mut self, synonym: S, alternatives: I)
+    where
+        S: AsRef<str>,
+        T: AsRef<str>,
+        I: IntoIterator<Item = T>,
+    {
+        let synonym = normalize_str(synonym.as_ref());
+        let alternatives = alternatives.into_iter().map(|s| s.as_ref().to_lowercase()).collect();
+        self.definitions.add_one_way(synonym, alternatives);
+    }
+
+    /// Declares a one-way synonym without alternatives, it is only
+    /// meaningful to remove all the alternatives of a word.
+    pub fn add_bare_one_way<S: AsRef<str>>(&mut self, synonym: S) {
+        let synonym = normalize_str(synonym.as_ref());
+        self.definitions.one_way.entry(synonym).or_default();
+    }
+
+    pub fn build(self) -> SynonymsDefinitions {
+        self.definitions
+    }
+}
+
 pub struct SynonymsUpdate {
     updates_store: store::Updates,
     updates_results_store: store::UpdatesResults,
     updates_notifier: UpdateEventsEmitter,
-    synonyms: BTreeMap<String, Vec<String>>,
+    synonyms: DefinitionsBuilder,
 }
 
 impl SynonymsUpdate {
@@ -26,22 +153,28 @@ impl SynonymsUpdate {
             updates_store,
             updates_results_store,
             updates_notifier,
-            synonyms: BTreeMap::new(),
+            synonyms: DefinitionsBuilder::default(),
         }
     }
 
+    /// Declares a one-way synonym, the alternatives
+    /// do not have the synonym as alternative.
     pub fn add_synonym<S, T, I>(&mut self, synonym: S, alternatives: I)
     where
         S: AsRef<str>,
         T: AsRef<str>,
         I: IntoIterator<Item = T>,
     {
-        let synonym = normalize_str(synonym.as_ref());
-        let alternatives = alternatives.into_iter().map(|s| s.as_ref().to_lowercase());
-        self.synonyms
-            .entry(synonym)
-            .or_insert_with(Vec::new)
-            .extend(alternatives);
+        self.synonyms.add_one_way(synonym, alternatives)
+    }
+
+    /// Declares a group of words that are all synonyms of each other.
+    pub fn add_multi_way_synonyms<T, I>(&mut self, synonyms: I)
+    where
+        T: AsRef<str>,
+        I: IntoIterator<Item = T>,
+    {
+        self.synonyms.add_multi_way(synonyms)
     }
 
     pub fn finalize(self, writer: &mut heed::RwTxn<UpdateT>) -> MResult<u64> {
@@ -50,7 +183,7 @@ impl SynonymsUpdate {
             writer,
             self.updates_store,
             self.updates_results_store,
-            self.synonyms,
+            self.synonyms.build(),
         )?;
         Ok(update_id)
     }
@@ -60,29 +193,50 @@ pub fn push_synonyms_update(
     writer: &mut heed::RwTxn<UpdateT>,
     updates_store: store::Updates,
     updates_results_store: store::UpdatesResults,
-    addition: BTreeMap<String, Vec<String>>,
+    definitions: SynonymsDefinitions,
 ) -> MResult<u64> {
     let last_update_id = next_update_id(writer, updates_store, updates_results_store)?;
 
-    let update = Update::synonyms_update(addition);
+    let update = Update::synonyms_definitions_update(definitions);
     updates_store.put_update(writer, last_update_id, &update)?;
 
     Ok(last_update_id)
 }
 
+/// Returns the synonyms definitions of the index, the indexes created
+/// before the synonyms types existed only have one-way synonyms.
+pub fn synonyms_definitions(
+    reader: &heed::RoTxn<MainT>,
+    main_store: store::Main,
+    synonyms_store: store::Synonyms,
+) -> MResult<SynonymsDefinitions> {
+    if let Some(definitions) = main_store.synonyms_definitions(reader)? {
+        return Ok(definitions);
+    }
+
+    let mut definitions = SynonymsDefinitions::default();
+    let synonyms_fst = main_store.synonyms_fst(reader)?.unwrap_or_default();
+    for synonym in synonyms_fst.stream().into_strs()? {
+        let alternatives = synonyms_store.synonyms(reader, synonym.as_bytes())?.unwrap_or_default();
+        let alternatives = alternatives.stream().into_strs()?.into_iter().collect();
+        definitions.add_one_way(synonym, alternatives);
+    }
+
+    Ok(definitions)
+}
+
 pub fn apply_synonyms_update(
     writer: &mut heed::RwTxn<MainT>,
     main_store: store::Main,
     synonyms_store: store::Synonyms,
-    addition: BTreeMap<String, Vec<String>>,
+    definitions: SynonymsDefinitions,
 ) -> MResult<()> {
     let mut synonyms_builder = SetBuilder::memory();
     synonyms_store.clear(writer)?;
-    for (word, alternatives) in addition {
+    for (word, alternatives) in definitions.alternatives() {
         synonyms_builder.insert(&word).unwrap();
 
         let alternatives = {
-            let alternatives = SetBuf::from_dirty(alternatives);
             let mut alternatives_builder = SetBuilder::memory();
             alternatives_builder.extend_iter(alternatives).unwrap();
             let bytes = alternatives_builder.into_inner().unwrap();
@@ -98,6 +252,57 @@ pub fn apply_synonyms_update(
         .unwrap();
 
     main_store.put_synonyms_fst(writer, &synonyms)?;
+    main_store.put_synonyms_definitions(writer, &definitions)?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(words: &[&str]) -> BTreeSet<String> {
+        words.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn synonyms_alternatives() {
+        let mut builder = DefinitionsBuilder::default();
+        builder.add_multi_way(&["car", "automobile", "vehicle"]);
+        builder.add_one_way("phone", &["iPhone"]);
+        let definitions = builder.build();
+
+        let alternatives = definitions.alternatives();
+        assert_eq!(alternatives["car"], set(&["automobile", "vehicle"]));
+        assert_eq!(alternatives["vehicle"], set(&["automobile", "car"]));
+        assert_eq!(alternatives["phone"], set(&["iphone"]));
+        assert!(alternatives.get("iphone").is_none());
+    }
+
+    #[test]
+    fn synonyms_edition() {
+        let mut builder = DefinitionsBuilder::default();
+        builder.add_multi_way(&["car", "automobile"]);
+        builder.add_one_way("phone", &["iphone", "android"]);
+        let mut definitions = builder.build();
+
+        let mut builder = DefinitionsBuilder::default();
+        builder.add_multi_way(&["automobile", "car"]);
+        builder.add_one_way("phone", &["pixel"]);
+        definitions.extend(builder.build());
+        assert_eq!(definitions.multi_way.len(), 1);
+        assert_eq!(definitions.one_way["phone"], set(&["android", "iphone", "pixel"]));
+
+        let mut builder = DefinitionsBuilder::default();
+        builder.add_multi_way(&["car", "automobile"]);
+        builder.add_one_way("phone", &["android"]);
+        definitions.remove(&builder.build());
+        assert!(definitions.multi_way.is_empty());
+        assert_eq!(definitions.one_way["phone"], set(&["iphone", "pixel"]));
+
+        let mut builder = DefinitionsBuilder::default();
+        builder.add_bare_one_way("phone");
+        definitions.remove(&builder.build());
+        assert!(definitions.is_empty());
+    }
+}
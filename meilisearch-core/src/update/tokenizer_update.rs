@@ -0,0 +1,53 @@
+use crate::database::{MainT, UpdateT};
+use crate::tokenizers::{tokenizer, DEFAULT_TOKENIZER};
+use crate::update::documents_addition::reindex_all_documents;
+use crate::update::{next_update_id, Update};
+use crate::{store, Error, MResult};
+
+pub fn apply_tokenizer_update(
+    writer: &mut heed::RwTxn<MainT>,
+    index: &store::Index,
+    name: Option<String>,
+) -> MResult<()> {
+    // selecting the default tokenizer is the same as not selecting any
+    let name = name.filter(|name| name != DEFAULT_TOKENIZER);
+
+    if let Some(name) = &name {
+        if tokenizer(name).is_none() {
+            return Err(Error::TokenizerNotFound(name.clone()));
+        }
+    }
+
+    if index.main.tokenizer(writer)? == name {
+        return Ok(());
+    }
+
+    match &name {
+        Some(name) => index.main.put_tokenizer(writer, name)?,
+        None => { index.main.delete_tokenizer(writer)?; },
+    }
+
+    // the documents texts must be split
+    // again with the new tokenizer
+    if let Ok(number) = index.main.number_of_documents(writer) {
+        if number > 0 {
+            reindex_all_documents(writer, index)?;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn push_tokenizer_update(
+    writer: &mut heed::RwTxn<UpdateT>,
+    updates_store: store::Updates,
+    updates_results_store: store::UpdatesResults,
+    name: Option<String>,
+) -> MResult<u64> {
+    let last_update_id = next_update_id(writer, updates_store, updates_results_store)?;
+
+    let update = Update::tokenizer_update(name);
+    updates_store.put_update(writer, last_update_id, &update)?;
+
+    Ok(last_update_id)
+}
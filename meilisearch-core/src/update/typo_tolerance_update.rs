@@ -0,0 +1,45 @@
+use crate::database::{MainT, UpdateT};
+use crate::update::{next_update_id, Update};
+use crate::{store, Error, MResult, TypoTolerance};
+
+pub fn apply_typo_tolerance_update(
+    writer: &mut heed::RwTxn<MainT>,
+    index: &store::Index,
+    typo_tolerance: TypoTolerance,
+) -> MResult<()> {
+    let schema = match index.main.schema(writer)? {
+        Some(schema) => schema,
+        None => return Err(Error::SchemaMissing),
+    };
+
+    if !typo_tolerance.has_valid_word_lengths() {
+        return Err(Error::InvalidTypoWordLengths {
+            one_typo: typo_tolerance.min_word_len_one_typo,
+            two_typos: typo_tolerance.min_word_len_two_typos,
+        });
+    }
+
+    for name in &typo_tolerance.disable_on_attributes {
+        if schema.attribute(name).is_none() {
+            return Err(Error::TypoAttributeNotFound(name.clone()));
+        }
+    }
+
+    index.main.put_typo_tolerance(writer, &typo_tolerance)?;
+
+    Ok(())
+}
+
+pub fn push_typo_tolerance_update(
+    writer: &mut heed::RwTxn<UpdateT>,
+    updates_store: store::Updates,
+    updates_results_store: store::UpdatesResults,
+    typo_tolerance: TypoTolerance,
+) -> MResult<u64> {
+    let last_update_id = next_update_id(writer, updates_store, updates_results_store)?;
+
+    let update = Update::typo_tolerance_update(typo_tolerance);
+    updates_store.put_update(writer, last_update_id, &update)?;
+
+    Ok(last_update_id)
+}
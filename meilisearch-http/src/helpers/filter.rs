@@ -0,0 +1,550 @@
+use std::cmp::Ordering;
+use std::iter::Peekable;
+use std::str::{CharIndices, FromStr};
+
+use meilisearch_core::{DocumentId, GeoMap, GeoPoint, Index, MainT, Number, RankedMap};
+use meilisearch_schema::{Schema, SchemaAttr};
+use serde_json::Value;
+
+use crate::helpers::meilisearch::Error;
+
+/// A boolean filter expression, parsed from strings like
+/// `(genre:comedy OR genre:drama) AND NOT studio:acme`.
+///
+/// Ranked attributes can also be compared to numbers with `=`, `!=`, `>`,
+/// `>=`, `<`, `<=` or `BETWEEN low AND high`; these conditions are evaluated
+/// against the numbers stored in the `RankedMap`.
+///
+/// `_geoRadius(lat, lng, meters)` only accepts the documents whose geo
+/// attribute is located in the circle of the given center and radius.
+///
+/// `NOT` binds tighter than `AND` which binds tighter than `OR`,
+/// parentheses can be used to change the evaluation order.
+///
+/// The value of an `attribute:value` condition spans up to the next operator
+/// or parenthesis, `studio:walt disney` is equivalent to `studio:"walt disney"`.
+/// The values containing an operator or a parenthesis must be quoted.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    Condition(Condition),
+    And(Box<Filter>, Box<Filter>),
+    Or(Box<Filter>, Box<Filter>),
+    Not(Box<Filter>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    Equal(SchemaAttr, String),
+    Comparison(SchemaAttr, Comparison, Number),
+    Between(SchemaAttr, Number, Number),
+    GeoRadius(SchemaAttr, GeoPoint, f64),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Comparison {
+    Equal,
+    NotEqual,
+    Greater,
+    GreaterOrEqual,
+    Lower,
+    LowerOrEqual,
+}
+
+impl Comparison {
+    fn accept(self, ordering: Ordering) -> bool {
+        match self {
+            Comparison::Equal => ordering == Ordering::Equal,
+            Comparison::NotEqual => ordering != Ordering::Equal,
+            Comparison::Greater => ordering == Ordering::Greater,
+            Comparison::GreaterOrEqual => ordering != Ordering::Less,
+            Comparison::Lower => ordering == Ordering::Less,
+            Comparison::LowerOrEqual => ordering != Ordering::Greater,
+        }
+    }
+}
+
+impl Filter {
+    pub fn parse(input: &str, schema: &Schema) -> Result<Filter, Error> {
+        let tokens = tokenize(input)?;
+        let mut parser = Parser { tokens, position: 0, schema };
+
+        let filter = parser.parse_or()?;
+        match parser.peek() {
+            None => Ok(filter),
+            Some(token) => Err(Error::FilterParsing(format!("unexpected {}", token))),
+        }
+    }
+
+    pub fn test(
+        &self,
+        reader: &heed::RoTxn<MainT>,
+        index: &Index,
+        ranked_map: &RankedMap,
+        geo_map: &GeoMap,
+        id: DocumentId,
+    ) -> bool {
+        match self {
+            Filter::Condition(condition) => condition.test(reader, index, ranked_map, geo_map, id),
+            Filter::And(lhs, rhs) => {
+                lhs.test(reader, index, ranked_map, geo_map, id)
+                    && rhs.test(reader, index, ranked_map, geo_map, id)
+            }
+            Filter::Or(lhs, rhs) => {
+                lhs.test(reader, index, ranked_map, geo_map, id)
+                    || rhs.test(reader, index, ranked_map, geo_map, id)
+            }
+            Filter::Not(filter) => !filter.test(reader, index, ranked_map, geo_map, id),
+        }
+    }
+}
+
+impl Condition {
+    fn test(
+        &self,
+        reader: &heed::RoTxn<MainT>,
+        index: &Index,
+        ranked_map: &RankedMap,
+        geo_map: &GeoMap,
+        id: DocumentId,
+    ) -> bool {
+        match self {
+            Condition::Equal(attribute, value) => {
+                match index.document_attribute::<Value>(reader, id, *attribute) {
+                    Ok(Some(Value::String(s))) => s.to_lowercase() == *value,
+                    Ok(Some(Value::Bool(b))) => (value == "true" && b) || (value == "false" && !b),
+                    Ok(Some(Value::Array(a))) => a
+                        .into_iter()
+                        .any(|s| s.as_str().map(str::to_lowercase).as_ref() == Some(value)),
+                    _ => false,
+                }
+            }
+            Condition::Comparison(attribute, comparison, number) => {
+                match ranked_map.get(id, *attribute) {
+                    Some(value) => comparison.accept(value.cmp(number)),
+                    None => false,
+                }
+            }
+            Condition::Between(attribute, low, high) => {
+                match ranked_map.get(id, *attribute) {
+                    Some(value) => *low <= value && value <= *high,
+                    None => false,
+                }
+            }
+            Condition::GeoRadius(attribute, center, radius) => {
+                match geo_map.get(id, *attribute) {
+                    Some(point) => point.distance(*center) <= *radius,
+                    None => false,
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    LeftParen,
+    RightParen,
+    Colon,
+    Comma,
+    Comparison(Comparison),
+    And,
+    Or,
+    Not,
+    Between,
+    Word(String),
+    Quoted(String),
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Token::LeftParen => f.write_str("`(`"),
+            Token::RightParen => f.write_str("`)`"),
+            Token::Colon => f.write_str("`:`"),
+            Token::Comma => f.write_str("`,`"),
+            Token::Comparison(Comparison::Equal) => f.write_str("`=`"),
+            Token::Comparison(Comparison::NotEqual) => f.write_str("`!=`"),
+            Token::Comparison(Comparison::Greater) => f.write_str("`>`"),
+            Token::Comparison(Comparison::GreaterOrEqual) => f.write_str("`>=`"),
+            Token::Comparison(Comparison::Lower) => f.write_str("`<`"),
+            Token::Comparison(Comparison::LowerOrEqual) => f.write_str("`<=`"),
+            Token::And => f.write_str("`AND`"),
+            Token::Or => f.write_str("`OR`"),
+            Token::Not => f.write_str("`NOT`"),
+            Token::Between => f.write_str("`BETWEEN`"),
+            Token::Word(word) => write!(f, "`{}`", word),
+            Token::Quoted(word) => write!(f, "`\"{}\"`", word),
+        }
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    !c.is_whitespace() && !"():,\"=!<>".contains(c)
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, Error> {
+    fn take_while<F>(input: &str, chars: &mut Peekable<CharIndices>, start: usize, f: F) -> usize
+    where
+        F: Fn(char) -> bool,
+    {
+        let mut end = input.len();
+        while let Some((i, c)) = chars.peek() {
+            if !f(*c) {
+                end = *i;
+                break;
+            }
+            chars.next();
+        }
+        end.max(start)
+    }
+
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '(' => Token::LeftParen,
+            ')' => Token::RightParen,
+            ':' => Token::Colon,
+            ',' => Token::Comma,
+            '=' => Token::Comparison(Comparison::Equal),
+            '!' | '<' | '>' => {
+                let or_equal = chars.peek().map_or(false, |(_, c)| *c == '=');
+                if or_equal {
+                    chars.next();
+                }
+
+                let comparison = match (c, or_equal) {
+                    ('!', true) => Comparison::NotEqual,
+                    ('<', false) => Comparison::Lower,
+                    ('<', true) => Comparison::LowerOrEqual,
+                    ('>', false) => Comparison::Greater,
+                    ('>', true) => Comparison::GreaterOrEqual,
+                    _ => return Err(Error::FilterParsing(String::from("expected `=` after `!`"))),
+                };
+
+                Token::Comparison(comparison)
+            }
+            '"' => {
+                let start = start + c.len_utf8();
+                let end = take_while(input, &mut chars, start, |c| c != '"');
+                if chars.next().is_none() {
+                    return Err(Error::FilterParsing(String::from("unclosed quote")));
+                }
+                Token::Quoted(input[start..end].to_string())
+            }
+            _ => {
+                let end = take_while(input, &mut chars, start, is_word_char);
+                match &input[start..end] {
+                    "AND" => Token::And,
+                    "OR" => Token::Or,
+                    "NOT" => Token::Not,
+                    "BETWEEN" => Token::Between,
+                    word => Token::Word(word.to_string()),
+                }
+            }
+        };
+
+        tokens.push(token);
+    }
+
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    position: usize,
+    schema: &'a Schema,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn parse_or(&mut self) -> Result<Filter, Error> {
+        let mut lhs = self.parse_and()?;
+        while let Some(Token::Or) = self.peek() {
+            self.next();
+            let rhs = self.parse_and()?;
+            lhs = Filter::Or(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_and(&mut self) -> Result<Filter, Error> {
+        let mut lhs = self.parse_not()?;
+        while let Some(Token::And) = self.peek() {
+            self.next();
+            let rhs = self.parse_not()?;
+            lhs = Filter::And(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_not(&mut self) -> Result<Filter, Error> {
+        match self.peek() {
+            Some(Token::Not) => {
+                self.next();
+                let filter = self.parse_not()?;
+                Ok(Filter::Not(Box::new(filter)))
+            }
+            _ => self.parse_primary(),
+        }
+    }
+
+    fn parse_primary(&mut self) -> Result<Filter, Error> {
+        match self.next() {
+            Some(Token::LeftParen) => {
+                let filter = self.parse_or()?;
+                match self.next() {
+                    Some(Token::RightParen) => Ok(filter),
+                    Some(token) => Err(Error::FilterParsing(format!("expected `)`, found {}", token))),
+                    None => Err(Error::FilterParsing(String::from("unclosed parenthesis"))),
+                }
+            }
+            Some(Token::Word(ref name)) if name == "_geoRadius" => self.parse_geo_radius(),
+            Some(Token::Word(name)) | Some(Token::Quoted(name)) => self.parse_condition(name),
+            Some(token) => Err(Error::FilterParsing(format!("expected a condition, found {}", token))),
+            None => Err(Error::FilterParsing(String::from("expected a condition, found the end of the filter"))),
+        }
+    }
+
+    fn parse_condition(&mut self, name: String) -> Result<Filter, Error> {
+        let attribute = match self.schema.attribute(&name) {
+            Some(attribute) => attribute,
+            None => return Err(Error::UnknownFilteredAttribute),
+        };
+
+        let condition = match self.next() {
+            Some(Token::Colon) => {
+                let value = match self.next() {
+                    Some(Token::Quoted(value)) => value,
+                    Some(Token::Word(value)) => {
+                        // the unquoted value continues until the next operator or parenthesis
+                        let mut words = vec![value];
+                        while let Some(Token::Word(word)) = self.peek() {
+                            words.push(word.clone());
+                            self.next();
+                        }
+                        words.join(" ")
+                    }
+                    _ => return Err(Error::MissingFilterValue),
+                };
+                Condition::Equal(attribute, value.trim().to_lowercase())
+            }
+            Some(Token::Comparison(comparison)) => {
+                self.check_ranked(&name, attribute)?;
+                let number = self.parse_number()?;
+                Condition::Comparison(attribute, comparison, number)
+            }
+            Some(Token::Between) => {
+                self.check_ranked(&name, attribute)?;
+                let low = self.parse_number()?;
+                match self.next() {
+                    Some(Token::And) => (),
+                    Some(token) => return Err(Error::FilterParsing(format!("expected `AND` in the `BETWEEN` condition on `{}`, found {}", name, token))),
+                    None => return Err(Error::MissingFilterValue),
+                }
+                let high = self.parse_number()?;
+                Condition::Between(attribute, low, high)
+            }
+            Some(token) => return Err(Error::FilterParsing(format!("expected `:`, a comparison or `BETWEEN` after `{}`, found {}", name, token))),
+            None => return Err(Error::MissingFilterValue),
+        };
+
+        Ok(Filter::Condition(condition))
+    }
+
+    fn parse_geo_radius(&mut self) -> Result<Filter, Error> {
+        let attribute = match self.schema.geo_attribute() {
+            Some(attribute) => attribute,
+            None => return Err(Error::MissingGeoAttribute),
+        };
+
+        let mut arguments = Vec::with_capacity(3);
+        match self.next() {
+            Some(Token::LeftParen) => (),
+            Some(token) => return Err(Error::FilterParsing(format!("expected `(` after `_geoRadius`, found {}", token))),
+            None => return Err(Error::MissingFilterValue),
+        }
+
+        loop {
+            arguments.push(self.parse_float()?);
+            match self.next() {
+                Some(Token::Comma) => (),
+                Some(Token::RightParen) => break,
+                Some(token) => return Err(Error::FilterParsing(format!("expected `,` or `)` in `_geoRadius`, found {}", token))),
+                None => return Err(Error::FilterParsing(String::from("unclosed parenthesis"))),
+            }
+        }
+
+        match arguments.as_slice() {
+            [lat, lng, radius] => {
+                let center = GeoPoint::new(*lat, *lng);
+                if !center.is_valid() {
+                    return Err(Error::FilterParsing(format!("`{}, {}` is not a valid geo point", lat, lng)));
+                }
+                Ok(Filter::Condition(Condition::GeoRadius(attribute, center, *radius)))
+            }
+            _ => Err(Error::FilterParsing(String::from("`_geoRadius` expects a latitude, a longitude and a radius in meters"))),
+        }
+    }
+
+    fn check_ranked(&self, name: &str, attribute: SchemaAttr) -> Result<(), Error> {
+        if self.schema.props(attribute).is_ranked() {
+            Ok(())
+        } else {
+            Err(Error::UnrankedFilteredAttribute(name.to_string()))
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<Number, Error> {
+        match self.next() {
+            Some(Token::Word(value)) | Some(Token::Quoted(value)) => {
+                Number::from_str(value.trim())
+                    .map_err(|e| Error::FilterParsing(format!("`{}` is not a number; {}", value, e)))
+            }
+            Some(token) => Err(Error::FilterParsing(format!("expected a number, found {}", token))),
+            None => Err(Error::MissingFilterValue),
+        }
+    }
+
+    fn parse_float(&mut self) -> Result<f64, Error> {
+        match self.next() {
+            Some(Token::Word(value)) | Some(Token::Quoted(value)) => {
+                f64::from_str(value.trim())
+                    .map_err(|e| Error::FilterParsing(format!("`{}` is not a number; {}", value, e)))
+            }
+            Some(token) => Err(Error::FilterParsing(format!("expected a number, found {}", token))),
+            None => Err(Error::MissingFilterValue),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use meilisearch_schema::{SchemaBuilder, DISPLAYED, GEO, INDEXED, RANKED};
+
+    fn schema() -> Schema {
+        let mut builder = SchemaBuilder::with_identifier("id");
+        builder.new_attribute("id", DISPLAYED);
+        builder.new_attribute("genre", DISPLAYED | INDEXED);
+        builder.new_attribute("studio", DISPLAYED | INDEXED);
+        builder.new_attribute("year", DISPLAYED | RANKED);
+        builder.new_attribute("location", DISPLAYED | GEO);
+        builder.build()
+    }
+
+    fn condition(attribute: u16, value: &str) -> Filter {
+        Filter::Condition(Condition::Equal(SchemaAttr(attribute), value.to_string()))
+    }
+
+    #[test]
+    fn simple_condition() {
+        let filter = Filter::parse("genre:Comedy", &schema()).unwrap();
+        assert_eq!(filter, condition(1, "comedy"));
+
+        let filter = Filter::parse("studio:\"Walt Disney\"", &schema()).unwrap();
+        assert_eq!(filter, condition(2, "walt disney"));
+
+        // the unquoted values can contain spaces
+        let filter = Filter::parse("studio:Walt Disney", &schema(), GEO_ATTRIBUTE).unwrap();
+        assert_eq!(filter, condition(2, "walt disney"));
+
+        let filter = Filter::parse("studio:Walt Disney AND genre:drama", &schema(), GEO_ATTRIBUTE).unwrap();
+        let expected = Filter::And(Box::new(condition(2, "walt disney")), Box::new(condition(1, "drama")));
+        assert_eq!(filter, expected);
+    }
+
+    #[test]
+    fn operators_precedence() {
+        let filter = Filter::parse("(genre:comedy OR genre:drama) AND NOT studio:acme", &schema()).unwrap();
+        let expected = Filter::And(
+            Box::new(Filter::Or(Box::new(condition(1, "comedy")), Box::new(condition(1, "drama")))),
+            Box::new(Filter::Not(Box::new(condition(2, "acme")))),
+        );
+        assert_eq!(filter, expected);
+
+        let filter = Filter::parse("genre:comedy OR genre:drama AND studio:acme", &schema()).unwrap();
+        let expected = Filter::Or(
+            Box::new(condition(1, "comedy")),
+            Box::new(Filter::And(Box::new(condition(1, "drama")), Box::new(condition(2, "acme")))),
+        );
+        assert_eq!(filter, expected);
+    }
+
+    #[test]
+    fn numeric_conditions() {
+        let year = SchemaAttr(3);
+
+        let filter = Filter::parse("year >= 1990 AND year<2000", &schema()).unwrap();
+        let expected = Filter::And(
+            Box::new(Filter::Condition(Condition::Comparison(year, Comparison::GreaterOrEqual, Number::Unsigned(1990)))),
+            Box::new(Filter::Condition(Condition::Comparison(year, Comparison::Lower, Number::Unsigned(2000)))),
+        );
+        assert_eq!(filter, expected);
+
+        let filter = Filter::parse("year BETWEEN 1990 AND 2000 OR genre:drama", &schema()).unwrap();
+        let expected = Filter::Or(
+            Box::new(Filter::Condition(Condition::Between(year, Number::Unsigned(1990), Number::Unsigned(2000)))),
+            Box::new(condition(1, "drama")),
+        );
+        assert_eq!(filter, expected);
+    }
+
+    #[test]
+    fn geo_radius_condition() {
+        let location = SchemaAttr(4);
+
+        let filter = Filter::parse("_geoRadius(48.8566, 2.3522, 2000) AND genre:drama", &schema()).unwrap();
+        let expected = Filter::And(
+            Box::new(Filter::Condition(Condition::GeoRadius(location, GeoPoint::new(48.8566, 2.3522), 2000.0))),
+            Box::new(condition(1, "drama")),
+        );
+        assert_eq!(filter, expected);
+
+        let parsing = Error::FilterParsing(String::new()).to_string();
+        let parse_error = |filter| Filter::parse(filter, &schema()).unwrap_err().to_string();
+        assert!(parse_error("_geoRadius(48.8566, 2.3522)").starts_with(&parsing));
+        assert!(parse_error("_geoRadius(48.8566 2.3522, 10)").starts_with(&parsing));
+        assert!(parse_error("_geoRadius(98.8566, 2.3522, 10)").starts_with(&parsing));
+
+        let mut builder = SchemaBuilder::with_identifier("id");
+        builder.new_attribute("id", DISPLAYED);
+        let error = Filter::parse("_geoRadius(48.8566, 2.3522, 10)", &builder.build()).unwrap_err();
+        assert_eq!(error.to_string(), Error::MissingGeoAttribute.to_string());
+    }
+
+    #[test]
+    fn invalid_filters() {
+        fn parse_error(filter: &str) -> String {
+            Filter::parse(filter, &schema()).unwrap_err().to_string()
+        }
+
+        let missing_value = Error::MissingFilterValue.to_string();
+        let unknown_attribute = Error::UnknownFilteredAttribute.to_string();
+        let unranked_attribute = Error::UnrankedFilteredAttribute(String::from("genre")).to_string();
+        let parsing = Error::FilterParsing(String::new()).to_string();
+
+        assert_eq!(parse_error("genre"), missing_value);
+        assert_eq!(parse_error("genre:"), missing_value);
+        assert_eq!(parse_error("author:asimov"), unknown_attribute);
+        assert!(parse_error("(genre:comedy").starts_with(&parsing));
+        assert!(parse_error("genre:comedy AND").starts_with(&parsing));
+        assert!(parse_error("genre:comedy genre:drama").starts_with(&parsing));
+        assert!(parse_error("studio:\"acme").starts_with(&parsing));
+        assert_eq!(parse_error("genre > 10"), unranked_attribute);
+        assert!(parse_error("year > recent").starts_with(&parsing));
+        assert!(parse_error("year BETWEEN 1990 2000").starts_with(&parsing));
+        assert!(parse_error("year ! 1990").starts_with(&parsing));
+    }
+}
@@ -1,20 +1,26 @@
+use crate::helpers::filter::Filter;
 use crate::routes::setting::{RankingOrdering, Setting};
 use indexmap::IndexMap;
-use log::{error, warn};
+use log::error;
 use meilisearch_core::criterion::*;
 use meilisearch_core::Highlight;
-use meilisearch_core::{Index, RankedMap};
+use meilisearch_core::{suggest_query, DocumentId, GeoMap, GeoPoint, Index, MatchingStrategy, Number, PrefixSearch, RankedMap, SearchCursor, SortResult};
 use meilisearch_core::MainT;
 use meilisearch_schema::{Schema, SchemaAttr};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
 use std::collections::{HashMap, HashSet};
 use std::convert::From;
+use std::hash::{Hash, Hasher};
 use std::error;
 use std::fmt;
 use std::time::{Duration, Instant};
 
+/// A corrected query is suggested when the query returns less hits than this.
+const SUGGESTION_MAX_HITS: usize = 3;
+
 #[derive(Debug)]
 pub enum Error {
     SearchDocuments(String),
@@ -25,6 +31,13 @@ pub enum Error {
     AttributeNotFoundOnSchema(String),
     MissingFilterValue,
     UnknownFilteredAttribute,
+    UnrankedFilteredAttribute(String),
+    FilterParsing(String),
+    UnknownFacetAttribute(String),
+    MissingGeoAttribute,
+    UnknownDistinctAttribute(String),
+    InvalidRankingRule(String),
+    UnrankedRankingAttribute(String),
     Internal(String),
 }
 
@@ -53,6 +66,23 @@ impl fmt::Display for Error {
             UnknownFilteredAttribute => {
                 f.write_str("a filter is specifying an unknown schema attribute")
             }
+            UnrankedFilteredAttribute(field) => {
+                write!(f, "a numeric filter is specifying the attribute {} which is not ranked", field)
+            }
+            FilterParsing(err) => write!(f, "invalid filter; {}", err),
+            UnknownFacetAttribute(field) => {
+                write!(f, "the attribute {} is not declared in the attributes for faceting", field)
+            }
+            MissingGeoAttribute => f.write_str("the schema does not declare any geo attribute"),
+            UnknownDistinctAttribute(field) => {
+                write!(f, "the distinct attribute {} is not found on schema", field)
+            }
+            InvalidRankingRule(rule) => {
+                write!(f, "the ranking rule {} is invalid, custom rules must be written `attribute:asc` or `attribute:desc`", rule)
+            }
+            UnrankedRankingAttribute(field) => {
+                write!(f, "a ranking rule is specifying the attribute {} which is not ranked", field)
+            }
             Internal(err) => write!(f, "internal error; {}", err),
         }
     }
@@ -80,8 +110,17 @@ impl IndexSearchExt for Index {
             attributes_to_search_in: None,
             attributes_to_highlight: None,
             filters: None,
+            facets_distribution: None,
+            around_lat_lng: None,
+            distinct: None,
+            ranking_rules: None,
             timeout: Duration::from_millis(30),
             matches: false,
+            ranking_info: false,
+            matching_strategy: MatchingStrategy::default(),
+            prefix_search: None,
+            search_after: None,
+            similar_to: None,
         }
     }
 }
@@ -96,8 +135,17 @@ pub struct SearchBuilder<'a> {
     attributes_to_search_in: Option<HashSet<String>>,
     attributes_to_highlight: Option<HashSet<String>>,
     filters: Option<String>,
+    facets_distribution: Option<Vec<String>>,
+    around_lat_lng: Option<GeoPoint>,
+    distinct: Option<Option<String>>,
+    ranking_rules: Option<Vec<String>>,
     timeout: Duration,
     matches: bool,
+    ranking_info: bool,
+    matching_strategy: MatchingStrategy,
+    prefix_search: Option<PrefixSearch>,
+    search_after: Option<SearchCursor>,
+    similar_to: Option<DocumentId>,
 }
 
 impl<'a> SearchBuilder<'a> {
@@ -148,6 +196,29 @@ impl<'a> SearchBuilder<'a> {
         self
     }
 
+    pub fn facets_distribution(&mut self, value: Vec<String>) -> &SearchBuilder {
+        self.facets_distribution = Some(value);
+        self
+    }
+
+    pub fn around_lat_lng(&mut self, value: GeoPoint) -> &SearchBuilder {
+        self.around_lat_lng = Some(value);
+        self
+    }
+
+    /// Overrides the distinct field of the settings for this query,
+    /// `None` disables the distinct rule.
+    pub fn distinct(&mut self, value: Option<String>) -> &SearchBuilder {
+        self.distinct = Some(value);
+        self
+    }
+
+    /// Overrides the ranking rules of the settings for this query.
+    pub fn ranking_rules(&mut self, value: Vec<String>) -> &SearchBuilder {
+        self.ranking_rules = Some(value);
+        self
+    }
+
     pub fn timeout(&mut self, value: Duration) -> &SearchBuilder {
         self.timeout = value;
         self
@@ -158,6 +229,36 @@ impl<'a> SearchBuilder<'a> {
         self
     }
 
+    pub fn show_ranking_info(&mut self) -> &SearchBuilder {
+        self.ranking_info = true;
+        self
+    }
+
+    pub fn matching_strategy(&mut self, value: MatchingStrategy) -> &SearchBuilder {
+        self.matching_strategy = value;
+        self
+    }
+
+    /// Overrides the prefix search mode of the settings for this query.
+    pub fn prefix_search(&mut self, value: PrefixSearch) -> &SearchBuilder {
+        self.prefix_search = Some(value);
+        self
+    }
+
+    /// Returns the hits that are ranked after the hit of the cursor, the offset
+    /// is then relative to this hit. It can not be used along with a distinct field.
+    pub fn search_after(&mut self, value: SearchCursor) -> &SearchBuilder {
+        self.search_after = Some(value);
+        self
+    }
+
+    /// Returns the documents similar to this document instead of
+    /// the ones matching the query, the document itself is excluded.
+    pub fn similar_to(&mut self, value: DocumentId) -> &SearchBuilder {
+        self.similar_to = Some(value);
+        self
+    }
+
     pub fn search(&self, reader: &heed::RoTxn<MainT>) -> Result<SearchResult, Error> {
         let schema = self.index.main.schema(reader);
         let schema = schema.map_err(|e| Error::Internal(e.to_string()))?;
@@ -170,8 +271,18 @@ impl<'a> SearchBuilder<'a> {
         let ranked_map = ranked_map.map_err(|e| Error::Internal(e.to_string()))?;
         let ranked_map = ranked_map.unwrap_or_default();
 
+        let geo_map = self.index.main.geo_map(reader);
+        let geo_map = geo_map.map_err(|e| Error::Internal(e.to_string()))?;
+        let geo_map = geo_map.unwrap_or_default();
+
         // Change criteria
-        let mut query_builder = match self.get_criteria(reader, &ranked_map, &schema)? {
+        let criteria = self.get_criteria(reader, &ranked_map, &geo_map, &schema)?;
+        let ranking_criteria = match &criteria {
+            Some(criteria) => criteria_names(criteria),
+            None => criteria_names(&Criteria::default()),
+        };
+
+        let mut query_builder = match criteria {
             Some(criteria) => self.index.query_builder_with_criteria(criteria),
             None => self.index.query_builder(),
         };
@@ -184,48 +295,96 @@ impl<'a> SearchBuilder<'a> {
         }
 
         if let Some(filters) = &self.filters {
-            let mut split = filters.split(':');
-            match (split.next(), split.next()) {
-                (Some(_), None) | (Some(_), Some("")) => return Err(Error::MissingFilterValue),
-                (Some(attr), Some(value)) => {
-                    let ref_reader = reader;
-                    let ref_index = &self.index;
-                    let value = value.trim().to_lowercase();
-
-                    let attr = match schema.attribute(attr) {
-                        Some(attr) => attr,
-                        None => return Err(Error::UnknownFilteredAttribute),
-                    };
-
-                    query_builder.with_filter(move |id| {
-                        let attr = attr;
-                        let index = ref_index;
-                        let reader = ref_reader;
-
-                        match index.document_attribute::<Value>(reader, id, attr) {
-                            Ok(Some(Value::String(s))) => s.to_lowercase() == value,
-                            Ok(Some(Value::Bool(b))) => {
-                                (value == "true" && b) || (value == "false" && !b)
-                            }
-                            Ok(Some(Value::Array(a))) => {
-                                a.into_iter().any(|s| s.as_str() == Some(&value))
-                            }
-                            _ => false,
-                        }
-                    });
+            let filter = Filter::parse(filters, &schema)?;
+            let ref_reader = reader;
+            let ref_index = &self.index;
+            let ref_ranked_map = &ranked_map;
+            let ref_geo_map = &geo_map;
+
+            query_builder.with_filter(move |id| {
+                filter.test(ref_reader, ref_index, ref_ranked_map, ref_geo_map, id)
+            });
+        }
+
+        let distinct_field = match &self.distinct {
+            Some(distinct) => distinct.clone(),
+            None => self.settings(reader)?.distinct_field,
+        };
+
+        // keep one document for each value of the distinct field
+        if let Some(field) = distinct_field {
+            let attribute = match schema.attribute(&field) {
+                Some(attribute) => attribute,
+                None => return Err(Error::UnknownDistinctAttribute(field)),
+            };
+
+            let ref_reader = reader;
+            let ref_index = &self.index;
+
+            query_builder.with_distinct(move |id| {
+                let documents_fields = ref_index.documents_fields;
+                match documents_fields.document_attribute(ref_reader, id, attribute) {
+                    Ok(Some(bytes)) if bytes != b"null" => {
+                        let mut hasher = DefaultHasher::new();
+                        bytes.hash(&mut hasher);
+                        Some(hasher.finish())
+                    }
+                    _ => None,
+                }
+            }, 1);
+        }
+
+        if let Some(facets) = &self.facets_distribution {
+            let attributes_for_faceting = self.index.main.attributes_for_faceting(reader);
+            let attributes_for_faceting = attributes_for_faceting.map_err(|e| Error::Internal(e.to_string()))?;
+            let attributes_for_faceting = attributes_for_faceting.unwrap_or_default();
+
+            let mut attributes = Vec::with_capacity(facets.len());
+            for name in facets {
+                if name == "*" {
+                    attributes = attributes_for_faceting.clone();
+                    break;
+                }
+
+                match schema.attribute(name) {
+                    Some(attribute) if attributes_for_faceting.contains(&attribute) => {
+                        attributes.push(attribute)
+                    }
+                    _ => return Err(Error::UnknownFacetAttribute(name.to_string())),
                 }
-                (_, _) => (),
             }
+
+            query_builder.with_facets(attributes);
         }
 
         query_builder.with_fetch_timeout(self.timeout);
 
+        if self.ranking_info {
+            query_builder.with_ranking_info();
+        }
+
+        query_builder.with_matching_strategy(self.matching_strategy);
+
+        if let Some(prefix_search) = self.prefix_search {
+            query_builder.with_prefix_search(prefix_search);
+        }
+
+        if let Some(cursor) = &self.search_after {
+            query_builder.with_search_after(cursor.clone());
+        }
+
         let start = Instant::now();
-        let docs = query_builder.query(reader, &self.query, self.offset..(self.offset + self.limit));
+        let range = self.offset..(self.offset + self.limit);
+        let result = match self.similar_to {
+            Some(document_id) => query_builder.similar(reader, document_id, range),
+            None => query_builder.query(reader, &self.query, range),
+        };
+        let result = result.map_err(|e| Error::SearchDocuments(e.to_string()))?;
+        let SortResult { documents, facets, collapsed, nb_hits, exhaustive_nb_hits, cursor } = result;
         let time_ms = start.elapsed().as_millis() as usize;
 
         let mut hits = Vec::with_capacity(self.limit);
-        for doc in docs.map_err(|e| Error::SearchDocuments(e.to_string()))? {
+        for doc in documents {
             // retrieve the content of document in kv store
             let mut fields: Option<HashSet<&str>> = None;
             if let Some(attributes_to_retrieve) = &self.attributes_to_retrieve {
@@ -268,72 +427,137 @@ impl<'a> SearchBuilder<'a> {
 
             let matches_info = if self.matches { Some(matches) } else { None };
 
+            // the distance in meters to the point given in the query
+            let geo_distance = match (self.around_lat_lng, schema.geo_attribute()) {
+                (Some(point), Some(attribute)) => geo_map
+                    .get(doc.id, attribute)
+                    .map(|p| p.distance(point).round() as u64),
+                _ => None,
+            };
+
+            let ranking_info = doc.ranking_info.map(|values| {
+                values
+                    .into_iter()
+                    .map(|(name, value)| (name, number_to_value(value)))
+                    .collect()
+            });
+
             let hit = SearchHit {
                 document,
                 formatted,
                 matches_info,
+                geo_distance,
+                collapsed_count: collapsed.get(&doc.id).cloned(),
+                ranking_info,
             };
 
             hits.push(hit);
         }
 
+        // the query of the following pages was already suggested with the first page
+        let first_page = self.search_after.is_none();
+        let suggested_query = if first_page && nb_hits < SUGGESTION_MAX_HITS && !self.query.trim().is_empty() {
+            suggest_query(reader, self.index.main, self.index.postings_lists, &self.query)
+                .map_err(|e| Error::Internal(e.to_string()))?
+        } else {
+            None
+        };
+
         let results = SearchResult {
             hits,
             offset: self.offset,
             limit: self.limit,
+            nb_hits,
+            exhaustive_nb_hits,
             processing_time_ms: time_ms,
             query: self.query.to_string(),
+            facets_distribution: facets.map(|facets| {
+                facets
+                    .into_iter()
+                    .map(|(attribute, counts)| (schema.attribute_name(attribute).to_string(), counts))
+                    .collect()
+            }),
+            cursor: cursor.map(|cursor| cursor.encode()),
+            suggested_query,
+            ranking_criteria,
         };
 
         Ok(results)
     }
 
+    fn settings(&self, reader: &heed::RoTxn<MainT>) -> Result<Setting, Error> {
+        match self.index.main.customs(reader) {
+            Ok(Some(bytes)) => bincode::deserialize(bytes).map_err(|e| Error::Internal(e.to_string())),
+            Ok(None) => Ok(Setting::default()),
+            Err(e) => Err(Error::Internal(e.to_string())),
+        }
+    }
+
     pub fn get_criteria(
         &self,
         reader: &heed::RoTxn<MainT>,
         ranked_map: &'a RankedMap,
+        geo_map: &'a GeoMap,
         schema: &Schema,
     ) -> Result<Option<Criteria<'a>>, Error> {
-        let current_settings = match self.index.main.customs(reader).unwrap() {
-            Some(bytes) => bincode::deserialize(bytes).unwrap(),
-            None => Setting::default(),
-        };
+        let current_settings = self.settings(reader)?;
 
         let ranking_rules = &current_settings.ranking_rules;
         let ranking_order = &current_settings.ranking_order;
 
+        // sorts the documents by distance to the point given in the query, it is placed
+        // where the `_geo` rule is in the ranking order or just before the DocumentId rule
+        let mut geo_distance = match self.around_lat_lng {
+            Some(point) => {
+                let attribute = schema.geo_attribute().ok_or(Error::MissingGeoAttribute)?;
+                let name = schema.attribute_name(attribute);
+                let geo_distance = GeoDistance::new(geo_map, schema, name, point)
+                    .map_err(|e| Error::Internal(e.to_string()))?;
+                Some(geo_distance)
+            }
+            None => None,
+        };
+
+        // the ranking rules of the query replace the ones of the settings
+        if let Some(rules) = &self.ranking_rules {
+            let mut builder = CriteriaBuilder::with_capacity(2 + rules.len());
+            for rule in rules {
+                if rule == "_geo" {
+                    if let Some(geo_distance) = geo_distance.take() {
+                        builder.push(geo_distance);
+                    }
+                } else if !push_builtin_criterion(&mut builder, rule) {
+                    let (name, order) = parse_custom_ranking_rule(rule)?;
+                    builder.push(custom_ranking(ranked_map, schema, name, order)?);
+                }
+            }
+            if let Some(geo_distance) = geo_distance.take() {
+                builder.push(geo_distance);
+            }
+            builder.push(DocumentId);
+            return Ok(Some(builder.build()));
+        }
+
         if let Some(ranking_rules) = ranking_rules {
             let mut builder = CriteriaBuilder::with_capacity(7 + ranking_rules.len());
             if let Some(ranking_rules_order) = ranking_order {
                 for rule in ranking_rules_order {
-                    match rule.as_str() {
-                        "_typo" => builder.push(Typo),
-                        "_words" => builder.push(Words),
-                        "_proximity" => builder.push(Proximity),
-                        "_attribute" => builder.push(Attribute),
-                        "_words_position" => builder.push(WordsPosition),
-                        "_exact" => builder.push(Exact),
-                        _ => {
-                            let order = match ranking_rules.get(rule.as_str()) {
-                                Some(o) => o,
-                                None => continue,
-                            };
-
-                            let custom_ranking = match order {
-                                RankingOrdering::Asc => {
-                                    SortByAttr::lower_is_better(&ranked_map, &schema, &rule)
-                                        .unwrap()
-                                }
-                                RankingOrdering::Dsc => {
-                                    SortByAttr::higher_is_better(&ranked_map, &schema, &rule)
-                                        .unwrap()
-                                }
-                            };
-
-                            builder.push(custom_ranking);
+                    if rule == "_geo" {
+                        if let Some(geo_distance) = geo_distance.take() {
+                            builder.push(geo_distance);
                         }
+                    } else if !push_builtin_criterion(&mut builder, rule) {
+                        let order = match ranking_rules.get(rule.as_str()) {
+                            Some(o) => *o,
+                            None => continue,
+                        };
+
+                        builder.push(custom_ranking(ranked_map, schema, rule, order)?);
                     }
                 }
+                if let Some(geo_distance) = geo_distance.take() {
+                    builder.push(geo_distance);
+                }
                 builder.push(DocumentId);
                 return Ok(Some(builder.build()));
             } else {
@@ -344,27 +568,29 @@ impl<'a> SearchBuilder<'a> {
                 builder.push(WordsPosition);
                 builder.push(Exact);
                 for (rule, order) in ranking_rules.iter() {
-                    let custom_ranking = match order {
-                        RankingOrdering::Asc => {
-                            SortByAttr::lower_is_better(&ranked_map, &schema, &rule)
-                        }
-                        RankingOrdering::Dsc => {
-                            SortByAttr::higher_is_better(&ranked_map, &schema, &rule)
-                        }
-                    };
-                    if let Ok(custom_ranking) = custom_ranking {
-                        builder.push(custom_ranking);
-                    } else {
-                        // TODO push this warning to a log tree
-                        warn!("Custom ranking cannot be added; Attribute {} not registered for ranking", rule)
-                    }
-
+                    builder.push(custom_ranking(ranked_map, schema, rule, *order)?);
+                }
+                if let Some(geo_distance) = geo_distance.take() {
+                    builder.push(geo_distance);
                 }
                 builder.push(DocumentId);
                 return Ok(Some(builder.build()));
             }
         }
 
+        if let Some(geo_distance) = geo_distance {
+            let mut builder = CriteriaBuilder::with_capacity(8);
+            builder.push(Typo);
+            builder.push(Words);
+            builder.push(Proximity);
+            builder.push(Attribute);
+            builder.push(WordsPosition);
+            builder.push(Exact);
+            builder.push(geo_distance);
+            builder.push(DocumentId);
+            return Ok(Some(builder.build()));
+        }
+
         Ok(None)
     }
 }
@@ -384,9 +610,117 @@ impl Ord for MatchPosition {
     }
 }
 
+fn push_builtin_criterion(builder: &mut CriteriaBuilder, rule: &str) -> bool {
+    match rule {
+        "_typo" => builder.push(Typo),
+        "_words" => builder.push(Words),
+        "_proximity" => builder.push(Proximity),
+        "_attribute" => builder.push(Attribute),
+        "_words_position" => builder.push(WordsPosition),
+        "_exact" => builder.push(Exact),
+        _ => return false,
+    }
+    true
+}
+
+fn criteria_names(criteria: &Criteria) -> Vec<String> {
+    criteria.as_ref().iter().map(|criterion| criterion.name().to_string()).collect()
+}
+
+/// Returns the order in which the values of a criterion rank the documents,
+/// the custom ranking rules are named `asc(attribute)` or `desc(attribute)`.
+/// The documents ids of different indexes can't be compared.
+fn criterion_order(name: &str) -> Option<RankingOrdering> {
+    match name {
+        "proximity" | "attribute" | "words position" | "geo distance" => Some(RankingOrdering::Asc),
+        "typo" | "words" | "exact" => Some(RankingOrdering::Dsc),
+        name if name.starts_with("asc(") && name.ends_with(')') => Some(RankingOrdering::Asc),
+        name if name.starts_with("desc(") && name.ends_with(')') => Some(RankingOrdering::Dsc),
+        _ => None,
+    }
+}
+
+/// Compares the ranking infos of two hits, possibly found in different indexes, on
+/// the given criteria in order. The values of a hit are weighted by the weight of its
+/// index, a higher weight ranks the hit better on every criterion.
+///
+/// A hit without value for a criterion is ranked after the ones with a value.
+pub fn compare_ranking_infos(
+    criteria: &[String],
+    (lhs, lhs_weight): (&RankingInfos, f64),
+    (rhs, rhs_weight): (&RankingInfos, f64),
+) -> Ordering {
+    // the higher the score the better, a positive score is multiplied by
+    // the weight and a negative one is divided by it to be improved too
+    fn score(value: f64, order: RankingOrdering, weight: f64) -> f64 {
+        let score = match order {
+            RankingOrdering::Asc => -value,
+            RankingOrdering::Dsc => value,
+        };
+        if score >= 0.0 { score * weight } else { score / weight }
+    }
+
+    for name in criteria {
+        let order = match criterion_order(name) {
+            Some(order) => order,
+            None => continue,
+        };
+
+        let lhs_value = lhs.get(name).and_then(Value::as_f64);
+        let rhs_value = rhs.get(name).and_then(Value::as_f64);
+
+        let ordering = match (lhs_value, rhs_value) {
+            (Some(lhs), Some(rhs)) => {
+                let lhs = score(lhs, order, lhs_weight);
+                let rhs = score(rhs, order, rhs_weight);
+                rhs.partial_cmp(&lhs).unwrap_or(Ordering::Equal)
+            }
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        };
+
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    Ordering::Equal
+}
+
+/// Parses a custom ranking rule written `attribute:asc` or `attribute:desc`.
+fn parse_custom_ranking_rule(rule: &str) -> Result<(&str, RankingOrdering), Error> {
+    let mut iter = rule.rsplitn(2, ':');
+    match (iter.next(), iter.next()) {
+        (Some("asc"), Some(name)) if !name.is_empty() => Ok((name, RankingOrdering::Asc)),
+        (Some("desc"), Some(name)) if !name.is_empty() => Ok((name, RankingOrdering::Dsc)),
+        _ => Err(Error::InvalidRankingRule(rule.to_string())),
+    }
+}
+
+fn custom_ranking<'a>(
+    ranked_map: &'a RankedMap,
+    schema: &Schema,
+    name: &str,
+    order: RankingOrdering,
+) -> Result<SortByAttr<'a>, Error> {
+    let result = match order {
+        RankingOrdering::Asc => SortByAttr::lower_is_better(ranked_map, schema, name),
+        RankingOrdering::Dsc => SortByAttr::higher_is_better(ranked_map, schema, name),
+    };
+
+    result.map_err(|error| match error {
+        SortByAttrError::AttributeNotFound => Error::AttributeNotFoundOnSchema(name.to_string()),
+        SortByAttrError::AttributeNotRegisteredForRanking => {
+            Error::UnrankedRankingAttribute(name.to_string())
+        }
+    })
+}
+
 pub type HighlightInfos = HashMap<String, Value>;
 pub type MatchesInfos = HashMap<String, Vec<MatchPosition>>;
-// pub type RankingInfos = HashMap<String, u64>;
+pub type FacetsDistribution = HashMap<String, HashMap<String, usize>>;
+pub type RankingInfos = IndexMap<String, Value>;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchHit {
@@ -396,6 +730,20 @@ pub struct SearchHit {
     pub formatted: IndexMap<String, Value>,
     #[serde(rename = "_matchesInfo", skip_serializing_if = "Option::is_none")]
     pub matches_info: Option<MatchesInfos>,
+    #[serde(rename = "_geoDistance", skip_serializing_if = "Option::is_none")]
+    pub geo_distance: Option<u64>,
+    #[serde(rename = "_collapsedCount", skip_serializing_if = "Option::is_none")]
+    pub collapsed_count: Option<usize>,
+    #[serde(rename = "_rankingInfo", skip_serializing_if = "Option::is_none")]
+    pub ranking_info: Option<RankingInfos>,
+}
+
+fn number_to_value(number: Number) -> Value {
+    match number {
+        Number::Unsigned(n) => Value::from(n),
+        Number::Signed(n) => Value::from(n),
+        Number::Float(n) => Value::from(n.0),
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -404,8 +752,22 @@ pub struct SearchResult {
     pub hits: Vec<SearchHit>,
     pub offset: usize,
     pub limit: usize,
+    pub nb_hits: usize,
+    pub exhaustive_nb_hits: bool,
     pub processing_time_ms: usize,
     pub query: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub facets_distribution: Option<FacetsDistribution>,
+    /// An opaque cursor to give as `searchAfter` to get the hits following the last one,
+    /// there is none when the hits are deduplicated by a distinct field.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+    /// A corrected query, suggested when the query returns zero or very few hits.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggested_query: Option<String>,
+    /// The names of the criteria that ranked the hits, in order.
+    #[serde(skip)]
+    pub ranking_criteria: Vec<String>,
     // pub parsed_query: String,
     // pub params: Option<String>,
 }
@@ -578,4 +940,73 @@ mod tests {
 
         assert_eq!(result, result_expected);
     }
+
+    #[test]
+    fn parse_custom_ranking_rules() {
+        assert_eq!(parse_custom_ranking_rule("price:asc").unwrap(), ("price", RankingOrdering::Asc));
+        assert_eq!(parse_custom_ranking_rule("release:date:desc").unwrap(), ("release:date", RankingOrdering::Dsc));
+        assert!(parse_custom_ranking_rule("price").is_err());
+        assert!(parse_custom_ranking_rule("price:up").is_err());
+        assert!(parse_custom_ranking_rule(":asc").is_err());
+    }
+
+    #[test]
+    fn compare_ranking_infos_of_indexes() {
+        fn infos(values: &[(&str, u64)]) -> RankingInfos {
+            values.iter().map(|(name, v)| (name.to_string(), Value::from(*v))).collect()
+        }
+
+        fn criteria(names: &[&str]) -> Vec<String> {
+            names.iter().map(|name| name.to_string()).collect()
+        }
+
+        fn compare(criteria: &[String], lhs: &RankingInfos, rhs: &RankingInfos) -> Ordering {
+            compare_ranking_infos(criteria, (lhs, 1.0), (rhs, 1.0))
+        }
+
+        // the higher typo score the better, the more words the better
+        let names = criteria(&["typo", "words", "stable document id"]);
+        let lhs = infos(&[("typo", 2000), ("words", 2), ("stable document id", 8)]);
+        let rhs = infos(&[("typo", 1000), ("words", 3), ("stable document id", 2)]);
+        assert_eq!(compare(&names, &lhs, &rhs), Ordering::Less);
+        assert_eq!(compare(&names, &rhs, &lhs), Ordering::Greater);
+
+        let lhs = infos(&[("typo", 2000), ("words", 2)]);
+        let rhs = infos(&[("typo", 2000), ("words", 3)]);
+        assert_eq!(compare(&names, &lhs, &rhs), Ordering::Greater);
+
+        // the criteria are compared in the given order, the documents ids are ignored
+        let names = criteria(&["desc(price)", "typo", "stable document id"]);
+        let lhs = infos(&[("typo", 2000), ("desc(price)", 10), ("stable document id", 8)]);
+        let rhs = infos(&[("typo", 1000), ("desc(price)", 30), ("stable document id", 2)]);
+        assert_eq!(compare(&names, &lhs, &rhs), Ordering::Greater);
+
+        let lhs = infos(&[("typo", 2000), ("desc(price)", 10), ("stable document id", 8)]);
+        let rhs = infos(&[("typo", 2000), ("desc(price)", 10), ("stable document id", 2)]);
+        assert_eq!(compare(&names, &lhs, &rhs), Ordering::Equal);
+
+        // a hit without value is ranked after the ones with a value
+        let lhs = infos(&[("typo", 2000)]);
+        let rhs = infos(&[("typo", 1000), ("desc(price)", 30)]);
+        assert_eq!(compare(&names, &lhs, &rhs), Ordering::Greater);
+
+        // an attribute named like a built-in criterion is not mistaken for it
+        let names = criteria(&["asc(typo)", "typo"]);
+        let lhs = infos(&[("asc(typo)", 10), ("typo", 1000)]);
+        let rhs = infos(&[("asc(typo)", 30), ("typo", 2000)]);
+        assert_eq!(compare(&names, &lhs, &rhs), Ordering::Less);
+
+        // the weight of an index ranks its hits better on every criterion
+        let names = criteria(&["typo", "proximity"]);
+        let lhs = infos(&[("typo", 1000), ("proximity", 4)]);
+        let rhs = infos(&[("typo", 1500), ("proximity", 4)]);
+        assert_eq!(compare_ranking_infos(&names, (&lhs, 2.0), (&rhs, 1.0)), Ordering::Less);
+        assert_eq!(compare_ranking_infos(&names, (&rhs, 1.0), (&lhs, 2.0)), Ordering::Greater);
+
+        let lhs = infos(&[("typo", 500), ("proximity", 10)]);
+        let rhs = infos(&[("typo", 1000), ("proximity", 4)]);
+        assert_eq!(compare_ranking_infos(&names, (&lhs, 2.0), (&rhs, 1.0)), Ordering::Greater);
+        assert_eq!(compare_ranking_infos(&names, (&lhs, 2.0), (&rhs, 1.0)).reverse(),
+                   compare_ranking_infos(&names, (&rhs, 1.0), (&lhs, 2.0)));
+    }
 }
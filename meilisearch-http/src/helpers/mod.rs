@@ -1,2 +1,3 @@
+pub mod filter;
 pub mod meilisearch;
 pub mod tide;
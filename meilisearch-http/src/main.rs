@@ -4,6 +4,7 @@ use std::{env, thread};
 use http::header::HeaderValue;
 use log::info;
 use main_error::MainError;
+use meilisearch_core::{register_tokenizer, CjkDictionary, CjkTokenizer};
 use structopt::StructOpt;
 use tide::middleware::{CorsMiddleware, CorsOrigin};
 use tide_log::RequestLogger;
@@ -23,6 +24,14 @@ pub fn main() -> Result<(), MainError> {
     env_logger::init();
 
     let opt = Opt::from_args();
+
+    // the tokenizers must be registered before the updates are processed
+    if let Some(path) = &opt.cjk_dictionary_path {
+        let dictionary = CjkDictionary::from_path(path)?;
+        register_tokenizer("cjk", CjkTokenizer::new(dictionary));
+        info!("CJK dictionary loaded from {}", path);
+    }
+
     let data = Data::new(opt.clone());
 
     if env::var("MEILI_NO_ANALYTICS") == Err(NotPresent) {
@@ -11,6 +11,7 @@ pub enum FieldProperties {
     Indexed,
     Displayed,
     Ranked,
+    Geo,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
@@ -30,6 +31,9 @@ impl From<Schema> for SchemaBody {
             if props.is_ranked() {
                 old_properties.insert(FieldProperties::Ranked);
             }
+            if props.is_geo() {
+                old_properties.insert(FieldProperties::Geo);
+            }
         }
         let old_properties = map
             .entry(value.identifier_name().to_string())
@@ -48,11 +52,13 @@ impl Into<Schema> for SchemaBody {
             let mut indexed = false;
             let mut displayed = false;
             let mut ranked = false;
+            let mut geo = false;
             for property in properties {
                 match property {
                     FieldProperties::Indexed => indexed = true,
                     FieldProperties::Displayed => displayed = true,
                     FieldProperties::Ranked => ranked = true,
+                    FieldProperties::Geo => geo = true,
                     FieldProperties::Identifier => identifier = field.clone(),
                 }
             }
@@ -62,6 +68,7 @@ impl Into<Schema> for SchemaBody {
                     indexed,
                     displayed,
                     ranked,
+                    geo,
                 },
             );
         }
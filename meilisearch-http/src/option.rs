@@ -17,4 +17,9 @@ pub struct Opt {
     /// Do not send analytics to Meili.
     #[structopt(long, env = "MEILI_NO_ANALYTICS")]
     pub no_analytics: bool,
+
+    /// The dictionary used to segment the chinese and japanese texts, one word per line
+    /// optionally followed by its frequency, the indexes select it with the "cjk" tokenizer.
+    #[structopt(long, env = "MEILI_CJK_DICTIONARY_PATH")]
+    pub cjk_dictionary_path: Option<String>,
 }
@@ -38,6 +38,7 @@ pub fn load_routes(app: &mut tide::App<Data>) {
 
             router.at("/:index").nest(|router| {
                 router.at("/search").get(search::search_with_url_query);
+                router.at("/autocomplete").get(search::autocomplete_with_url_query);
 
                 router.at("/updates").nest(|router| {
                     router.at("/").get(index::get_all_updates_status);
@@ -69,6 +70,8 @@ pub fn load_routes(app: &mut tide::App<Data>) {
                             .at("/")
                             .get(document::get_document)
                             .delete(document::delete_document);
+
+                        router.at("/similar").get(search::search_similar_documents);
                     });
 
                     router
@@ -76,9 +79,14 @@ pub fn load_routes(app: &mut tide::App<Data>) {
                         .post(document::delete_multiple_documents);
                 });
 
-                router.at("/synonyms")
-                    .get(synonym::get)
-                    .post(synonym::update);
+                router.at("/synonyms").nest(|router| {
+                    router
+                        .at("/")
+                        .get(synonym::get)
+                        .post(synonym::update)
+                        .patch(synonym::add)
+                        .delete(synonym::delete);
+                });
 
                 router.at("/stop-words").nest(|router| {
                     router
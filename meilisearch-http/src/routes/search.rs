@@ -1,15 +1,17 @@
+use std::cmp::{self, Ordering};
 use std::collections::HashMap;
 use std::collections::HashSet;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use meilisearch_core::Index;
+use meilisearch_core::{autocomplete, GeoPoint, Index, MatchingStrategy, PrefixSearch, SearchCursor};
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use tide::querystring::ContextExt as QSContextExt;
 use tide::{Context, Response};
 
 use crate::error::{ResponseError, SResult};
-use crate::helpers::meilisearch::{Error, IndexSearchExt, SearchHit};
+use crate::helpers::filter::Filter;
+use crate::helpers::meilisearch::{compare_ranking_infos, Error, IndexSearchExt, SearchHit, SearchResult};
 use crate::helpers::tide::ContextExt;
 use crate::Data;
 
@@ -25,8 +27,16 @@ struct SearchQuery {
     crop_length: Option<usize>,
     attributes_to_highlight: Option<String>,
     filters: Option<String>,
+    facets_distribution: Option<String>,
+    around_lat_lng: Option<String>,
+    distinct: Option<String>,
+    ranking_rules: Option<String>,
     timeout_ms: Option<u64>,
     matches: Option<bool>,
+    show_ranking_info: Option<bool>,
+    matching_strategy: Option<MatchingStrategy>,
+    prefix_search: Option<PrefixSearch>,
+    search_after: Option<String>,
 }
 
 pub async fn search_with_url_query(ctx: Context<Data>) -> SResult<Response> {
@@ -99,6 +109,38 @@ pub async fn search_with_url_query(ctx: Context<Data>) -> SResult<Response> {
         search_builder.filters(filters);
     }
 
+    if let Some(facets_distribution) = query.facets_distribution {
+        let facets_distribution = facets_distribution
+            .split(',')
+            .map(ToString::to_string)
+            .collect();
+        search_builder.facets_distribution(facets_distribution);
+    }
+
+    if let Some(around_lat_lng) = query.around_lat_lng {
+        let point = parse_lat_lng(&around_lat_lng).ok_or_else(|| {
+            ResponseError::bad_request("aroundLatLng must be a valid `lat,lng` geo point")
+        })?;
+        search_builder.around_lat_lng(point);
+    }
+
+    // an empty distinct disables the distinct field of the settings
+    if let Some(distinct) = query.distinct {
+        if distinct.is_empty() {
+            search_builder.distinct(None);
+        } else {
+            search_builder.distinct(Some(distinct));
+        }
+    }
+
+    if let Some(ranking_rules) = query.ranking_rules {
+        let ranking_rules = ranking_rules
+            .split(',')
+            .map(|r| r.trim().to_string())
+            .collect();
+        search_builder.ranking_rules(ranking_rules);
+    }
+
     if let Some(timeout_ms) = query.timeout_ms {
         search_builder.timeout(Duration::from_millis(timeout_ms));
     }
@@ -109,6 +151,105 @@ pub async fn search_with_url_query(ctx: Context<Data>) -> SResult<Response> {
         }
     }
 
+    if let Some(true) = query.show_ranking_info {
+        search_builder.show_ranking_info();
+    }
+
+    if let Some(matching_strategy) = query.matching_strategy {
+        search_builder.matching_strategy(matching_strategy);
+    }
+
+    if let Some(prefix_search) = query.prefix_search {
+        search_builder.prefix_search(prefix_search);
+    }
+
+    if let Some(search_after) = query.search_after {
+        let cursor = SearchCursor::decode(&search_after)
+            .ok_or_else(|| ResponseError::bad_request("searchAfter must be a cursor returned by a previous search"))?;
+        search_builder.search_after(cursor);
+    }
+
+    let response = match search_builder.search(&reader) {
+        Ok(response) => response,
+        Err(Error::Internal(message)) => return Err(ResponseError::Internal(message)),
+        Err(others) => return Err(ResponseError::bad_request(others)),
+    };
+
+    Ok(tide::response::json(response))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct SimilarQuery {
+    offset: Option<usize>,
+    limit: Option<usize>,
+    attributes_to_retrieve: Option<String>,
+    attributes_to_search_in: Option<String>,
+    filters: Option<String>,
+    distinct: Option<String>,
+    show_ranking_info: Option<bool>,
+}
+
+pub async fn search_similar_documents(ctx: Context<Data>) -> SResult<Response> {
+    // ctx.is_allowed(DocumentsRead)?;
+
+    let index = ctx.index()?;
+    let identifier = ctx.identifier()?;
+    let document_id = meilisearch_core::serde::compute_document_id(identifier.clone());
+
+    let db = &ctx.state().db;
+    let reader = db.main_read_txn().map_err(ResponseError::internal)?;
+
+    let words = index
+        .docs_words
+        .doc_words(&reader, document_id)
+        .map_err(ResponseError::internal)?;
+    if words.is_none() {
+        return Err(ResponseError::document_not_found(identifier));
+    }
+
+    let query: SimilarQuery = ctx
+        .url_query()
+        .map_err(|_| ResponseError::bad_request("invalid query parameter"))?;
+
+    let mut search_builder = index.new_search(String::new());
+    search_builder.similar_to(document_id);
+
+    if let Some(offset) = query.offset {
+        search_builder.offset(offset);
+    }
+    if let Some(limit) = query.limit {
+        search_builder.limit(limit);
+    }
+
+    if let Some(attributes_to_retrieve) = query.attributes_to_retrieve {
+        for attr in attributes_to_retrieve.split(',') {
+            search_builder.add_retrievable_field(attr.to_string());
+        }
+    }
+    if let Some(attributes_to_search_in) = query.attributes_to_search_in {
+        for attr in attributes_to_search_in.split(',') {
+            search_builder.add_attribute_to_search_in(attr.to_string());
+        }
+    }
+
+    if let Some(filters) = query.filters {
+        search_builder.filters(filters);
+    }
+
+    // an empty distinct disables the distinct field of the settings
+    if let Some(distinct) = query.distinct {
+        if distinct.is_empty() {
+            search_builder.distinct(None);
+        } else {
+            search_builder.distinct(Some(distinct));
+        }
+    }
+
+    if let Some(true) = query.show_ranking_info {
+        search_builder.show_ranking_info();
+    }
+
     let response = match search_builder.search(&reader) {
         Ok(response) => response,
         Err(Error::Internal(message)) => return Err(ResponseError::Internal(message)),
@@ -118,10 +259,97 @@ pub async fn search_with_url_query(ctx: Context<Data>) -> SResult<Response> {
     Ok(tide::response::json(response))
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct AutocompleteQuery {
+    q: String,
+    limit: Option<usize>,
+    filters: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AutocompleteHit {
+    completion: String,
+    nb_documents: usize,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AutocompleteResult {
+    completions: Vec<AutocompleteHit>,
+    processing_time_ms: usize,
+    query: String,
+}
+
+pub async fn autocomplete_with_url_query(ctx: Context<Data>) -> SResult<Response> {
+    // ctx.is_allowed(DocumentsRead)?;
+
+    let index = ctx.index()?;
+    let db = &ctx.state().db;
+    let reader = db.main_read_txn().map_err(ResponseError::internal)?;
+
+    let schema = index
+        .main
+        .schema(&reader)
+        .map_err(ResponseError::internal)?
+        .ok_or(ResponseError::open_index("No Schema found"))?;
+
+    let query: AutocompleteQuery = ctx
+        .url_query()
+        .map_err(|_| ResponseError::bad_request("invalid query parameter"))?;
+
+    let limit = query.limit.unwrap_or(10);
+    let start = Instant::now();
+
+    let completions = match &query.filters {
+        Some(filters) => {
+            let filter = Filter::parse(filters, &schema).map_err(ResponseError::bad_request)?;
+            let ranked_map = index.main.ranked_map(&reader).map_err(ResponseError::internal)?.unwrap_or_default();
+            let geo_map = index.main.geo_map(&reader).map_err(ResponseError::internal)?.unwrap_or_default();
+            let test = |id| filter.test(&reader, &index, &ranked_map, &geo_map, id);
+            autocomplete(&reader, index.main, index.postings_lists, &query.q, Some(test), limit)
+        }
+        None => {
+            let filter = None::<fn(_) -> bool>;
+            autocomplete(&reader, index.main, index.postings_lists, &query.q, filter, limit)
+        }
+    };
+
+    let completions = completions
+        .map_err(ResponseError::internal)?
+        .into_iter()
+        .map(|c| AutocompleteHit { completion: c.completion, nb_documents: c.nb_documents })
+        .collect();
+
+    let response = AutocompleteResult {
+        completions,
+        processing_time_ms: start.elapsed().as_millis() as usize,
+        query: query.q,
+    };
+
+    Ok(tide::response::json(response))
+}
+
+fn parse_lat_lng(value: &str) -> Option<GeoPoint> {
+    let mut iter = value.split(',').map(|s| s.trim().parse::<f64>());
+    match (iter.next(), iter.next(), iter.next()) {
+        (Some(Ok(lat)), Some(Ok(lng)), None) => {
+            let point = GeoPoint::new(lat, lng);
+            if point.is_valid() {
+                Some(point)
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
 #[derive(Clone, Deserialize)]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 struct SearchMultiBody {
-    indexes: HashSet<String>,
+    indexes: Vec<String>,
     query: String,
     offset: Option<usize>,
     limit: Option<usize>,
@@ -130,18 +358,56 @@ struct SearchMultiBody {
     attributes_to_crop: Option<HashMap<String, usize>>,
     attributes_to_highlight: Option<HashSet<String>>,
     filters: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    distinct: Option<Option<String>>,
+    ranking_rules: Option<Vec<String>>,
     timeout_ms: Option<u64>,
     matches: Option<bool>,
+    show_ranking_info: Option<bool>,
+    matching_strategy: Option<MatchingStrategy>,
+    prefix_search: Option<PrefixSearch>,
+    /// The weight of each index, the criteria values of the hits of an index are
+    /// weighted by it to rank them better or worse than the hits of the other
+    /// indexes. The indexes weight 1.0 by default.
+    #[serde(default)]
+    weights: HashMap<String, f64>,
+}
+
+// Any value that is present is considered Some value, including null.
+fn deserialize_some<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+    where T: Deserialize<'de>,
+          D: Deserializer<'de>
+{
+    Deserialize::deserialize(deserializer).map(Some)
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct FederatedHit {
+    #[serde(rename = "_index")]
+    index: String,
+    #[serde(flatten)]
+    hit: SearchHit,
 }
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct SearchMultiBodyResponse {
-    hits: HashMap<String, Vec<SearchHit>>,
+    hits: Vec<FederatedHit>,
     offset: usize,
     hits_per_page: usize,
+    nb_hits: usize,
+    exhaustive_nb_hits: bool,
     processing_time_ms: usize,
     query: String,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    errors: HashMap<String, String>,
+}
+
+/// The hits of an index along with what is needed to rank them with the other indexes ones.
+struct IndexHits {
+    index_uid: String,
+    weight: f64,
+    result: SearchResult,
 }
 
 pub async fn search_multi_index(mut ctx: Context<Data>) -> SResult<Response> {
@@ -151,40 +417,43 @@ pub async fn search_multi_index(mut ctx: Context<Data>) -> SResult<Response> {
         .await
         .map_err(ResponseError::bad_request)?;
 
-    let mut index_list = body.clone().indexes;
-
-    for index in index_list.clone() {
+    let mut index_list = Vec::new();
+    for index in &body.indexes {
         if index == "*" {
-            index_list = ctx.state().db.indexes_uids().into_iter().collect();
+            index_list = ctx.state().db.indexes_uids();
+            index_list.sort_unstable();
             break;
         }
+        if !index_list.contains(index) {
+            index_list.push(index.clone());
+        }
     }
 
-    let mut offset = 0;
-    let mut count = 20;
-
-    if let Some(body_offset) = body.offset {
-        if let Some(limit) = body.limit {
-            offset = body_offset;
-            count = limit;
-        }
+    if let Some((index_uid, weight)) = body.weights.iter().find(|(_, w)| !(w.is_finite() && **w > 0.0)) {
+        let message = format!("the weight of the index {} must be a positive number, not {}", index_uid, weight);
+        return Err(ResponseError::bad_request(message));
     }
 
-    let offset = offset;
-    let count = count;
+    let offset = body.offset.unwrap_or(0);
+    let count = body.limit.unwrap_or(20);
+
     let db = &ctx.state().db;
     let par_body = body.clone();
-    let responses_per_index: Vec<SResult<_>> = index_list
+    let responses_per_index: Vec<Result<IndexHits, (String, String)>> = index_list
         .into_par_iter()
         .map(move |index_uid| {
-            let index: Index = db
-                .open_index(&index_uid)
-                .ok_or(ResponseError::index_not_found(&index_uid))?;
+            let index: Index = match db.open_index(&index_uid) {
+                Some(index) => index,
+                None => return Err((index_uid, String::from("index not found"))),
+            };
 
             let mut search_builder = index.new_search(par_body.query.clone());
 
-            search_builder.offset(offset);
-            search_builder.limit(count);
+            // every index must return enough hits to fill the
+            // requested page once merged with the other ones
+            search_builder.offset(0);
+            search_builder.limit(offset + count);
+            search_builder.show_ranking_info();
 
             if let Some(attributes_to_retrieve) = par_body.attributes_to_retrieve.clone() {
                 search_builder.attributes_to_retrieve(attributes_to_retrieve);
@@ -201,6 +470,12 @@ pub async fn search_multi_index(mut ctx: Context<Data>) -> SResult<Response> {
             if let Some(filters) = par_body.filters.clone() {
                 search_builder.filters(filters);
             }
+            if let Some(distinct) = par_body.distinct.clone() {
+                search_builder.distinct(distinct);
+            }
+            if let Some(ranking_rules) = par_body.ranking_rules.clone() {
+                search_builder.ranking_rules(ranking_rules);
+            }
             if let Some(timeout_ms) = par_body.timeout_ms {
                 search_builder.timeout(Duration::from_millis(timeout_ms));
             }
@@ -209,34 +484,121 @@ pub async fn search_multi_index(mut ctx: Context<Data>) -> SResult<Response> {
                     search_builder.get_matches();
                 }
             }
+            if let Some(matching_strategy) = par_body.matching_strategy {
+                search_builder.matching_strategy(matching_strategy);
+            }
+            if let Some(prefix_search) = par_body.prefix_search {
+                search_builder.prefix_search(prefix_search);
+            }
+
+            let reader = match db.main_read_txn() {
+                Ok(reader) => reader,
+                Err(e) => return Err((index_uid, e.to_string())),
+            };
 
-            let reader = db.main_read_txn().map_err(ResponseError::internal)?;
-            let response = search_builder
-                .search(&reader)
-                .map_err(ResponseError::internal)?;
-            Ok((index_uid, response))
+            match search_builder.search(&reader) {
+                Ok(result) => {
+                    let weight = par_body.weights.get(&index_uid).cloned().unwrap_or(1.0);
+                    Ok(IndexHits { index_uid, weight, result })
+                }
+                Err(e) => Err((index_uid, e.to_string())),
+            }
         })
         .collect();
 
-    let mut hits_map = HashMap::new();
+    let mut indexes_hits = Vec::new();
+    let mut errors = HashMap::new();
+    for response in responses_per_index {
+        match response {
+            Ok(index_hits) => indexes_hits.push(index_hits),
+            Err((index_uid, message)) => {
+                errors.insert(index_uid, message);
+            }
+        }
+    }
 
+    // the hits are ranked on the criteria of the first index, the indexes
+    // ranking their hits on other criteria can't be merged with it
+    let mut criteria: Option<(String, Vec<String>)> = None;
+    let mut nb_hits = 0;
+    let mut exhaustive_nb_hits = true;
     let mut max_query_time = 0;
+    let mut indexes_heads = Vec::new();
+
+    for index_hits in indexes_hits {
+        let result = index_hits.result;
+        match &criteria {
+            Some((first_uid, names)) if *names != result.ranking_criteria => {
+                let message = format!("the ranking rules of this index differ from the ones of the index {}", first_uid);
+                errors.insert(index_hits.index_uid, message);
+                continue;
+            }
+            Some(_) => (),
+            None => criteria = Some((index_hits.index_uid.clone(), result.ranking_criteria.clone())),
+        }
 
-    for response in responses_per_index {
-        if let Ok((index_uid, response)) = response {
-            if response.processing_time_ms > max_query_time {
-                max_query_time = response.processing_time_ms;
+        nb_hits += result.nb_hits;
+        exhaustive_nb_hits &= result.exhaustive_nb_hits;
+        max_query_time = cmp::max(max_query_time, result.processing_time_ms);
+
+        indexes_heads.push((index_hits.weight, index_hits.index_uid, result.hits.into_iter()));
+    }
+
+    // the hits of every index are already ranked, they are merged by only comparing
+    // the first remaining hit of each index, the hits of an index keep their order
+    let criteria = criteria.map(|(_, names)| names).unwrap_or_default();
+    let compare_heads = |(lweight, lhs): (f64, &SearchHit), (rweight, rhs): (f64, &SearchHit)| {
+        match (lhs.ranking_info.as_ref(), rhs.ranking_info.as_ref()) {
+            (Some(lhs), Some(rhs)) => compare_ranking_infos(&criteria, (lhs, lweight), (rhs, rweight)),
+            _ => Ordering::Equal,
+        }
+    };
+
+    let show_ranking_info = body.show_ranking_info.unwrap_or(false);
+    let mut hits = Vec::with_capacity(count);
+    let mut position = 0;
+
+    while hits.len() < count {
+        let mut best: Option<(usize, f64, &SearchHit)> = None;
+        for (i, (weight, _, index_hits)) in indexes_heads.iter().enumerate() {
+            if let Some(hit) = index_hits.as_slice().first() {
+                // the first index wins when the hits are equally ranked
+                best = match best {
+                    Some((_, bweight, bhit)) if compare_heads((*weight, hit), (bweight, bhit)) != Ordering::Less => best,
+                    _ => Some((i, *weight, hit)),
+                };
+            }
+        }
+
+        let i = match best {
+            Some((i, _, _)) => i,
+            None => break,
+        };
+
+        let (_, index_uid, index_hits) = &mut indexes_heads[i];
+        let mut hit = match index_hits.next() {
+            Some(hit) => hit,
+            None => break,
+        };
+
+        if position >= offset {
+            if !show_ranking_info {
+                hit.ranking_info = None;
             }
-            hits_map.insert(index_uid, response.hits);
+            hits.push(FederatedHit { index: index_uid.clone(), hit });
         }
+        position += 1;
     }
 
     let response = SearchMultiBodyResponse {
-        hits: hits_map,
+        hits,
         offset,
         hits_per_page: count,
+        nb_hits,
+        exhaustive_nb_hits,
         processing_time_ms: max_query_time,
         query: body.query,
+        errors,
     };
 
     Ok(tide::response::json(response))
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
 use http::StatusCode;
+use meilisearch_core::{tokenizer, CustomSeparators, Index, Language, MainT, PrefixSearch, TypoTolerance};
 use serde::{Deserialize, Serialize, Deserializer};
 use tide::response::IntoResponse;
 use tide::{Context, Response};
@@ -11,6 +12,8 @@ use crate::models::token::ACL::*;
 use crate::routes::document::IndexUpdateResponse;
 use crate::Data;
 
+/// The settings stored in the customs of the index, its bincode encoding must
+/// not change, the other settings are stored under their own main store keys.
 #[derive(Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct Setting {
@@ -19,6 +22,19 @@ pub struct Setting {
     pub ranking_rules: Option<RankingRules>,
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexSettings {
+    #[serde(flatten)]
+    pub customs: Setting,
+    pub attributes_for_faceting: Option<AttributesForFaceting>,
+    pub typo_tolerance: Option<TypoTolerance>,
+    pub prefix_search: Option<PrefixSearch>,
+    pub stemming: Option<Language>,
+    pub tokenizer: Option<String>,
+    pub separators: Option<CustomSeparators>,
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum RankingOrdering {
@@ -29,6 +45,7 @@ pub enum RankingOrdering {
 pub type RankingOrder = Vec<String>;
 pub type DistinctField = String;
 pub type RankingRules = HashMap<String, RankingOrdering>;
+pub type AttributesForFaceting = Vec<String>;
 
 pub async fn get(ctx: Context<Data>) -> SResult<Response> {
     ctx.is_allowed(SettingsRead)?;
@@ -37,14 +54,43 @@ pub async fn get(ctx: Context<Data>) -> SResult<Response> {
     let db = &ctx.state().db;
     let reader = db.main_read_txn().map_err(ResponseError::internal)?;
 
-    let settings = match index.main.customs(&reader).unwrap() {
-        Some(bytes) => bincode::deserialize(bytes).unwrap(),
-        None => Setting::default(),
+    let customs = customs_settings(&reader, &index)?;
+    let main = index.main;
+
+    let attributes_for_faceting = match main.schema(&reader).map_err(ResponseError::internal)? {
+        Some(schema) => main
+            .attributes_for_faceting(&reader)
+            .map_err(ResponseError::internal)?
+            .filter(|attributes| !attributes.is_empty())
+            .map(|attributes| {
+                attributes
+                    .into_iter()
+                    .map(|attribute| schema.attribute_name(attribute).to_string())
+                    .collect()
+            }),
+        None => None,
+    };
+
+    let settings = IndexSettings {
+        customs,
+        attributes_for_faceting,
+        typo_tolerance: main.typo_tolerance(&reader).map_err(ResponseError::internal)?,
+        prefix_search: main.prefix_search(&reader).map_err(ResponseError::internal)?,
+        stemming: main.stemming_language(&reader).map_err(ResponseError::internal)?,
+        tokenizer: main.tokenizer(&reader).map_err(ResponseError::internal)?,
+        separators: main.separators(&reader).map_err(ResponseError::internal)?,
     };
 
     Ok(tide::response::json(settings))
 }
 
+fn customs_settings(reader: &heed::RoTxn<MainT>, index: &Index) -> SResult<Setting> {
+    match index.main.customs(reader).map_err(ResponseError::internal)? {
+        Some(bytes) => bincode::deserialize(bytes).map_err(ResponseError::internal),
+        None => Ok(Setting::default()),
+    }
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct SettingBody {
@@ -54,6 +100,18 @@ pub struct SettingBody {
     pub distinct_field: Option<Option<DistinctField>>,
     #[serde(default, deserialize_with = "deserialize_some")]
     pub ranking_rules: Option<Option<RankingRules>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub attributes_for_faceting: Option<Option<AttributesForFaceting>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub typo_tolerance: Option<Option<TypoTolerance>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub prefix_search: Option<Option<PrefixSearch>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub stemming: Option<Option<Language>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub tokenizer: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub separators: Option<Option<CustomSeparators>>,
 }
 
 // Any value that is present is considered Some value, including null.
@@ -75,10 +133,7 @@ pub async fn update(mut ctx: Context<Data>) -> SResult<Response> {
     let reader = db.main_read_txn().map_err(ResponseError::internal)?;
     let mut writer = db.update_write_txn().map_err(ResponseError::internal)?;
 
-    let mut current_settings = match index.main.customs(&reader).unwrap() {
-        Some(bytes) => bincode::deserialize(bytes).unwrap(),
-        None => Setting::default(),
-    };
+    let mut current_settings = customs_settings(&reader, &index)?;
 
     if let Some(ranking_order) = settings.ranking_order {
         current_settings.ranking_order = ranking_order;
@@ -92,7 +147,64 @@ pub async fn update(mut ctx: Context<Data>) -> SResult<Response> {
         current_settings.ranking_rules = ranking_rules;
     }
 
-    let bytes = bincode::serialize(&current_settings).unwrap();
+    // the facets store must be rebuilt when the facet attributes change
+    if let Some(attributes_for_faceting) = settings.attributes_for_faceting {
+        let attributes = attributes_for_faceting.unwrap_or_default();
+        index
+            .facets_update(&mut writer, attributes)
+            .map_err(ResponseError::internal)?;
+    }
+
+    if let Some(typo_tolerance) = settings.typo_tolerance {
+        let tolerance = typo_tolerance.unwrap_or_default();
+        if !tolerance.has_valid_word_lengths() {
+            let message = format!(
+                "minWordLenTwoTypos ({}) can not be lower than minWordLenOneTypo ({})",
+                tolerance.min_word_len_two_typos, tolerance.min_word_len_one_typo,
+            );
+            return Err(ResponseError::bad_request(message));
+        }
+
+        index
+            .typo_tolerance_update(&mut writer, tolerance)
+            .map_err(ResponseError::internal)?;
+    }
+
+    if let Some(prefix_search) = settings.prefix_search {
+        index
+            .prefix_search_update(&mut writer, prefix_search.unwrap_or_default())
+            .map_err(ResponseError::internal)?;
+    }
+
+    // the documents are reindexed with the stems of the new language
+    if let Some(stemming) = settings.stemming {
+        index
+            .stemming_update(&mut writer, stemming)
+            .map_err(ResponseError::internal)?;
+    }
+
+    // the tokenizers are registered by the program embedding the engine
+    if let Some(name) = settings.tokenizer {
+        if let Some(name) = &name {
+            if tokenizer(name).is_none() {
+                let message = format!("tokenizer {} is not registered", name);
+                return Err(ResponseError::bad_request(message));
+            }
+        }
+
+        index
+            .tokenizer_update(&mut writer, name)
+            .map_err(ResponseError::internal)?;
+    }
+
+    // the documents are reindexed with the new separators
+    if let Some(separators) = settings.separators {
+        index
+            .separators_update(&mut writer, separators.unwrap_or_default())
+            .map_err(ResponseError::internal)?;
+    }
+
+    let bytes = bincode::serialize(&current_settings).map_err(ResponseError::internal)?;
 
     let update_id = index
         .customs_update(&mut writer, bytes)
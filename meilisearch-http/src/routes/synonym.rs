@@ -1,9 +1,9 @@
 use std::collections::HashMap;
 
 use http::StatusCode;
+use serde::Deserialize;
 use tide::response::IntoResponse;
 use tide::{Context, Response};
-use indexmap::IndexMap;
 
 use crate::error::{ResponseError, SResult};
 use crate::helpers::tide::ContextExt;
@@ -11,6 +11,31 @@ use crate::models::token::ACL::*;
 use crate::routes::document::IndexUpdateResponse;
 use crate::Data;
 
+/// The synonyms sent by the users, the one-way synonyms can also be
+/// sent as a map of words to their alternatives like in the first versions.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum SynonymsBody {
+    Grouped(GroupedSynonyms),
+    OneWay(HashMap<String, Vec<String>>),
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct GroupedSynonyms {
+    multi_way: Vec<Vec<String>>,
+    one_way: HashMap<String, Vec<String>>,
+}
+
+impl From<SynonymsBody> for GroupedSynonyms {
+    fn from(body: SynonymsBody) -> GroupedSynonyms {
+        match body {
+            SynonymsBody::Grouped(grouped) => grouped,
+            SynonymsBody::OneWay(one_way) => GroupedSynonyms { one_way, ..GroupedSynonyms::default() },
+        }
+    }
+}
+
 pub async fn get(ctx: Context<Data>) -> SResult<Response> {
     ctx.is_allowed(SettingsRead)?;
     let index = ctx.index()?;
@@ -18,49 +43,106 @@ pub async fn get(ctx: Context<Data>) -> SResult<Response> {
     let db = &ctx.state().db;
     let reader = db.main_read_txn().map_err(ResponseError::internal)?;
 
-    let synonyms_fst = index
-        .main
-        .synonyms_fst(&reader)
+    let synonyms = index
+        .synonyms_definitions(&reader)
         .map_err(ResponseError::internal)?;
 
-    let synonyms_fst = synonyms_fst.unwrap_or_default();
-    let synonyms_list = synonyms_fst.stream().into_strs().map_err(ResponseError::internal)?;
+    Ok(tide::response::json(synonyms))
+}
+
+pub async fn update(mut ctx: Context<Data>) -> SResult<Response> {
+    ctx.is_allowed(SettingsWrite)?;
 
-    let mut response = IndexMap::new();
+    let data: SynonymsBody = ctx.body_json().await.map_err(ResponseError::bad_request)?;
+    let data = GroupedSynonyms::from(data);
 
-    let index_synonyms = &index.synonyms;
+    let index = ctx.index()?;
 
-    for synonym in synonyms_list {
-        let alternative_list = index_synonyms
-            .synonyms(&reader, synonym.as_bytes())
-            .map_err(ResponseError::internal)?;
+    let db = &ctx.state().db;
+    let mut writer = db.update_write_txn().map_err(ResponseError::internal)?;
 
-        if let Some(list) = alternative_list {
-            let list = list.stream().into_strs().map_err(ResponseError::internal)?;
-            response.insert(synonym, list);
-        }
+    let mut synonyms_update = index.synonyms_update();
+
+    for synonyms in data.multi_way {
+        synonyms_update.add_multi_way_synonyms(synonyms);
+    }
+
+    for (input, synonyms) in data.one_way {
+        synonyms_update.add_synonym(input, synonyms.into_iter());
+    }
+
+    let update_id = synonyms_update
+        .finalize(&mut writer)
+        .map_err(ResponseError::internal)?;
+
+    writer.commit().map_err(ResponseError::internal)?;
+
+    let response_body = IndexUpdateResponse { update_id };
+    Ok(tide::response::json(response_body)
+        .with_status(StatusCode::ACCEPTED)
+        .into_response())
+}
+
+pub async fn add(mut ctx: Context<Data>) -> SResult<Response> {
+    ctx.is_allowed(SettingsWrite)?;
+
+    let data: SynonymsBody = ctx.body_json().await.map_err(ResponseError::bad_request)?;
+    let data = GroupedSynonyms::from(data);
+
+    let index = ctx.index()?;
+
+    let db = &ctx.state().db;
+    let mut writer = db.update_write_txn().map_err(ResponseError::internal)?;
+
+    let mut synonyms_addition = index.synonyms_addition();
+
+    for synonyms in data.multi_way {
+        synonyms_addition.add_multi_way_synonyms(synonyms);
+    }
+
+    for (input, synonyms) in data.one_way {
+        synonyms_addition.add_synonym(input, synonyms.into_iter());
     }
 
-    Ok(tide::response::json(response))
+    let update_id = synonyms_addition
+        .finalize(&mut writer)
+        .map_err(ResponseError::internal)?;
+
+    writer.commit().map_err(ResponseError::internal)?;
+
+    let response_body = IndexUpdateResponse { update_id };
+    Ok(tide::response::json(response_body)
+        .with_status(StatusCode::ACCEPTED)
+        .into_response())
 }
 
-pub async fn update(mut ctx: Context<Data>) -> SResult<Response> {
+pub async fn delete(mut ctx: Context<Data>) -> SResult<Response> {
     ctx.is_allowed(SettingsWrite)?;
 
-    let data: HashMap<String, Vec<String>> = ctx.body_json().await.map_err(ResponseError::bad_request)?;
+    let data: SynonymsBody = ctx.body_json().await.map_err(ResponseError::bad_request)?;
+    let data = GroupedSynonyms::from(data);
 
     let index = ctx.index()?;
 
     let db = &ctx.state().db;
     let mut writer = db.update_write_txn().map_err(ResponseError::internal)?;
 
-    let mut synonyms_update = index.synonyms_update();
+    let mut synonyms_deletion = index.synonyms_deletion();
 
-    for (input, synonyms) in data {
-        synonyms_update.add_synonym(input, synonyms.into_iter());
+    for synonyms in data.multi_way {
+        synonyms_deletion.delete_multi_way_synonyms(synonyms);
     }
 
-    let update_id = synonyms_update
+    // an empty list of alternatives deletes all the alternatives of the word
+    for (input, synonyms) in data.one_way {
+        if synonyms.is_empty() {
+            synonyms_deletion.delete_all_alternatives_of(input);
+        } else {
+            synonyms_deletion.delete_specific_alternatives_of(input, synonyms.into_iter());
+        }
+    }
+
+    let update_id = synonyms_deletion
         .finalize(&mut writer)
         .map_err(ResponseError::internal)?;
 
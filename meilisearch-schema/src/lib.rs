@@ -10,16 +10,25 @@ pub const DISPLAYED: SchemaProps = SchemaProps {
     displayed: true,
     indexed: false,
     ranked: false,
+    geo: false,
 };
 pub const INDEXED: SchemaProps = SchemaProps {
     displayed: false,
     indexed: true,
     ranked: false,
+    geo: false,
 };
 pub const RANKED: SchemaProps = SchemaProps {
     displayed: false,
     indexed: false,
     ranked: true,
+    geo: false,
+};
+pub const GEO: SchemaProps = SchemaProps {
+    displayed: false,
+    indexed: false,
+    ranked: false,
+    geo: true,
 };
 
 #[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -32,6 +41,9 @@ pub struct SchemaProps {
 
     #[serde(default)]
     pub ranked: bool,
+
+    #[serde(default)]
+    pub geo: bool,
 }
 
 impl SchemaProps {
@@ -46,6 +58,10 @@ impl SchemaProps {
     pub fn is_ranked(self) -> bool {
         self.ranked
     }
+
+    pub fn is_geo(self) -> bool {
+        self.geo
+    }
 }
 
 impl BitOr for SchemaProps {
@@ -56,6 +72,7 @@ impl BitOr for SchemaProps {
             displayed: self.displayed | other.displayed,
             indexed: self.indexed | other.indexed,
             ranked: self.ranked | other.ranked,
+            geo: self.geo | other.geo,
         }
     }
 }
@@ -72,6 +89,9 @@ impl fmt::Debug for SchemaProps {
         #[derive(Debug)]
         struct RANKED;
 
+        #[derive(Debug)]
+        struct GEO;
+
         let mut debug_set = f.debug_set();
 
         if self.displayed {
@@ -86,6 +106,10 @@ impl fmt::Debug for SchemaProps {
             debug_set.entry(&RANKED);
         }
 
+        if self.geo {
+            debug_set.entry(&GEO);
+        }
+
         debug_set.finish()
     }
 }
@@ -191,6 +215,11 @@ impl Schema {
         name
     }
 
+    /// Returns the first attribute that holds geo points, if any.
+    pub fn geo_attribute(&self) -> Option<SchemaAttr> {
+        self.iter().find(|(_, _, props)| props.is_geo()).map(|(_, attr, _)| attr)
+    }
+
     pub fn iter<'a>(&'a self) -> impl Iterator<Item = (&str, SchemaAttr, SchemaProps)> + 'a {
         self.inner.props.iter().map(move |(name, prop)| {
             let attr = self.inner.attrs.get(name).unwrap();
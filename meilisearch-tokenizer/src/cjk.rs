@@ -0,0 +1,264 @@
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::iter::Peekable;
+use std::path::Path;
+
+use crate::{is_cjk, DefaultTokens, Token, Tokenizer};
+
+/// The chinese and japanese words along with their frequencies,
+/// used to segment the texts that are written without spaces.
+#[derive(Debug, Default, Clone)]
+pub struct CjkDictionary {
+    words: HashMap<String, u64>,
+    total: u64,
+    max_word_chars: usize,
+}
+
+impl CjkDictionary {
+    /// Reads a dictionary with one word per line, the word can be followed by its
+    /// frequency and any other column, the format of the jieba dictionaries.
+    pub fn from_reader<R: BufRead>(reader: R) -> io::Result<CjkDictionary> {
+        let mut dictionary = CjkDictionary::default();
+
+        for line in reader.lines() {
+            let line = line?;
+            let mut columns = line.split_whitespace();
+            if let Some(word) = columns.next() {
+                let frequency = columns.next().and_then(|f| f.parse().ok()).unwrap_or(1);
+                dictionary.insert(word, frequency);
+            }
+        }
+
+        Ok(dictionary)
+    }
+
+    pub fn from_path<P: AsRef<Path>>(path: P) -> io::Result<CjkDictionary> {
+        let file = File::open(path)?;
+        CjkDictionary::from_reader(BufReader::new(file))
+    }
+
+    /// Creates a dictionary in which all the words have the same frequency,
+    /// the texts are therefore segmented into the longest known words.
+    pub fn from_words<I, S>(words: I) -> CjkDictionary
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut dictionary = CjkDictionary::default();
+        for word in words {
+            dictionary.insert(word.as_ref(), 1);
+        }
+        dictionary
+    }
+
+    fn insert(&mut self, word: &str, frequency: u64) {
+        let frequency = frequency.max(1);
+        let previous = self.words.insert(word.to_owned(), frequency).unwrap_or(0);
+        self.total = self.total - previous + frequency;
+        self.max_word_chars = self.max_word_chars.max(word.chars().count());
+    }
+
+    fn log_frequency(&self, word: &str, chars: usize) -> Option<f64> {
+        match self.words.get(word) {
+            Some(frequency) => Some((*frequency as f64).ln()),
+            // the unknown characters are words of the lowest frequency
+            None if chars == 1 => Some(0.0),
+            None => None,
+        }
+    }
+
+    /// Splits a text into the sequence of words that has the highest
+    /// probability, the unknown characters are considered as words.
+    pub fn segment<'t>(&self, text: &'t str) -> Vec<&'t str> {
+        let offsets: Vec<_> = text
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain(Some(text.len()))
+            .collect();
+        let count = offsets.len() - 1;
+        // the unknown characters are counted in the total
+        let log_total = ((self.total + 1) as f64).ln();
+
+        // the best score of the text prefixes along with
+        // the number of chars of the last word of the prefix
+        let mut best: Vec<(f64, usize)> = Vec::with_capacity(count + 1);
+        best.push((0.0, 0));
+
+        for end in 1..=count {
+            let max_chars = self.max_word_chars.max(1).min(end);
+            let mut best_end = (std::f64::NEG_INFINITY, 1);
+
+            // the longest words are tried first to prefer them on equality
+            for chars in (1..=max_chars).rev() {
+                let word = &text[offsets[end - chars]..offsets[end]];
+                if let Some(log_frequency) = self.log_frequency(word, chars) {
+                    let score = best[end - chars].0 + log_frequency - log_total;
+                    if score > best_end.0 {
+                        best_end = (score, chars);
+                    }
+                }
+            }
+
+            best.push(best_end);
+        }
+
+        let mut words = Vec::new();
+        let mut end = count;
+        while end > 0 {
+            let chars = best[end].1;
+            words.push(&text[offsets[end - chars]..offsets[end]]);
+            end -= chars;
+        }
+
+        words.reverse();
+        words
+    }
+}
+
+/// The tokenizer that segments the chinese and japanese texts into the words
+/// of a dictionary, the other texts are split like the default tokenizer does.
+#[derive(Debug, Clone)]
+pub struct CjkTokenizer {
+    dictionary: CjkDictionary,
+}
+
+impl CjkTokenizer {
+    pub fn new(dictionary: CjkDictionary) -> CjkTokenizer {
+        CjkTokenizer { dictionary }
+    }
+}
+
+impl Tokenizer for CjkTokenizer {
+    fn tokenize<'a>(&'a self, text: &'a str) -> Box<dyn Iterator<Item = Token<'a>> + 'a> {
+        Box::new(CjkTokens {
+            text,
+            dictionary: &self.dictionary,
+            inner: DefaultTokens::new(text).peekable(),
+            segmented: VecDeque::new(),
+            shift: 0,
+        })
+    }
+}
+
+fn is_cjk_token(token: &Token) -> bool {
+    let mut chars = token.word.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => is_cjk(c),
+        _ => false,
+    }
+}
+
+struct CjkTokens<'a> {
+    text: &'a str,
+    dictionary: &'a CjkDictionary,
+    inner: Peekable<DefaultTokens<'a>>,
+    segmented: VecDeque<Token<'a>>,
+    // the number of word positions that were merged into
+    // the segmented words, removed from the following tokens
+    shift: usize,
+}
+
+impl<'a> CjkTokens<'a> {
+    fn offset(&self, word: &str) -> usize {
+        word.as_ptr() as usize - self.text.as_ptr() as usize
+    }
+
+    // the default tokenizer returns every CJK character as a token,
+    // the consecutive ones are gathered and segmented into words
+    fn segment_run(&mut self, first: Token<'a>) {
+        let mut last = first;
+        while let Some(token) = self.inner.peek() {
+            if is_cjk_token(token) && token.char_index == last.char_index + 1 {
+                last = *token;
+                self.inner.next();
+            } else {
+                break;
+            }
+        }
+
+        let start = self.offset(first.word);
+        let end = self.offset(last.word) + last.word.len();
+        let text = self.text;
+        let run = &text[start..end];
+
+        let word_index = first.word_index - self.shift;
+        let mut char_index = first.char_index;
+        let mut count = 0;
+
+        for (i, word) in self.dictionary.segment(run).into_iter().enumerate() {
+            self.segmented.push_back(Token {
+                word,
+                word_index: word_index + i,
+                char_index,
+            });
+            char_index += word.chars().count();
+            count += 1;
+        }
+
+        self.shift += (last.word_index - first.word_index + 1) - count;
+    }
+}
+
+impl<'a> Iterator for CjkTokens<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(token) = self.segmented.pop_front() {
+            return Some(token);
+        }
+
+        let token = self.inner.next()?;
+        if is_cjk_token(&token) {
+            self.segment_run(token);
+            return self.segmented.pop_front();
+        }
+
+        Some(Token {
+            word_index: token.word_index - self.shift,
+            ..token
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::split_query_string;
+
+    #[test]
+    fn longest_words() {
+        let dictionary = CjkDictionary::from_words(&["我", "是", "中国", "中国人", "国人"]);
+        assert_eq!(dictionary.segment("我是中国人"), vec!["我", "是", "中国人"]);
+        assert_eq!(dictionary.segment("你是中国人"), vec!["你", "是", "中国人"]);
+    }
+
+    #[test]
+    fn most_frequent_words() {
+        let text = "研究 100\n研究生 10\n生命 100\n命 1\n";
+        let dictionary = CjkDictionary::from_reader(text.as_bytes()).unwrap();
+        assert_eq!(dictionary.segment("研究生命"), vec!["研究", "生命"]);
+    }
+
+    #[test]
+    fn segmented_tokens() {
+        let dictionary = CjkDictionary::from_words(&["中国人", "东京"]);
+        let tokenizer = CjkTokenizer::new(dictionary);
+        let mut tokens = tokenizer.tokenize("我是中国人, hello 东京");
+
+        assert_eq!(tokens.next(), Some(Token { word: "我", word_index: 0, char_index: 0 }));
+        assert_eq!(tokens.next(), Some(Token { word: "是", word_index: 1, char_index: 1 }));
+        assert_eq!(tokens.next(), Some(Token { word: "中国人", word_index: 2, char_index: 2 }));
+        assert_eq!(tokens.next(), Some(Token { word: "hello", word_index: 10, char_index: 7 }));
+        assert_eq!(tokens.next(), Some(Token { word: "东京", word_index: 11, char_index: 13 }));
+        assert_eq!(tokens.next(), None);
+    }
+
+    #[test]
+    fn segmented_query() {
+        let dictionary = CjkDictionary::from_words(&["東京", "タワー"]);
+        let tokenizer = CjkTokenizer::new(dictionary);
+        let words: Vec<_> = split_query_string(&tokenizer, "東京タワー").collect();
+        assert_eq!(words, vec!["東京", "タワー"]);
+    }
+}
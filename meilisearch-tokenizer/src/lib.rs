@@ -1,7 +1,16 @@
+mod cjk;
+
+pub use self::cjk::{CjkDictionary, CjkTokenizer};
+
 use self::SeparatorCategory::*;
 use deunicode::deunicode_char;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use slice_group_by::StrGroupBy;
+use std::borrow::Cow;
+use std::collections::BTreeSet;
 use std::iter::Peekable;
+use std::sync::Arc;
 
 pub fn is_cjk(c: char) -> bool {
     (c >= '\u{1100}' && c <= '\u{11ff}')  // Hangul Jamo
@@ -45,10 +54,6 @@ impl SeparatorCategory {
     }
 }
 
-fn is_separator(c: char) -> bool {
-    classify_separator(c).is_some()
-}
-
 fn classify_separator(c: char) -> Option<SeparatorCategory> {
     match c {
         c if c.is_whitespace() => Some(Soft), // whitespaces
@@ -67,25 +72,57 @@ enum CharCategory {
     Other,
 }
 
-fn classify_char(c: char) -> CharCategory {
-    if let Some(category) = classify_separator(c) {
-        CharCategory::Separator(category)
-    } else if is_cjk(c) {
-        CharCategory::Cjk
-    } else {
-        CharCategory::Other
-    }
+/// The characters that split the words, or never split them,
+/// in addition to the default separators rules.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase", default))]
+pub struct CustomSeparators {
+    /// The characters that split the words like whitespaces do.
+    pub separators: BTreeSet<char>,
+    /// The characters that are part of the words, like the dot of `node.js`.
+    pub non_separators: BTreeSet<char>,
 }
 
-fn is_str_word(s: &str) -> bool {
-    !s.chars().any(is_separator)
-}
+impl CustomSeparators {
+    pub fn is_empty(&self) -> bool {
+        self.separators.is_empty() && self.non_separators.is_empty()
+    }
+
+    fn classify_separator(&self, c: char) -> Option<SeparatorCategory> {
+        if self.non_separators.contains(&c) {
+            None
+        } else if self.separators.contains(&c) {
+            Some(Soft)
+        } else {
+            classify_separator(c)
+        }
+    }
 
-fn same_group_category(a: char, b: char) -> bool {
-    match (classify_char(a), classify_char(b)) {
-        (CharCategory::Cjk, _) | (_, CharCategory::Cjk) => false,
-        (CharCategory::Separator(_), CharCategory::Separator(_)) => true,
-        (a, b) => a == b,
+    fn is_separator(&self, c: char) -> bool {
+        self.classify_separator(c).is_some()
+    }
+
+    fn classify_char(&self, c: char) -> CharCategory {
+        if let Some(category) = self.classify_separator(c) {
+            CharCategory::Separator(category)
+        } else if is_cjk(c) {
+            CharCategory::Cjk
+        } else {
+            CharCategory::Other
+        }
+    }
+
+    fn is_str_word(&self, s: &str) -> bool {
+        !s.chars().any(|c| self.is_separator(c))
+    }
+
+    fn same_group_category(&self, a: char, b: char) -> bool {
+        match (self.classify_char(a), self.classify_char(b)) {
+            (CharCategory::Cjk, _) | (_, CharCategory::Cjk) => false,
+            (CharCategory::Separator(_), CharCategory::Separator(_)) => true,
+            (a, b) => a == b,
+        }
     }
 }
 
@@ -94,8 +131,11 @@ fn chars_count_index((n, _): (usize, usize), (i, c): (usize, char)) -> (usize, u
     (n + 1, i + c.len_utf8())
 }
 
-pub fn split_query_string(query: &str) -> impl Iterator<Item = &str> {
-    Tokenizer::new(query).map(|t| t.word)
+pub fn split_query_string<'a, T>(tokenizer: &'a T, query: &'a str) -> impl Iterator<Item = &'a str>
+where
+    T: Tokenizer + ?Sized,
+{
+    tokenizer.tokenize(query).map(|t| t.word)
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -105,42 +145,102 @@ pub struct Token<'a> {
     pub char_index: usize,
 }
 
-pub struct Tokenizer<'a> {
+/// Splits a text into the words that are indexed and searched.
+///
+/// The `word_index` of the tokens is used to compute the proximity between
+/// the words, the default tokenizer separates the words of two different
+/// sentences by 8 to consider them far from each other.
+pub trait Tokenizer {
+    fn tokenize<'a>(&'a self, text: &'a str) -> Box<dyn Iterator<Item = Token<'a>> + 'a>;
+}
+
+impl<T: Tokenizer + ?Sized> Tokenizer for &T {
+    fn tokenize<'a>(&'a self, text: &'a str) -> Box<dyn Iterator<Item = Token<'a>> + 'a> {
+        (**self).tokenize(text)
+    }
+}
+
+impl<T: Tokenizer + ?Sized> Tokenizer for Box<T> {
+    fn tokenize<'a>(&'a self, text: &'a str) -> Box<dyn Iterator<Item = Token<'a>> + 'a> {
+        (**self).tokenize(text)
+    }
+}
+
+impl<T: Tokenizer + ?Sized> Tokenizer for Arc<T> {
+    fn tokenize<'a>(&'a self, text: &'a str) -> Box<dyn Iterator<Item = Token<'a>> + 'a> {
+        (**self).tokenize(text)
+    }
+}
+
+/// The tokenizer that splits the words on whitespaces and punctuation,
+/// every CJK character is considered as a word.
+#[derive(Debug, Default, Clone)]
+pub struct DefaultTokenizer {
+    separators: CustomSeparators,
+}
+
+impl DefaultTokenizer {
+    pub fn with_separators(separators: CustomSeparators) -> DefaultTokenizer {
+        DefaultTokenizer { separators }
+    }
+}
+
+impl Tokenizer for DefaultTokenizer {
+    fn tokenize<'a>(&'a self, text: &'a str) -> Box<dyn Iterator<Item = Token<'a>> + 'a> {
+        Box::new(DefaultTokens::with_separators(text, &self.separators))
+    }
+}
+
+pub struct DefaultTokens<'a> {
     inner: &'a str,
     word_index: usize,
     char_index: usize,
+    separators: Cow<'a, CustomSeparators>,
 }
 
-impl<'a> Tokenizer<'a> {
-    pub fn new(string: &str) -> Tokenizer {
+impl<'a> DefaultTokens<'a> {
+    pub fn new(string: &str) -> DefaultTokens {
+        DefaultTokens::from_cow(string, Cow::Owned(CustomSeparators::default()))
+    }
+
+    pub fn with_separators(string: &'a str, separators: &'a CustomSeparators) -> DefaultTokens<'a> {
+        DefaultTokens::from_cow(string, Cow::Borrowed(separators))
+    }
+
+    fn from_cow(string: &'a str, separators: Cow<'a, CustomSeparators>) -> DefaultTokens<'a> {
         // skip every separator and set `char_index`
         // to the number of char trimmed
         let (count, index) = string
             .char_indices()
-            .take_while(|(_, c)| is_separator(*c))
+            .take_while(|(_, c)| separators.is_separator(*c))
             .fold((0, 0), chars_count_index);
 
-        Tokenizer {
+        DefaultTokens {
             inner: &string[index..],
             word_index: 0,
             char_index: count,
+            separators,
         }
     }
 }
 
-impl<'a> Iterator for Tokenizer<'a> {
+impl<'a> Iterator for DefaultTokens<'a> {
     type Item = Token<'a>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let mut iter = self.inner.linear_group_by(same_group_category).peekable();
+        let separators = &self.separators;
+        let mut iter = self
+            .inner
+            .linear_group_by(|a, b| separators.same_group_category(a, b))
+            .peekable();
 
         while let (Some(string), next_string) = (iter.next(), iter.peek()) {
             let (count, index) = string.char_indices().fold((0, 0), chars_count_index);
 
-            if !is_str_word(string) {
+            if !separators.is_str_word(string) {
                 self.word_index += string
                     .chars()
-                    .filter_map(classify_separator)
+                    .filter_map(|c| separators.classify_separator(c))
                     .fold(Soft, |a, x| a.merge(x))
                     .to_usize();
                 self.char_index += count;
@@ -154,7 +254,7 @@ impl<'a> Iterator for Tokenizer<'a> {
                 char_index: self.char_index,
             };
 
-            if next_string.filter(|s| is_str_word(s)).is_some() {
+            if next_string.filter(|s| separators.is_str_word(s)).is_some() {
                 self.word_index += 1;
             }
 
@@ -169,23 +269,27 @@ impl<'a> Iterator for Tokenizer<'a> {
     }
 }
 
-pub struct SeqTokenizer<'a, I>
-where
-    I: Iterator<Item = &'a str>,
-{
+type BoxTokens<'a> = Box<dyn Iterator<Item = Token<'a>> + 'a>;
+
+/// Tokenizes a sequence of texts as if they were separated by hard separators,
+/// the texts can outlive the tokenizer, the tokens are bound to the shortest.
+pub struct SeqTokenizer<'a, T: ?Sized, I> {
+    tokenizer: &'a T,
     inner: I,
-    current: Option<Peekable<Tokenizer<'a>>>,
+    current: Option<Peekable<BoxTokens<'a>>>,
     word_offset: usize,
     char_offset: usize,
 }
 
-impl<'a, I> SeqTokenizer<'a, I>
+impl<'a, 's: 'a, T, I> SeqTokenizer<'a, T, I>
 where
-    I: Iterator<Item = &'a str>,
+    T: Tokenizer + ?Sized,
+    I: Iterator<Item = &'s str>,
 {
-    pub fn new(mut iter: I) -> SeqTokenizer<'a, I> {
-        let current = iter.next().map(|s| Tokenizer::new(s).peekable());
+    pub fn new(tokenizer: &'a T, mut iter: I) -> SeqTokenizer<'a, T, I> {
+        let current = iter.next().map(|s| tokenizer.tokenize(s).peekable());
         SeqTokenizer {
+            tokenizer,
             inner: iter,
             current,
             word_offset: 0,
@@ -194,9 +298,10 @@ where
     }
 }
 
-impl<'a, I> Iterator for SeqTokenizer<'a, I>
+impl<'a, 's: 'a, T, I> Iterator for SeqTokenizer<'a, T, I>
 where
-    I: Iterator<Item = &'a str>,
+    T: Tokenizer + ?Sized,
+    I: Iterator<Item = &'s str>,
 {
     type Item = Token<'a>;
 
@@ -226,7 +331,8 @@ where
                     None => {
                         // no more words in this text we must
                         // start tokenizing the next text
-                        self.current = self.inner.next().map(|s| Tokenizer::new(s).peekable());
+                        let tokenizer = self.tokenizer;
+                        self.current = self.inner.next().map(|s| tokenizer.tokenize(s).peekable());
                         self.next()
                     }
                 }
@@ -243,7 +349,7 @@ mod tests {
 
     #[test]
     fn easy() {
-        let mut tokenizer = Tokenizer::new("salut");
+        let mut tokenizer = DefaultTokens::new("salut");
 
         assert_eq!(
             tokenizer.next(),
@@ -255,7 +361,7 @@ mod tests {
         );
         assert_eq!(tokenizer.next(), None);
 
-        let mut tokenizer = Tokenizer::new("yo    ");
+        let mut tokenizer = DefaultTokens::new("yo    ");
 
         assert_eq!(
             tokenizer.next(),
@@ -270,7 +376,7 @@ mod tests {
 
     #[test]
     fn hard() {
-        let mut tokenizer = Tokenizer::new(" .? yo lolo. aïe (ouch)");
+        let mut tokenizer = DefaultTokens::new(" .? yo lolo. aïe (ouch)");
 
         assert_eq!(
             tokenizer.next(),
@@ -306,7 +412,7 @@ mod tests {
         );
         assert_eq!(tokenizer.next(), None);
 
-        let mut tokenizer = Tokenizer::new("yo ! lolo ? wtf - lol . aïe ,");
+        let mut tokenizer = DefaultTokens::new("yo ! lolo ? wtf - lol . aïe ,");
 
         assert_eq!(
             tokenizer.next(),
@@ -353,7 +459,7 @@ mod tests {
 
     #[test]
     fn hard_long_chars() {
-        let mut tokenizer = Tokenizer::new(" .? yo 😂. aïe");
+        let mut tokenizer = DefaultTokens::new(" .? yo 😂. aïe");
 
         assert_eq!(
             tokenizer.next(),
@@ -381,7 +487,7 @@ mod tests {
         );
         assert_eq!(tokenizer.next(), None);
 
-        let mut tokenizer = Tokenizer::new("yo ! lolo ? 😱 - lol . 😣 ,");
+        let mut tokenizer = DefaultTokens::new("yo ! lolo ? 😱 - lol . 😣 ,");
 
         assert_eq!(
             tokenizer.next(),
@@ -428,7 +534,7 @@ mod tests {
 
     #[test]
     fn hard_kanjis() {
-        let mut tokenizer = Tokenizer::new("\u{2ec4}lolilol\u{2ec7}");
+        let mut tokenizer = DefaultTokens::new("\u{2ec4}lolilol\u{2ec7}");
 
         assert_eq!(
             tokenizer.next(),
@@ -456,7 +562,7 @@ mod tests {
         );
         assert_eq!(tokenizer.next(), None);
 
-        let mut tokenizer = Tokenizer::new("\u{2ec4}\u{2ed3}\u{2ef2} lolilol - hello    \u{2ec7}");
+        let mut tokenizer = DefaultTokens::new("\u{2ec4}\u{2ed3}\u{2ef2} lolilol - hello    \u{2ec7}");
 
         assert_eq!(
             tokenizer.next(),
@@ -508,4 +614,72 @@ mod tests {
         );
         assert_eq!(tokenizer.next(), None);
     }
+
+    #[test]
+    fn custom_separators() {
+        let mut separators = CustomSeparators::default();
+        separators.separators.insert('#');
+        separators.non_separators.insert('.');
+        let tokenizer = DefaultTokenizer::with_separators(separators);
+
+        let words: Vec<_> = split_query_string(&tokenizer, "node.js#rust C++").collect();
+        assert_eq!(words, vec!["node.js", "rust", "C++"]);
+
+        let words: Vec<_> = split_query_string(&DefaultTokenizer::default(), "node.js#rust").collect();
+        assert_eq!(words, vec!["node", "js#rust"]);
+    }
+
+    struct WhitespaceTokenizer;
+
+    impl Tokenizer for WhitespaceTokenizer {
+        fn tokenize<'a>(&'a self, text: &'a str) -> Box<dyn Iterator<Item = Token<'a>> + 'a> {
+            let iter = text.split_whitespace().enumerate().map(move |(word_index, word)| {
+                let offset = word.as_ptr() as usize - text.as_ptr() as usize;
+                let char_index = text[..offset].chars().count();
+                Token { word, word_index, char_index }
+            });
+            Box::new(iter)
+        }
+    }
+
+    #[test]
+    fn custom_tokenizer() {
+        let words: Vec<_> = split_query_string(&WhitespaceTokenizer, "AB-1234/X blue").collect();
+        assert_eq!(words, vec!["AB-1234/X", "blue"]);
+
+        let words: Vec<_> = split_query_string(&DefaultTokenizer::default(), "AB-1234/X blue").collect();
+        assert_eq!(words, vec!["AB", "1234", "X", "blue"]);
+    }
+
+    #[test]
+    fn custom_seq_tokenizer() {
+        let texts = vec!["AB-1234/X blue", "red"];
+        let mut tokenizer = SeqTokenizer::new(&WhitespaceTokenizer, texts.into_iter());
+
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token {
+                word: "AB-1234/X",
+                word_index: 0,
+                char_index: 0
+            })
+        );
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token {
+                word: "blue",
+                word_index: 1,
+                char_index: 10
+            })
+        );
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token {
+                word: "red",
+                word_index: 9,
+                char_index: 18
+            })
+        );
+        assert_eq!(tokenizer.next(), None);
+    }
 }